@@ -0,0 +1,44 @@
+#![no_main]
+
+use {
+    arb::parse_witness,
+    arbitrary::Arbitrary,
+    bitcoin::{
+        blockdata::{
+            opcodes,
+            script,
+        },
+        Witness,
+    },
+    libfuzzer_sys::fuzz_target,
+};
+
+#[derive(Clone, Debug, Arbitrary)]
+enum Input {
+    /// Arbitrary witness elements
+    Witness(Vec<Vec<u8>>),
+    /// Arbitrary pushes inside an inscription envelope, which reach further
+    /// into the parser than arbitrary witnesses
+    Envelope(Vec<Vec<u8>>),
+}
+
+fuzz_target!(|input: Input| {
+    let witness = match input {
+        Input::Witness(elements) => Witness::from_vec(elements),
+        Input::Envelope(pushes) => {
+            let mut builder = script::Builder::new()
+                .push_opcode(opcodes::OP_FALSE)
+                .push_opcode(opcodes::all::OP_IF);
+
+            for push in pushes {
+                builder = builder.push_slice(&push);
+            }
+
+            let script = builder.push_opcode(opcodes::all::OP_ENDIF).into_script();
+
+            Witness::from_vec(vec![script.into_bytes(), Vec::new()])
+        },
+    };
+
+    parse_witness(&witness).ok();
+});
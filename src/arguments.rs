@@ -11,6 +11,7 @@ pub(crate) struct Arguments {
 
 impl Arguments {
     pub(crate) fn run(self) -> Result {
+        ProtocolRegistry::load(&self.options)?.install();
         self.subcommand.run(self.options)
     }
 }
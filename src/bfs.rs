@@ -0,0 +1,385 @@
+use super::*;
+
+/// What a `bfs` inscription does to the file tree of its `bid`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Action {
+    /// Write the inscription's content to the path, replacing any earlier file.
+    Put,
+    /// Remove the file at the path, or the directory and everything under it.
+    Rm,
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "put" => Ok(Self::Put),
+            "rm" => Ok(Self::Rm),
+            _ => bail!("invalid bfs action `{s}`"),
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        match self {
+            Self::Put => write!(f, "put"),
+            Self::Rm => write!(f, "rm"),
+        }
+    }
+}
+
+/// An absolute path in a `bfs` file tree, like `/docs/readme.txt`. Paths are
+/// `/` separated, and segments may not be empty, `.` or `..`.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct BfsPath(String);
+
+impl BfsPath {
+    pub(crate) fn root() -> Self {
+        Self("/".into())
+    }
+
+    pub(crate) fn is_root(&self) -> bool {
+        self.0 == "/"
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The directories containing this path, excluding the root, outermost
+    /// first.
+    pub(crate) fn ancestors(&self) -> Vec<BfsPath> {
+        self.0
+            .match_indices('/')
+            .skip(1)
+            .map(|(i, _)| Self(self.0[..i].into()))
+            .collect()
+    }
+
+    /// The directory containing this path, which is the root for the root.
+    pub(crate) fn parent(&self) -> Self {
+        self.ancestors().pop().unwrap_or_else(Self::root)
+    }
+
+    pub(crate) fn join(
+        &self,
+        name: &str,
+    ) -> Self {
+        if self.is_root() {
+            Self(format!("/{name}"))
+        } else {
+            Self(format!("{}/{name}", self.0))
+        }
+    }
+
+    /// The key of this path in the file tree of `bid`.
+    pub(crate) fn key(
+        &self,
+        bid: &Username,
+    ) -> String {
+        format!("{bid}{}", self.0)
+    }
+
+    /// The prefix shared by the keys of everything under this path in the file
+    /// tree of `bid`.
+    pub(crate) fn directory_key(
+        &self,
+        bid: &Username,
+    ) -> String {
+        if self.is_root() {
+            format!("{bid}/")
+        } else {
+            format!("{bid}{}/", self.0)
+        }
+    }
+}
+
+impl FromStr for BfsPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.starts_with('/') {
+            bail!("invalid bfs path `{s}`: must start with `/`");
+        }
+
+        if s == "/" {
+            return Ok(Self::root());
+        }
+
+        for segment in s[1..].split('/') {
+            if segment.is_empty() {
+                bail!("invalid bfs path `{s}`: empty path segment");
+            }
+
+            if segment == "." || segment == ".." {
+                bail!("invalid bfs path `{s}`: `.` and `..` segments are not allowed");
+            }
+
+            if segment.chars().any(char::is_control) {
+                bail!("invalid bfs path `{s}`: control characters are not allowed");
+            }
+        }
+
+        Ok(Self(s.into()))
+    }
+}
+
+impl Display for BfsPath {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A change to the file tree of a `bid`, made by a `bfs` inscription.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Operation {
+    pub(crate) action: Action,
+    pub(crate) bid: Username,
+    pub(crate) path: BfsPath,
+}
+
+impl Operation {
+    /// The operation made by `inscription`, if it is a `bfs` inscription.
+    pub(crate) fn from_inscription(inscription: &Inscription) -> Option<Result<Self>> {
+        let registry = ProtocolRegistry::current();
+        let spec = registry.get(inscription.protocol_id())?;
+
+        if spec.protocol != "bfs" {
+            return None;
+        }
+
+        let properties = match inscription.protocol_properties() {
+            Some(properties) => properties,
+            None => return Some(Err(anyhow!("bfs inscription has no properties"))),
+        };
+
+        Some(
+            serde_json::from_str::<serde_json::Value>(properties)
+                .context("protocol properties are not valid JSON")
+                .and_then(|properties| {
+                    let property = |name| {
+                        properties[name]
+                            .as_str()
+                            .ok_or_else(|| anyhow!("bfs inscription has no {name}"))
+                    };
+
+                    let operation = Self {
+                        action: property("action")?.parse()?,
+                        bid: property("bid")?.parse()?,
+                        path: property("path")?.parse()?,
+                    };
+
+                    if operation.path.is_root() {
+                        bail!(
+                            "bfs inscription may not {} the root directory",
+                            operation.action
+                        );
+                    }
+
+                    Ok(operation)
+                }),
+        )
+    }
+}
+
+/// An entry in a `bfs` directory listing, which is a file if it has an
+/// inscription, and a directory otherwise.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub(crate) struct DirectoryEntry {
+    pub(crate) name: String,
+    pub(crate) inscription_id: Option<InscriptionId>,
+}
+
+/// The immediate children of `directory`, given every file beneath it.
+pub(crate) fn list_directory(
+    directory: &BfsPath,
+    files: &[(BfsPath, InscriptionId)],
+) -> Vec<DirectoryEntry> {
+    let prefix = if directory.is_root() {
+        "/".to_string()
+    } else {
+        format!("{directory}/")
+    };
+
+    let mut entries = BTreeMap::new();
+
+    for (path, inscription_id) in files {
+        let Some(relative) = path.as_str().strip_prefix(&prefix) else {
+            continue;
+        };
+
+        match relative.split_once('/') {
+            Some((name, _)) => {
+                entries.entry(name.to_string()).or_insert(None);
+            },
+            None => {
+                entries.insert(relative.to_string(), Some(*inscription_id));
+            },
+        }
+    }
+
+    entries
+        .into_iter()
+        .map(|(name, inscription_id)| DirectoryEntry {
+            name,
+            inscription_id,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_paths() {
+        assert_eq!("/".parse::<BfsPath>().unwrap(), BfsPath::root());
+        assert_eq!(
+            "/docs/readme.txt".parse::<BfsPath>().unwrap().as_str(),
+            "/docs/readme.txt"
+        );
+        assert_eq!("/a b/c.d".parse::<BfsPath>().unwrap().as_str(), "/a b/c.d");
+    }
+
+    #[test]
+    fn invalid_paths() {
+        assert_eq!(
+            "docs".parse::<BfsPath>().unwrap_err().to_string(),
+            "invalid bfs path `docs`: must start with `/`"
+        );
+        assert_eq!(
+            "/docs/".parse::<BfsPath>().unwrap_err().to_string(),
+            "invalid bfs path `/docs/`: empty path segment"
+        );
+        assert_eq!(
+            "//docs".parse::<BfsPath>().unwrap_err().to_string(),
+            "invalid bfs path `//docs`: empty path segment"
+        );
+        assert_eq!(
+            "/docs/../etc".parse::<BfsPath>().unwrap_err().to_string(),
+            "invalid bfs path `/docs/../etc`: `.` and `..` segments are not allowed"
+        );
+        assert_eq!(
+            "/docs\n".parse::<BfsPath>().unwrap_err().to_string(),
+            "invalid bfs path `/docs\n`: control characters are not allowed"
+        );
+    }
+
+    #[test]
+    fn ancestors() {
+        assert_eq!(BfsPath::root().ancestors(), Vec::new());
+        assert_eq!(BfsPath::root().parent(), BfsPath::root());
+        assert_eq!(
+            "/a/b".parse::<BfsPath>().unwrap().parent(),
+            "/a".parse::<BfsPath>().unwrap()
+        );
+        assert_eq!("/a".parse::<BfsPath>().unwrap().ancestors(), Vec::new());
+        assert_eq!(
+            "/a/b/c".parse::<BfsPath>().unwrap().ancestors(),
+            vec![
+                "/a".parse::<BfsPath>().unwrap(),
+                "/a/b".parse::<BfsPath>().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let bid = "alice_1".parse::<Username>().unwrap();
+        let path = "/docs".parse::<BfsPath>().unwrap();
+        assert_eq!(path.key(&bid), "alice_1/docs");
+        assert_eq!(path.directory_key(&bid), "alice_1/docs/");
+        assert_eq!(BfsPath::root().directory_key(&bid), "alice_1/");
+        assert_eq!(BfsPath::root().join("docs"), path);
+        assert_eq!(path.join("a.txt").as_str(), "/docs/a.txt");
+    }
+
+    #[test]
+    fn operations_are_parsed_from_inscriptions() {
+        let wrapped = Inscription::wrapped(
+            "bfs-v1",
+            serde_json::json!({ "bid": "alice_1", "path": "/a.txt", "action": "put" }),
+            "text/plain",
+            b"hello",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Operation::from_inscription(&wrapped).unwrap().unwrap(),
+            Operation {
+                action: Action::Put,
+                bid: "alice_1".parse().unwrap(),
+                path: "/a.txt".parse().unwrap(),
+            }
+        );
+
+        assert!(Operation::from_inscription(&inscription("text/plain", "foo")).is_none());
+    }
+
+    #[test]
+    fn operations_on_root_are_invalid() {
+        let inscription = Inscription::wrapped(
+            "bfs-v1",
+            serde_json::json!({ "bid": "alice_1", "path": "/", "action": "rm" }),
+            "text/plain",
+            b"",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Operation::from_inscription(&inscription)
+                .unwrap()
+                .unwrap_err()
+                .to_string(),
+            "bfs inscription may not rm the root directory"
+        );
+    }
+
+    #[test]
+    fn directory_listing() {
+        let files = [
+            ("/a.txt", txid(1)),
+            ("/docs/b.txt", txid(2)),
+            ("/docs/c/d.txt", txid(3)),
+        ]
+        .into_iter()
+        .map(|(path, txid)| (path.parse().unwrap(), txid.into()))
+        .collect::<Vec<(BfsPath, InscriptionId)>>();
+
+        assert_eq!(
+            list_directory(&BfsPath::root(), &files),
+            vec![
+                DirectoryEntry {
+                    name: "a.txt".into(),
+                    inscription_id: Some(txid(1).into()),
+                },
+                DirectoryEntry {
+                    name: "docs".into(),
+                    inscription_id: None,
+                },
+            ]
+        );
+
+        assert_eq!(
+            list_directory(&"/docs".parse().unwrap(), &files),
+            vec![
+                DirectoryEntry {
+                    name: "b.txt".into(),
+                    inscription_id: Some(txid(2).into()),
+                },
+                DirectoryEntry {
+                    name: "c".into(),
+                    inscription_id: None,
+                },
+            ]
+        );
+    }
+}
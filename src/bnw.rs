@@ -0,0 +1,192 @@
+use super::*;
+
+/// The name of a `bnw` wall, which is 1 to 32 characters of `a-z`, `0-9`, `_`
+/// and `-`.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct WallName(String);
+
+impl WallName {
+    pub(crate) const MAX_LENGTH: usize = 32;
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The key of this wall among the walls of `bid`.
+    pub(crate) fn key(
+        &self,
+        bid: &Username,
+    ) -> String {
+        format!("{bid}/{}", self.0)
+    }
+}
+
+impl FromStr for WallName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            bail!("invalid wall name: must not be empty");
+        }
+
+        if s.len() > Self::MAX_LENGTH {
+            bail!(
+                "invalid wall name `{s}`: longer than {} characters",
+                Self::MAX_LENGTH
+            );
+        }
+
+        if !s
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            bail!("invalid wall name `{s}`: only `a-z`, `0-9`, `_` and `-` are allowed");
+        }
+
+        Ok(Self(s.into()))
+    }
+}
+
+impl Display for WallName {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A wall showcasing inscriptions held by the owner of a `bid`, in order.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Wall {
+    pub(crate) bid: Username,
+    pub(crate) name: WallName,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+}
+
+impl Wall {
+    pub(crate) const MAX_INSCRIPTIONS: usize = 100;
+
+    /// The wall declared by `inscription`, if it is a `bnw` inscription.
+    pub(crate) fn from_inscription(inscription: &Inscription) -> Option<Result<Self>> {
+        let registry = ProtocolRegistry::current();
+        let spec = registry.get(inscription.protocol_id())?;
+
+        if spec.protocol != "bnw" {
+            return None;
+        }
+
+        let properties = match inscription.protocol_properties() {
+            Some(properties) => properties,
+            None => return Some(Err(anyhow!("bnw inscription has no properties"))),
+        };
+
+        Some(
+            serde_json::from_str::<serde_json::Value>(properties)
+                .context("protocol properties are not valid JSON")
+                .and_then(|properties| {
+                    let property = |name| {
+                        properties[name]
+                            .as_str()
+                            .ok_or_else(|| anyhow!("bnw inscription has no {name}"))
+                    };
+
+                    let inscriptions = properties["inscriptions"]
+                        .as_array()
+                        .ok_or_else(|| anyhow!("bnw inscription has no inscriptions"))?;
+
+                    if inscriptions.len() > Self::MAX_INSCRIPTIONS {
+                        bail!(
+                            "bnw wall lists more than {} inscriptions",
+                            Self::MAX_INSCRIPTIONS
+                        );
+                    }
+
+                    Ok(Self {
+                        bid: property("bid")?.parse()?,
+                        name: property("wall")?.parse()?,
+                        inscriptions: inscriptions
+                            .iter()
+                            .map(|inscription_id| {
+                                let inscription_id = inscription_id.as_str().ok_or_else(|| {
+                                    anyhow!("bnw inscription ids must be strings")
+                                })?;
+
+                                inscription_id.parse().map_err(|err| {
+                                    anyhow!("invalid inscription id `{inscription_id}`: {err}")
+                                })
+                            })
+                            .collect::<Result<Vec<InscriptionId>>>()?,
+                    })
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wall(properties: serde_json::Value) -> Result<Wall> {
+        Wall::from_inscription(
+            &Inscription::wrapped("bnw-v1", properties, "text/plain;charset=utf-8", b"").unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn wall_names() {
+        assert_eq!(
+            "my-wall_1".parse::<WallName>().unwrap().as_str(),
+            "my-wall_1"
+        );
+        assert_eq!(
+            "".parse::<WallName>().unwrap_err().to_string(),
+            "invalid wall name: must not be empty"
+        );
+        assert_eq!(
+            "My Wall".parse::<WallName>().unwrap_err().to_string(),
+            "invalid wall name `My Wall`: only `a-z`, `0-9`, `_` and `-` are allowed"
+        );
+        assert_eq!(
+            "a".repeat(33).parse::<WallName>().unwrap_err().to_string(),
+            format!(
+                "invalid wall name `{}`: longer than 32 characters",
+                "a".repeat(33)
+            )
+        );
+    }
+
+    #[test]
+    fn walls_are_parsed_from_inscriptions() {
+        assert_eq!(
+            wall(serde_json::json!({
+                "bid": "alice_1",
+                "wall": "main",
+                "inscriptions": [inscription_id(1), inscription_id(2)],
+            }))
+            .unwrap(),
+            Wall {
+                bid: "alice_1".parse().unwrap(),
+                name: "main".parse().unwrap(),
+                inscriptions: vec![inscription_id(1), inscription_id(2)],
+            }
+        );
+
+        assert!(Wall::from_inscription(&inscription("text/plain", "foo")).is_none());
+    }
+
+    #[test]
+    fn invalid_inscription_ids_are_errors() {
+        assert_eq!(
+            wall(serde_json::json!({
+                "bid": "alice_1",
+                "wall": "main",
+                "inscriptions": ["foo"],
+            }))
+            .unwrap_err()
+            .to_string(),
+            "invalid inscription id `foo`: invalid length: 3"
+        );
+    }
+}
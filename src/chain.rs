@@ -50,6 +50,22 @@ impl Chain {
         }
     }
 
+    /// The number of blocks a `bid` username registration or renewal lasts.
+    pub(crate) fn bid_renewal_period(self) -> u64 {
+        match self {
+            Self::Regtest => 100,
+            Self::Mainnet | Self::Signet | Self::Testnet => 52_500,
+        }
+    }
+
+    /// The height at which sunrise reservations of `bid` usernames end.
+    pub(crate) fn bid_sunrise_end_height(self) -> u64 {
+        match self {
+            Self::Mainnet => 840_000,
+            Self::Regtest | Self::Signet | Self::Testnet => 0,
+        }
+    }
+
     pub(crate) fn genesis_block(self) -> Block {
         bitcoin::blockdata::constants::genesis_block(self.network())
     }
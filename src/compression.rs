@@ -0,0 +1,415 @@
+use {
+    super::*,
+    brotli::{
+        CompressorWriter,
+        Decompressor,
+    },
+    clap::ValueEnum,
+    flate2::{
+        read::{
+            DeflateDecoder,
+            GzDecoder,
+        },
+        write::{
+            DeflateEncoder,
+            GzEncoder,
+        },
+    },
+    std::io::{
+        Cursor,
+        Read,
+        Write,
+    },
+};
+
+/// The most compressed input decompressed, to prevent a DoS vector.
+const MAX_INPUT_SIZE: usize = 10_000_000;
+
+/// The most decompressed output produced, to prevent decompression bombs.
+pub(crate) const MAX_DECOMPRESSED_SIZE: usize = 50_000_000;
+
+const ZSTD_LEVEL: i32 = 19;
+
+/// Why the compressed content of an inscription couldn't be decompressed.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum CompressionError {
+    DictionaryUnsupported(Codec),
+    InvalidContent { codec: Codec, message: String },
+    TooLarge(Codec),
+    UnknownCodec(String),
+}
+
+impl Display for CompressionError {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        match self {
+            Self::DictionaryUnsupported(codec) => {
+                write!(
+                    f,
+                    "compression codec `{codec}` does not support dictionaries"
+                )
+            },
+            Self::InvalidContent { codec, message } => {
+                write!(f, "invalid `{codec}` compressed content: {message}")
+            },
+            Self::TooLarge(codec) => write!(
+                f,
+                "`{codec}` compressed content decompresses to more than {MAX_DECOMPRESSED_SIZE} bytes"
+            ),
+            Self::UnknownCodec(encoding) => write!(f, "unknown compression codec `{encoding}`"),
+        }
+    }
+}
+
+impl std::error::Error for CompressionError {}
+
+/// A codec used to compress the content of wrapped inscriptions. Envelope
+/// expansions record it as its encoding, its name followed by ` base64`.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) enum Codec {
+    Brotli,
+    Deflate,
+    Gzip,
+    Zstd,
+}
+
+impl Display for Codec {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            Self::Brotli => "br",
+            Self::Deflate => "deflate",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        })
+    }
+}
+
+impl Codec {
+    pub(crate) const ALL: [Codec; 4] = [Self::Brotli, Self::Deflate, Self::Gzip, Self::Zstd];
+
+    pub(crate) fn encoding(self) -> String {
+        format!("{self} base64")
+    }
+
+    /// The codec of a JSON envelope expansion's `compression` encoding.
+    pub(crate) fn from_encoding(encoding: &str) -> Result<Self, CompressionError> {
+        Self::ALL
+            .into_iter()
+            .find(|codec| codec.encoding() == encoding)
+            .ok_or_else(|| CompressionError::UnknownCodec(encoding.into()))
+    }
+
+    /// The codec named `name`, as in a binary envelope's content encoding tag.
+    pub(crate) fn from_name(name: &str) -> Result<Self, CompressionError> {
+        Self::ALL
+            .into_iter()
+            .find(|codec| codec.to_string() == name)
+            .ok_or_else(|| CompressionError::UnknownCodec(name.into()))
+    }
+
+    pub(crate) fn compress(
+        self,
+        content: &[u8],
+        dictionary: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        if dictionary.is_some() && self != Self::Zstd {
+            return Err(CompressionError::DictionaryUnsupported(self).into());
+        }
+
+        let mut compressed = Vec::new();
+
+        match self {
+            Self::Brotli => {
+                let mut compressor = CompressorWriter::new(&mut compressed, 4096, 11, 22);
+                compressor.write_all(content)?;
+            },
+            Self::Deflate => {
+                let mut encoder = DeflateEncoder::new(&mut compressed, flate2::Compression::best());
+                encoder.write_all(content)?;
+                encoder.finish()?;
+            },
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(&mut compressed, flate2::Compression::best());
+                encoder.write_all(content)?;
+                encoder.finish()?;
+            },
+            Self::Zstd => {
+                compressed = zstd::bulk::Compressor::with_dictionary(
+                    ZSTD_LEVEL,
+                    dictionary.unwrap_or_default(),
+                )?
+                .compress(content)?;
+            },
+        }
+
+        Ok(compressed)
+    }
+
+    /// Decompress `content`, reading at most 10MB of input, and producing at
+    /// most 50MB of output.
+    pub(crate) fn decompress(
+        self,
+        content: &[u8],
+        dictionary: Option<&[u8]>,
+    ) -> Result<Vec<u8>, CompressionError> {
+        if dictionary.is_some() && self != Self::Zstd {
+            return Err(CompressionError::DictionaryUnsupported(self));
+        }
+
+        let input = LimitedReader::new(Cursor::new(content), MAX_INPUT_SIZE);
+
+        let mut decompressed = Vec::new();
+
+        let limit = MAX_DECOMPRESSED_SIZE as u64 + 1;
+
+        let result = match self {
+            Self::Brotli => Decompressor::new(input, 4096)
+                .take(limit)
+                .read_to_end(&mut decompressed),
+            Self::Deflate => DeflateDecoder::new(input)
+                .take(limit)
+                .read_to_end(&mut decompressed),
+            Self::Gzip => GzDecoder::new(input)
+                .take(limit)
+                .read_to_end(&mut decompressed),
+            Self::Zstd => zstd::stream::read::Decoder::with_dictionary(
+                io::BufReader::new(input),
+                dictionary.unwrap_or_default(),
+            )
+            .and_then(|decoder| decoder.take(limit).read_to_end(&mut decompressed)),
+        };
+
+        result.map_err(|err| CompressionError::InvalidContent {
+            codec: self,
+            message: err.to_string(),
+        })?;
+
+        if decompressed.len() > MAX_DECOMPRESSED_SIZE {
+            return Err(CompressionError::TooLarge(self));
+        }
+
+        Ok(decompressed)
+    }
+}
+
+/// The compressed content of a wrapped inscription that wasn't decompressed
+/// when it was parsed, either because it was compressed with a dictionary
+/// inscription, or because it can't be.
+#[derive(Debug, PartialEq)]
+pub(crate) struct CompressedContent {
+    pub(crate) codec: Result<Codec, CompressionError>,
+    pub(crate) content: Vec<u8>,
+    pub(crate) content_type: Option<String>,
+    pub(crate) dictionary: Option<InscriptionId>,
+}
+
+impl CompressedContent {
+    pub(crate) fn decompress(
+        &self,
+        dictionary: Option<&[u8]>,
+    ) -> Result<Vec<u8>, CompressionError> {
+        self.codec.clone()?.decompress(&self.content, dictionary)
+    }
+}
+
+/// How to compress the content of an inscription: with a particular codec, or
+/// with whichever codec produces the smallest result.
+#[derive(Debug, PartialEq, Copy, Clone, ValueEnum)]
+pub(crate) enum Compression {
+    Auto,
+    #[clap(alias("br"))]
+    Brotli,
+    Deflate,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// The codec producing the smallest compressed `content`, along with it.
+    pub(crate) fn compress(
+        self,
+        content: &[u8],
+        dictionary: Option<&[u8]>,
+    ) -> Result<(Codec, Vec<u8>)> {
+        let codecs = match self {
+            Self::Auto => Codec::ALL
+                .into_iter()
+                .filter(|codec| dictionary.is_none() || *codec == Codec::Zstd)
+                .collect(),
+            Self::Brotli => vec![Codec::Brotli],
+            Self::Deflate => vec![Codec::Deflate],
+            Self::Gzip => vec![Codec::Gzip],
+            Self::Zstd => vec![Codec::Zstd],
+        };
+
+        let mut smallest: Option<(Codec, Vec<u8>)> = None;
+
+        for codec in codecs {
+            let compressed = codec.compress(content, dictionary)?;
+            if smallest
+                .as_ref()
+                .map_or(true, |(_, smallest)| compressed.len() < smallest.len())
+            {
+                smallest = Some((codec, compressed));
+            }
+        }
+
+        Ok(smallest.unwrap())
+    }
+}
+
+struct LimitedReader<R> {
+    inner: io::BufReader<R>,
+    limit: usize,
+    total_read: usize,
+}
+
+impl<R: Read> LimitedReader<R> {
+    fn new(
+        inner: R,
+        limit: usize,
+    ) -> Self {
+        Self {
+            inner: io::BufReader::new(inner),
+            limit,
+            total_read: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(
+        &mut self,
+        buf: &mut [u8],
+    ) -> io::Result<usize> {
+        if self.total_read >= self.limit {
+            return Ok(0);
+        }
+        let remaining = self.limit - self.total_read;
+        let to_read = buf.len().min(remaining);
+        let n = self.inner.read(&mut buf[..to_read])?;
+        self.total_read += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &[u8] = b"HELLO HELLO HELLO HELLO HELLO HELLO HELLO HELLO HELLO HELLO";
+
+    #[test]
+    fn codecs_round_trip() {
+        for codec in Codec::ALL {
+            assert_eq!(
+                codec
+                    .decompress(&codec.compress(CONTENT, None).unwrap(), None)
+                    .unwrap(),
+                CONTENT,
+                "{codec}"
+            );
+        }
+    }
+
+    #[test]
+    fn encodings_round_trip() {
+        assert_eq!(Codec::Brotli.encoding(), "br base64");
+        for codec in Codec::ALL {
+            assert_eq!(Codec::from_encoding(&codec.encoding()), Ok(codec));
+            assert_eq!(Codec::from_name(&codec.to_string()), Ok(codec));
+        }
+    }
+
+    #[test]
+    fn unknown_codecs_are_an_error() {
+        assert_eq!(
+            Codec::from_encoding("lzma base64"),
+            Err(CompressionError::UnknownCodec("lzma base64".into()))
+        );
+        assert_eq!(
+            CompressionError::UnknownCodec("lzma base64".into()).to_string(),
+            "unknown compression codec `lzma base64`"
+        );
+    }
+
+    #[test]
+    fn invalid_content_is_an_error() {
+        assert_eq!(
+            Codec::Gzip
+                .decompress(b"foo", None)
+                .unwrap_err()
+                .to_string(),
+            "invalid `gzip` compressed content: failed to fill whole buffer"
+        );
+    }
+
+    #[test]
+    fn decompression_bombs_are_an_error() {
+        let bomb = Codec::Gzip
+            .compress(&vec![0; MAX_DECOMPRESSED_SIZE + 1], None)
+            .unwrap();
+
+        assert_eq!(
+            Codec::Gzip.decompress(&bomb, None),
+            Err(CompressionError::TooLarge(Codec::Gzip))
+        );
+    }
+
+    #[test]
+    fn zstd_dictionaries_are_required_to_decompress() {
+        let dictionary = b"The quick brown fox jumps over the lazy dog. Sphinx of black quartz, \
+        judge my vow.";
+        let content = &dictionary[..44];
+
+        let compressed = Codec::Zstd.compress(content, Some(dictionary)).unwrap();
+
+        assert_eq!(
+            Codec::Zstd
+                .decompress(&compressed, Some(dictionary))
+                .unwrap(),
+            content
+        );
+        assert!(compressed.len() < Codec::Zstd.compress(content, None).unwrap().len());
+        assert!(Codec::Zstd.decompress(&compressed, None).is_err());
+    }
+
+    #[test]
+    fn only_zstd_supports_dictionaries() {
+        assert_eq!(
+            Codec::Gzip.decompress(b"", Some(b"foo")),
+            Err(CompressionError::DictionaryUnsupported(Codec::Gzip))
+        );
+        assert_eq!(
+            Codec::Brotli
+                .compress(CONTENT, Some(b"foo"))
+                .unwrap_err()
+                .to_string(),
+            "compression codec `br` does not support dictionaries"
+        );
+    }
+
+    #[test]
+    fn auto_picks_the_smallest_result() {
+        let (codec, compressed) = Compression::Auto.compress(CONTENT, None).unwrap();
+
+        for other in Codec::ALL {
+            assert!(compressed.len() <= other.compress(CONTENT, None).unwrap().len());
+        }
+
+        assert_eq!(codec.decompress(&compressed, None).unwrap(), CONTENT);
+
+        assert_eq!(
+            Compression::Auto
+                .compress(CONTENT, Some(b"HELLO"))
+                .unwrap()
+                .0,
+            Codec::Zstd
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use {
     self::{
         entry::{
+            BidEntry,
+            BidEntryValue,
             BlockHashValue,
             Entry,
             InscriptionEntry,
@@ -10,6 +12,9 @@ use {
             SatPointValue,
             SatRange,
         },
+        mempool::Mempool,
+        reorg::*,
+        transfer::TransferValue,
         updater::Updater,
     },
     super::*,
@@ -36,19 +41,42 @@ use {
     },
     std::{
         collections::HashMap,
+        ops::Range,
         sync::atomic::{
             self,
             AtomicBool,
         },
     },
+    tokio::sync::broadcast,
 };
 
 mod entry;
+mod event;
 mod fetcher;
+mod mempool;
+mod query;
+mod reorg;
 mod rtx;
+mod transfer;
 mod updater;
 
-const SCHEMA_VERSION: u64 = 3;
+pub(crate) use self::{
+    event::Event,
+    mempool::{
+        Pending,
+        PendingKind,
+    },
+    query::{
+        Cursor,
+        InscriptionQuery,
+    },
+    transfer::Transfer,
+};
+
+const SCHEMA_VERSION: u64 = 17;
+
+/// How many events subscribers may fall behind by before they miss events.
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
 
 macro_rules! define_table {
     ($name:ident, $key:ty, $value:ty) => {
@@ -56,29 +84,58 @@ macro_rules! define_table {
     };
 }
 
+define_table! { BFS_PATH_TO_INSCRIPTION_ID, &str, &InscriptionIdValue }
+define_table! { BID_NAME_TO_INSCRIPTION_ID, &str, &InscriptionIdValue }
+define_table! { BNW_WALL_TO_INSCRIPTION_ID, &str, &InscriptionIdValue }
+define_table! { CHILD_TO_PARENT, &InscriptionIdValue, &InscriptionIdValue }
+define_table! { CONTENT_HASH_TO_INSCRIPTION_ID, &str, &InscriptionIdValue }
+define_table! { CONTENT_TYPE_TO_INSCRIPTION_NUMBER, (&str, u64), () }
+define_table! { FEE_TO_INSCRIPTION_NUMBER, (u64, u64), () }
 define_table! { HEIGHT_TO_BLOCK_HASH, u64, &BlockHashValue }
+define_table! { HEIGHT_TO_EVENTS, u64, &[u8] }
+define_table! { INSCRIPTION_ID_TO_BID_ENTRY, &InscriptionIdValue, BidEntryValue }
+define_table! { INSCRIPTION_ID_TO_BNW_SATPOINTS, &InscriptionIdValue, &[u8] }
 define_table! { INSCRIPTION_ID_TO_INSCRIPTION_ENTRY, &InscriptionIdValue, InscriptionEntryValue }
+define_table! { INSCRIPTION_ID_TO_PROTOCOL_ID, &InscriptionIdValue, &str }
 define_table! { INSCRIPTION_ID_TO_SATPOINT, &InscriptionIdValue, &SatPointValue }
+define_table! { INSCRIPTION_ID_TO_TRANSFERS, (&InscriptionIdValue, u64), &TransferValue }
+define_table! { INSCRIPTION_ID_TO_VALIDATION_ERROR, &InscriptionIdValue, &str }
 define_table! { INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
+define_table! { MEDIA_TO_INSCRIPTION_NUMBER, (&str, u64), () }
 define_table! { OUTPOINT_TO_SAT_RANGES, &OutPointValue, &[u8] }
+define_table! { OUTPOINT_TO_SCRIPT_PUBKEY, &OutPointValue, &[u8] }
 define_table! { OUTPOINT_TO_VALUE, &OutPointValue, u64}
+define_table! { PARENT_TO_CHILDREN, (&InscriptionIdValue, &InscriptionIdValue), () }
+define_table! { PROTOCOL_ID_TO_INSCRIPTION_NUMBER, (&str, u64), () }
+define_table! { PUB_CONTENT_TYPE_TO_INSCRIPTION_NUMBER, (&str, u64), () }
+define_table! { PUB_FEE_TO_INSCRIPTION_NUMBER, (u64, u64), () }
+define_table! { PUB_INSCRIPTION_ID_TO_INSCRIPTION_ENTRY, &InscriptionIdValue, InscriptionEntryValue }
+define_table! { PUB_INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
+define_table! { PUB_MEDIA_TO_INSCRIPTION_NUMBER, (&str, u64), () }
+define_table! { PUB_PROTOCOL_ID_TO_INSCRIPTION_NUMBER, (&str, u64), () }
 define_table! { SATPOINT_TO_INSCRIPTION_ID, &SatPointValue, &InscriptionIdValue }
 define_table! { SAT_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
+define_table! { SCRIPT_PUBKEY_TO_OUTPOINT, (&[u8], &OutPointValue), () }
 define_table! { STATISTIC_TO_COUNT, u64, u64 }
 define_table! { WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP, u64, u128 }
 
 pub(crate) struct Index {
     auth: Auth,
+    chain: Chain,
     client: Client,
     database: Database,
+    events: broadcast::Sender<Event>,
     path: PathBuf,
     first_inscription_height: u64,
     genesis_block_coinbase_transaction: Transaction,
     genesis_block_coinbase_txid: Txid,
     height_limit: Option<u64>,
+    mempool: Mutex<Mempool>,
+    pending: Mutex<Option<BTreeMap<InscriptionId, Pending>>>,
     reorged: AtomicBool,
     rpc_url: String,
+    savepoints: Savepoints,
 }
 
 #[derive(Debug, PartialEq)]
@@ -95,6 +152,7 @@ pub(crate) enum Statistic {
     LostSats = 2,
     OutputsTraversed = 3,
     SatRanges = 4,
+    ProtocolsDigest = 5,
 }
 
 impl Statistic {
@@ -202,6 +260,24 @@ impl Index {
           }
         }
 
+                let protocols_digest = database
+                    .begin_read()?
+                    .open_table(STATISTIC_TO_COUNT)?
+                    .get(&Statistic::ProtocolsDigest.key())?
+                    .map(|x| x.value());
+
+                let loaded_protocols_digest = ProtocolRegistry::current().digest();
+
+                if protocols_digest != Some(loaded_protocols_digest) {
+                    bail!(
+                        "index at `{}` was built with different protocol specs, which change how inscriptions are indexed, consider rebuilding the index, or loading the same specs from the data dir and `--protocols-dir`: index protocols digest {}, loaded protocols digest {loaded_protocols_digest:016x}",
+                        path.display(),
+                        protocols_digest
+                            .map(|digest| format!("{digest:016x}"))
+                            .unwrap_or_else(|| "none".into()),
+                    );
+                }
+
                 database
             },
             Err(redb::Error::Io(error)) if error.kind() == io::ErrorKind::NotFound => {
@@ -223,11 +299,33 @@ impl Index {
                     tx
                 };
 
+                tx.open_table(BFS_PATH_TO_INSCRIPTION_ID)?;
+                tx.open_table(BID_NAME_TO_INSCRIPTION_ID)?;
+                tx.open_table(BNW_WALL_TO_INSCRIPTION_ID)?;
+                tx.open_table(CHILD_TO_PARENT)?;
+                tx.open_table(CONTENT_HASH_TO_INSCRIPTION_ID)?;
+                tx.open_table(CONTENT_TYPE_TO_INSCRIPTION_NUMBER)?;
+                tx.open_table(FEE_TO_INSCRIPTION_NUMBER)?;
                 tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
+                tx.open_table(HEIGHT_TO_EVENTS)?;
+                tx.open_table(INSCRIPTION_ID_TO_BID_ENTRY)?;
+                tx.open_table(INSCRIPTION_ID_TO_BNW_SATPOINTS)?;
                 tx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+                tx.open_table(INSCRIPTION_ID_TO_PROTOCOL_ID)?;
                 tx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+                tx.open_table(INSCRIPTION_ID_TO_TRANSFERS)?;
+                tx.open_table(INSCRIPTION_ID_TO_VALIDATION_ERROR)?;
                 tx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+                tx.open_table(MEDIA_TO_INSCRIPTION_NUMBER)?;
                 tx.open_table(OUTPOINT_TO_VALUE)?;
+                tx.open_table(PARENT_TO_CHILDREN)?;
+                tx.open_table(PROTOCOL_ID_TO_INSCRIPTION_NUMBER)?;
+                tx.open_table(PUB_CONTENT_TYPE_TO_INSCRIPTION_NUMBER)?;
+                tx.open_table(PUB_FEE_TO_INSCRIPTION_NUMBER)?;
+                tx.open_table(PUB_INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+                tx.open_table(PUB_INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+                tx.open_table(PUB_MEDIA_TO_INSCRIPTION_NUMBER)?;
+                tx.open_table(PUB_PROTOCOL_ID_TO_INSCRIPTION_NUMBER)?;
                 tx.open_table(SATPOINT_TO_INSCRIPTION_ID)?;
                 tx.open_table(SAT_TO_INSCRIPTION_ID)?;
                 tx.open_table(SAT_TO_SATPOINT)?;
@@ -236,11 +334,21 @@ impl Index {
                 tx.open_table(STATISTIC_TO_COUNT)?
                     .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
 
+                tx.open_table(STATISTIC_TO_COUNT)?.insert(
+                    &Statistic::ProtocolsDigest.key(),
+                    &ProtocolRegistry::current().digest(),
+                )?;
+
                 if options.index_sats {
                     tx.open_table(OUTPOINT_TO_SAT_RANGES)?
                         .insert(&OutPoint::null().store(), [].as_slice())?;
                 }
 
+                if options.index_addresses {
+                    tx.open_table(OUTPOINT_TO_SCRIPT_PUBKEY)?;
+                    tx.open_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+                }
+
                 tx.commit()?;
 
                 database
@@ -254,14 +362,19 @@ impl Index {
         Ok(Self {
             genesis_block_coinbase_txid: genesis_block_coinbase_transaction.txid(),
             auth,
+            chain: options.chain(),
             client,
             database,
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
             path,
             first_inscription_height: options.first_inscription_height(),
             genesis_block_coinbase_transaction,
             height_limit: options.height_limit,
+            mempool: Mutex::new(Mempool::default()),
+            pending: Mutex::new(None),
             reorged: AtomicBool::new(false),
             rpc_url,
+            savepoints: Savepoints::default(),
         })
     }
 
@@ -336,6 +449,14 @@ impl Index {
         }
     }
 
+    pub(crate) fn has_address_index(&self) -> Result<bool> {
+        match self.begin_read()?.0.open_table(SCRIPT_PUBKEY_TO_OUTPOINT) {
+            Ok(_) => Ok(true),
+            Err(redb::Error::TableDoesNotExist(_)) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     fn require_sat_index(
         &self,
         feature: &str,
@@ -399,7 +520,71 @@ impl Index {
     }
 
     pub(crate) fn update(&self) -> Result {
-        Updater::update(self)
+        loop {
+            match Updater::update(self) {
+                Ok(()) => return Ok(()),
+                Err(error) => match error.downcast_ref() {
+                    Some(&ReorgError::Recoverable { height, depth }) => {
+                        let height = Reorg::handle_reorg(self, height, depth)?;
+                        self.events.send(Event::Reorged { height }).ok();
+                    },
+                    Some(ReorgError::Unrecoverable) => {
+                        self.reorged.store(true, atomic::Ordering::Relaxed);
+                        return Err(error);
+                    },
+                    None => return Err(error),
+                },
+            }
+        }
+    }
+
+    /// The height of the oldest savepoint, which is as far back as a reorg
+    /// can roll the index.
+    pub(crate) fn oldest_savepoint(&self) -> Option<u64> {
+        self.savepoints.oldest()
+    }
+
+    /// Subscribe to the events of blocks as they're indexed.
+    pub(crate) fn subscribe_events(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+
+    /// The events of the blocks indexed at `heights`.
+    pub(crate) fn get_events(
+        &self,
+        heights: Range<u64>,
+    ) -> Result<Vec<Event>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(HEIGHT_TO_EVENTS)?
+            .range(heights)?
+            .flat_map(|(height, events)| Event::load_all(height.value(), events.value()))
+            .collect())
+    }
+
+    /// Rescan bitcoind's mempool for pending inscriptions and transfers.
+    pub(crate) fn update_mempool(&self) -> Result {
+        let pending = self.mempool.lock().unwrap().scan(self)?;
+        *self.pending.lock().unwrap() = Some(pending);
+        Ok(())
+    }
+
+    /// The pending inscriptions and transfers found by the last mempool scan,
+    /// or `None` if the mempool isn't being tracked.
+    pub(crate) fn get_pending(&self) -> Option<BTreeMap<InscriptionId, Pending>> {
+        self.pending.lock().unwrap().clone()
+    }
+
+    pub(crate) fn get_pending_by_id(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Option<Pending> {
+        self.pending
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|pending| pending.get(&inscription_id).copied())
     }
 
     pub(crate) fn is_reorged(&self) -> bool {
@@ -581,23 +766,320 @@ impl Index {
             .map(|satpoint| Entry::load(*satpoint.value())))
     }
 
-    pub(crate) fn get_inscription_by_id(
+    pub(crate) fn get_inscription_protocol_id(
         &self,
         inscription_id: InscriptionId,
-    ) -> Result<Option<Inscription>> {
-        if self
+    ) -> Result<Option<String>> {
+        Ok(self
             .database
             .begin_read()?
-            .open_table(INSCRIPTION_ID_TO_SATPOINT)?
+            .open_table(INSCRIPTION_ID_TO_PROTOCOL_ID)?
+            .get(&inscription_id.store())?
+            .map(|protocol_id| protocol_id.value().to_string()))
+    }
+
+    pub(crate) fn get_inscription_validation_error(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<Option<String>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(INSCRIPTION_ID_TO_VALIDATION_ERROR)?
+            .get(&inscription_id.store())?
+            .map(|error| error.value().to_string()))
+    }
+
+    pub(crate) fn get_bid_registration(
+        &self,
+        username: &Username,
+    ) -> Result<Option<(InscriptionId, BidEntry)>> {
+        let rtx = self.database.begin_read()?;
+
+        let Some(inscription_id) = rtx
+            .open_table(BID_NAME_TO_INSCRIPTION_ID)?
+            .get(username.as_str())?
+            .map(|inscription_id| InscriptionId::load(*inscription_id.value()))
+        else {
+            return Ok(None);
+        };
+
+        let entry = rtx
+            .open_table(INSCRIPTION_ID_TO_BID_ENTRY)?
             .get(&inscription_id.store())?
-            .is_none()
+            .map(|entry| BidEntry::load(entry.value()));
+
+        Ok(entry.map(|entry| (inscription_id, entry)))
+    }
+
+    pub(crate) fn get_bfs_file(
+        &self,
+        bid: &Username,
+        path: &BfsPath,
+    ) -> Result<Option<InscriptionId>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(BFS_PATH_TO_INSCRIPTION_ID)?
+            .get(path.key(bid).as_str())?
+            .map(|inscription_id| InscriptionId::load(*inscription_id.value())))
+    }
+
+    /// Every file under `directory` in the file tree of `bid`, in path order.
+    pub(crate) fn get_bfs_files(
+        &self,
+        bid: &Username,
+        directory: &BfsPath,
+    ) -> Result<Vec<(BfsPath, InscriptionId)>> {
+        let prefix = directory.directory_key(bid);
+
+        let mut files = Vec::new();
+
+        for (key, inscription_id) in self
+            .database
+            .begin_read()?
+            .open_table(BFS_PATH_TO_INSCRIPTION_ID)?
+            .range::<&str>(prefix.as_str()..)?
         {
+            let key = key.value();
+
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            files.push((
+                key[bid.as_str().len()..].parse()?,
+                InscriptionId::load(*inscription_id.value()),
+            ));
+        }
+
+        Ok(files)
+    }
+
+    /// The inscriptions declaring the walls of `bid`, in name order.
+    pub(crate) fn get_bnw_walls(
+        &self,
+        bid: &Username,
+    ) -> Result<Vec<InscriptionId>> {
+        let prefix = format!("{bid}/");
+
+        let mut walls = Vec::new();
+
+        for (key, inscription_id) in self
+            .database
+            .begin_read()?
+            .open_table(BNW_WALL_TO_INSCRIPTION_ID)?
+            .range::<&str>(prefix.as_str()..)?
+        {
+            if !key.value().starts_with(&prefix) {
+                break;
+            }
+
+            walls.push(InscriptionId::load(*inscription_id.value()));
+        }
+
+        Ok(walls)
+    }
+
+    /// The parent of `inscription_id`, if its reveal transaction declared one
+    /// and spent it.
+    pub(crate) fn get_parent(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<Option<InscriptionId>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(CHILD_TO_PARENT)?
+            .get(&inscription_id.store())?
+            .map(|parent| InscriptionId::load(*parent.value())))
+    }
+
+    /// Every move of an inscription since it was inscribed, oldest first.
+    pub(crate) fn get_transfers(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<Vec<Transfer>> {
+        let inscription_id = inscription_id.store();
+
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(INSCRIPTION_ID_TO_TRANSFERS)?
+            .range((&inscription_id, 0)..=(&inscription_id, u64::MAX))?
+            .map(|(_key, transfer)| Transfer::load(*transfer.value()))
+            .collect())
+    }
+
+    /// The children of `inscription_id`, in the order they were inscribed.
+    pub(crate) fn get_children(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<Vec<InscriptionId>> {
+        let rtx = self.database.begin_read()?;
+        let id_to_entry = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+        let parent = inscription_id.store();
+
+        let mut children = rtx
+            .open_table(PARENT_TO_CHILDREN)?
+            .range((&parent, &[0; 36])..=(&parent, &[u8::MAX; 36]))?
+            .map(|(key, _)| {
+                let child = *key.value().1;
+                let number = id_to_entry
+                    .get(&child)?
+                    .map(|entry| InscriptionEntry::load(entry.value()).number);
+                Ok((number, InscriptionId::load(child)))
+            })
+            .collect::<Result<Vec<(Option<u64>, InscriptionId)>>>()?;
+
+        children.sort_by_key(|(number, _child)| *number);
+
+        Ok(children.into_iter().map(|(_number, child)| child).collect())
+    }
+
+    /// Where each inscription listed by the wall `inscription_id` was when the
+    /// wall was inscribed.
+    pub(crate) fn get_bnw_wall_satpoints(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<Vec<SatPoint>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(INSCRIPTION_ID_TO_BNW_SATPOINTS)?
+            .get(&inscription_id.store())?
+            .map(|satpoints| {
+                satpoints
+                    .value()
+                    .chunks_exact(44)
+                    .map(|satpoint| SatPoint::load(satpoint.try_into().unwrap()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn get_bnw_wall(
+        &self,
+        bid: &Username,
+        wall: &WallName,
+    ) -> Result<Option<InscriptionId>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(BNW_WALL_TO_INSCRIPTION_ID)?
+            .get(wall.key(bid).as_str())?
+            .map(|inscription_id| InscriptionId::load(*inscription_id.value())))
+    }
+
+    /// The first inscription whose content has the hex encoded sha256
+    /// `content_hash`.
+    pub(crate) fn get_inscription_id_by_content_hash(
+        &self,
+        content_hash: &str,
+    ) -> Result<Option<InscriptionId>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(CONTENT_HASH_TO_INSCRIPTION_ID)?
+            .get(content_hash)?
+            .map(|inscription_id| InscriptionId::load(*inscription_id.value())))
+    }
+
+    /// Whether `inscription_id` is a tracked or non-tracked inscription.
+    pub(crate) fn has_inscription(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<bool> {
+        let rtx = self.database.begin_read()?;
+
+        Ok(rtx
+            .open_table(INSCRIPTION_ID_TO_SATPOINT)?
+            .get(&inscription_id.store())?
+            .is_some()
+            || rtx
+                .open_table(PUB_INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?
+                .get(&inscription_id.store())?
+                .is_some())
+    }
+
+    pub(crate) fn get_inscription_by_id(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<Option<Inscription>> {
+        if !self.has_inscription(inscription_id)? {
             return Ok(None);
         }
 
         Ok(self
             .get_transaction(inscription_id.txid)?
-            .and_then(|tx| Inscription::from_transaction(&tx)))
+            .and_then(|tx| {
+                Inscription::from_transaction(&tx)
+                    .into_iter()
+                    .nth(inscription_id.index.try_into().unwrap())
+            }))
+    }
+
+    /// The content of the multipart inscription with `manifest`, reassembled
+    /// from its parts, or why it couldn't be reassembled.
+    pub(crate) fn get_multipart_content(
+        &self,
+        manifest: &Manifest,
+    ) -> Result<Result<Vec<u8>, String>> {
+        let mut bodies = Vec::new();
+
+        for part in &manifest.parts {
+            let Some(inscription) = self.get_inscription_by_id(part.inscription_id)? else {
+                return Ok(Err(format!(
+                    "part {} of multipart inscription not found",
+                    part.inscription_id
+                )));
+            };
+
+            bodies.push(inscription.body().unwrap_or_default().to_vec());
+        }
+
+        Ok(manifest
+            .assemble(&bodies)
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    /// The outputs held by `script_pubkey` that carry inscriptions or rare
+    /// sats, or `None` if the index wasn't created with `--index-addresses`.
+    pub(crate) fn get_address_outputs(
+        &self,
+        script_pubkey: &Script,
+    ) -> Result<Option<Vec<OutPoint>>> {
+        if !self.has_address_index()? {
+            return Ok(None);
+        }
+
+        let script_pubkey = script_pubkey.as_bytes();
+
+        Ok(Some(
+            self.database
+                .begin_read()?
+                .open_table(SCRIPT_PUBKEY_TO_OUTPOINT)?
+                .range((script_pubkey, &[0; 36])..=(script_pubkey, &[u8::MAX; 36]))?
+                .map(|(key, _)| OutPoint::load(*key.value().1))
+                .collect(),
+        ))
+    }
+
+    pub(crate) fn get_rare_sats_on_output(
+        &self,
+        outpoint: OutPoint,
+    ) -> Result<Vec<Sat>> {
+        if !self.has_sat_index()? {
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .list_inner(outpoint.store())?
+            .unwrap_or_default()
+            .chunks_exact(11)
+            .map(|chunk| Sat(SatRange::load(chunk.try_into().unwrap()).0))
+            .filter(|sat| !sat.is_common())
+            .collect())
     }
 
     pub(crate) fn get_inscriptions_on_output(
@@ -791,11 +1273,29 @@ impl Index {
         &self,
         n: usize,
         from: Option<u64>,
+    ) -> Result<(Vec<InscriptionId>, Option<u64>, Option<u64>)> {
+        self.get_latest_with_prev_and_next(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, n, from)
+    }
+
+    /// The latest non-tracked `pub` inscriptions, numbered in their own
+    /// sequence, and the numbers of the previous and next pages.
+    pub(crate) fn get_latest_pub_inscriptions_with_prev_and_next(
+        &self,
+        n: usize,
+        from: Option<u64>,
+    ) -> Result<(Vec<InscriptionId>, Option<u64>, Option<u64>)> {
+        self.get_latest_with_prev_and_next(PUB_INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, n, from)
+    }
+
+    fn get_latest_with_prev_and_next(
+        &self,
+        table: TableDefinition<u64, &InscriptionIdValue>,
+        n: usize,
+        from: Option<u64>,
     ) -> Result<(Vec<InscriptionId>, Option<u64>, Option<u64>)> {
         let rtx = self.database.begin_read()?;
 
-        let inscription_number_to_inscription_id =
-            rtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+        let inscription_number_to_inscription_id = rtx.open_table(table)?;
 
         let latest = match inscription_number_to_inscription_id.iter()?.rev().next() {
             Some((number, _id)) => number.value(),
@@ -832,6 +1332,15 @@ impl Index {
         Ok((inscriptions, prev, next))
     }
 
+    /// The inscriptions matching `query`, and a cursor to the next page of
+    /// them, if there is one.
+    pub(crate) fn query_inscriptions(
+        &self,
+        query: &InscriptionQuery,
+    ) -> Result<(Vec<InscriptionId>, Option<Cursor>)> {
+        query.run(self)
+    }
+
     pub(crate) fn get_feed_inscriptions(
         &self,
         n: usize,
@@ -859,6 +1368,32 @@ impl Index {
             .map(|value| InscriptionEntry::load(value.value())))
     }
 
+    /// The entry of the non-tracked `pub` inscription `inscription_id`, whose
+    /// number is in the sequence of `pub` inscriptions, and which has no sat.
+    pub(crate) fn get_pub_inscription_entry(
+        &self,
+        inscription_id: InscriptionId,
+    ) -> Result<Option<InscriptionEntry>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(PUB_INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?
+            .get(&inscription_id.store())?
+            .map(|value| InscriptionEntry::load(value.value())))
+    }
+
+    pub(crate) fn get_pub_inscription_id_by_number(
+        &self,
+        n: u64,
+    ) -> Result<Option<InscriptionId>> {
+        Ok(self
+            .database
+            .begin_read()?
+            .open_table(PUB_INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?
+            .get(&n)?
+            .map(|id| Entry::load(*id.value())))
+    }
+
     #[cfg(test)]
     fn assert_inscription_location(
         &self,
@@ -946,12 +1481,50 @@ impl Index {
             .range::<&[u8; 44]>(&start..=&end)?
             .map(|(satpoint, id)| (Entry::load(*satpoint.value()), Entry::load(*id.value()))))
     }
+
+    /// Sort `flotsam` by its offset into the inputs of transaction `txid`, and
+    /// split it into what lands on the sats of `outputs`, with the satpoints it
+    /// lands on, and what's beyond them, which is spent as fees.
+    fn locate_flotsam<T>(
+        txid: Txid,
+        outputs: &[TxOut],
+        mut flotsam: Vec<T>,
+        offset: impl Fn(&T) -> u64,
+    ) -> (Vec<(T, SatPoint)>, Vec<T>) {
+        flotsam.sort_by_key(|flotsam| offset(flotsam));
+        let mut flotsam = flotsam.into_iter().peekable();
+
+        let mut located = Vec::new();
+        let mut output_value = 0;
+        for (vout, tx_out) in outputs.iter().enumerate() {
+            let end = output_value + tx_out.value;
+
+            while let Some(next) = flotsam.next_if(|flotsam| offset(flotsam) < end) {
+                let satpoint = SatPoint {
+                    outpoint: OutPoint {
+                        txid,
+                        vout: vout.try_into().unwrap(),
+                    },
+                    offset: offset(&next) - output_value,
+                };
+
+                located.push((next, satpoint));
+            }
+
+            output_value = end;
+        }
+
+        (located, flotsam.collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use {
-        super::*,
+        super::{
+            query::Order,
+            *,
+        },
         bitcoin::secp256k1::rand::{
             self,
             RngCore,
@@ -1462,404 +2035,1408 @@ mod tests {
     }
 
     #[test]
-    fn unaligned_inscriptions_are_tracked_correctly() {
+    fn inscription_protocol_ids_are_recorded() {
         for context in Context::configurations() {
-            context.mine_blocks(1);
+            context.mine_blocks(2);
 
-            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            let ord_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
                 witness: inscription("text/plain", "hello").to_witness(),
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
-
-            context.mine_blocks(1);
-
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint { txid, vout: 0 },
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
-            );
 
-            let send_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(2, 0, 0), (2, 1, 0)],
+            let script = bitcoin::blockdata::script::Builder::new()
+                .push_opcode(bitcoin::blockdata::opcodes::OP_FALSE)
+                .push_opcode(bitcoin::blockdata::opcodes::all::OP_IF)
+                .push_slice(b"pub")
+                .push_slice(&[1])
+                .push_slice(b"text/plain")
+                .push_slice(&[])
+                .push_slice(b"hello")
+                .push_opcode(bitcoin::blockdata::opcodes::all::OP_ENDIF)
+                .into_script();
+
+            let pub_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                witness: Witness::from_vec(vec![script.into_bytes(), Vec::new()]),
                 ..Default::default()
             });
 
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: send_txid,
-                        vout: 0,
-                    },
-                    offset: 50 * COIN_VALUE,
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_protocol_id(ord_txid.into())
+                    .unwrap(),
+                Some("ord-v0".into())
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_protocol_id(pub_txid.into())
+                    .unwrap(),
+                Some("pub-v0".into())
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_by_id(pub_txid.into())
+                    .unwrap()
+                    .unwrap()
+                    .protocol_id(),
+                "pub-v0"
+            );
+        }
+    }
+
+    #[test]
+    fn pub_inscriptions_are_standalone_records() {
+        for context in Context::configurations() {
+            context.mine_blocks(2);
+
+            let pub_inscription = |body: &[u8]| {
+                Witness::from_vec(vec![
+                    bitcoin::blockdata::script::Builder::new()
+                        .push_opcode(bitcoin::blockdata::opcodes::OP_FALSE)
+                        .push_opcode(bitcoin::blockdata::opcodes::all::OP_IF)
+                        .push_slice(b"pub")
+                        .push_slice(&[1])
+                        .push_slice(b"text/plain")
+                        .push_slice(&[])
+                        .push_slice(body)
+                        .push_opcode(bitcoin::blockdata::opcodes::all::OP_ENDIF)
+                        .into_script()
+                        .into_bytes(),
+                    Vec::new(),
+                ])
+            };
+
+            let first = InscriptionId::from(context.rpc_server.broadcast_tx(
+                TransactionTemplate {
+                    inputs: &[(1, 0, 0)],
+                    witness: pub_inscription(b"foo"),
+                    fee: 10,
+                    ..Default::default()
                 },
-                50 * COIN_VALUE,
+            ));
+
+            context.mine_blocks(1);
+
+            let tracked = InscriptionId::from(context.rpc_server.broadcast_tx(
+                TransactionTemplate {
+                    inputs: &[(2, 0, 0)],
+                    witness: inscription("text/plain", "bar").to_witness(),
+                    ..Default::default()
+                },
+            ));
+
+            let second = InscriptionId::from(context.rpc_server.broadcast_tx(
+                TransactionTemplate {
+                    inputs: &[(3, 0, 0)],
+                    witness: pub_inscription(b"baz"),
+                    ..Default::default()
+                },
+            ));
+
+            context.mine_blocks(1);
+
+            let entry = context
+                .index
+                .get_pub_inscription_entry(first)
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(entry.number, 0);
+            assert_eq!(entry.height, 3);
+            assert_eq!(entry.fee, 10);
+            assert_eq!(entry.sat, None);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_pub_inscription_entry(second)
+                    .unwrap()
+                    .unwrap()
+                    .number,
+                1
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_entry(tracked)
+                    .unwrap()
+                    .unwrap()
+                    .number,
+                0
+            );
+
+            for id in [first, second] {
+                assert!(context.index.get_inscription_entry(id).unwrap().is_none());
+                assert_eq!(
+                    context.index.get_inscription_satpoint_by_id(id).unwrap(),
+                    None
+                );
+                assert_eq!(
+                    context.index.get_inscription_protocol_id(id).unwrap(),
+                    Some("pub-v0".into())
+                );
+            }
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscriptions(None)
+                    .unwrap()
+                    .into_values()
+                    .collect::<Vec<InscriptionId>>(),
+                [tracked]
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_by_id(first)
+                    .unwrap()
+                    .unwrap()
+                    .body(),
+                Some(b"foo".as_slice())
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .get_latest_pub_inscriptions_with_prev_and_next(100, None)
+                    .unwrap(),
+                (vec![second, first], None, None)
+            );
+
+            assert_eq!(
+                context.index.get_events(3..4).unwrap(),
+                [Event::Inscribed {
+                    height: 3,
+                    inscription_id: first,
+                    satpoint: None,
+                }]
+            );
+
+            assert!(context
+                .index
+                .get_events(4..5)
+                .unwrap()
+                .contains(&Event::Inscribed {
+                    height: 4,
+                    inscription_id: second,
+                    satpoint: None,
+                }));
+
+            for tracked_query in [None, Some(true)] {
+                assert_eq!(
+                    context
+                        .index
+                        .query_inscriptions(&InscriptionQuery {
+                            tracked: tracked_query,
+                            ..Default::default()
+                        })
+                        .unwrap(),
+                    (vec![tracked], None)
+                );
+            }
+
+            assert_eq!(
+                context
+                    .index
+                    .query_inscriptions(&InscriptionQuery {
+                        tracked: Some(false),
+                        ..Default::default()
+                    })
+                    .unwrap(),
+                (vec![second, first], None)
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .query_inscriptions(&InscriptionQuery {
+                        tracked: Some(false),
+                        min_fee: Some(10),
+                        ..Default::default()
+                    })
+                    .unwrap(),
+                (vec![first], None)
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .query_inscriptions(&InscriptionQuery {
+                        tracked: Some(false),
+                        protocol_id: Some("ord-v0".into()),
+                        ..Default::default()
+                    })
+                    .unwrap(),
+                (vec![], None)
             );
         }
     }
 
     #[test]
-    fn merged_inscriptions_are_tracked_correctly() {
+    fn inscriptions_with_invalid_properties_are_flagged() {
         for context in Context::configurations() {
             context.mine_blocks(2);
 
-            let first_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            let wrapped = |properties: serde_json::Value| {
+                Inscription::new(
+                    Some("application/json".into()),
+                    Some(
+                        serde_json::json!({
+                          "protocol": "ord-v1",
+                          "protocol_version": "\"1.0.0\"",
+                          "protocol_properties": properties.to_string(),
+                          "content": "aGVsbG8=",
+                          "content_type": "text/plain;charset=utf-8",
+                          "wrapped": true,
+                        })
+                        .to_string()
+                        .into(),
+                    ),
+                )
+                .to_witness()
+            };
+
+            let valid = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: wrapped(serde_json::json!({"protocol": "ord", "title": "hello"})),
                 ..Default::default()
             });
 
-            let first_inscription_id = InscriptionId::from(first_txid);
-
-            let second_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            let invalid = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(2, 0, 0)],
-                witness: inscription("text/png", [1; 100]).to_witness(),
-                ..Default::default()
-            });
-            let second_inscription_id = InscriptionId::from(second_txid);
-
-            context.mine_blocks(1);
-
-            let merged_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(3, 1, 0), (3, 2, 0)],
+                witness: wrapped(serde_json::json!({"protocol": "ord", "license": ["MIT"]})),
                 ..Default::default()
             });
 
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                first_inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: merged_txid,
-                        vout: 0,
-                    },
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(valid.into())
+                    .unwrap(),
+                None
             );
 
-            context.index.assert_inscription_location(
-                second_inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: merged_txid,
-                        vout: 0,
-                    },
-                    offset: 50 * COIN_VALUE,
-                },
-                100 * COIN_VALUE,
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(invalid.into())
+                    .unwrap(),
+                Some("property `license` must be a string".into())
             );
         }
     }
 
     #[test]
-    fn inscriptions_that_are_sent_to_second_output_are_are_tracked_correctly() {
+    fn bid_usernames_are_registered_renewed_and_expire() {
         for context in Context::configurations() {
-            context.mine_blocks(1);
+            context.mine_blocks(3);
 
-            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            let username = "alice_1".parse::<Username>().unwrap();
+
+            let registration = || {
+                Inscription::wrapped(
+                    "bid-v1",
+                    serde_json::json!({ "name": "alice_1" }),
+                    "text/plain;charset=utf-8",
+                    b"alice_1",
+                )
+                .unwrap()
+                .to_witness()
+            };
+
+            let first = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: registration(),
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
 
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint { txid, vout: 0 },
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
-            );
-
-            let send_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(2, 0, 0), (2, 1, 0)],
-                outputs: 2,
+            let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                witness: registration(),
                 ..Default::default()
             });
 
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: send_txid,
-                        vout: 1,
-                    },
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context.index.get_bid_registration(&username).unwrap(),
+                Some((
+                    first.into(),
+                    BidEntry {
+                        registered: 4,
+                        expires: 104
+                    }
+                ))
             );
-        }
-    }
 
-    #[test]
-    fn missing_inputs_are_fetched_from_bitcoin_core() {
-        for args in [
-            ["--first-inscription-height", "2"].as_slice(),
-            ["--first-inscription-height", "2", "--index-sats"].as_slice(),
-        ] {
-            let context = Context::builder().args(args).build();
-            context.mine_blocks(1);
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(second.into())
+                    .unwrap(),
+                Some("bid `alice_1` is already registered".into())
+            );
 
-            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(1, 0, 0)],
-                witness: inscription("text/plain", "hello").to_witness(),
+            context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(4, 1, 0)],
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
 
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint { txid, vout: 0 },
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context.index.get_bid_registration(&username).unwrap(),
+                Some((
+                    first.into(),
+                    BidEntry {
+                        registered: 4,
+                        expires: 106
+                    }
+                ))
             );
 
-            let send_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(2, 0, 0), (2, 1, 0)],
+            context.mine_blocks(100);
+
+            let third = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 0, 0)],
+                witness: registration(),
                 ..Default::default()
             });
 
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: send_txid,
-                        vout: 0,
-                    },
-                    offset: 50 * COIN_VALUE,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context.index.get_bid_registration(&username).unwrap(),
+                Some((
+                    third.into(),
+                    BidEntry {
+                        registered: 107,
+                        expires: 207
+                    }
+                ))
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(third.into())
+                    .unwrap(),
+                None
             );
         }
     }
 
     #[test]
-    fn fee_spent_inscriptions_are_tracked_correctly() {
+    fn invalid_bid_usernames_are_flagged() {
         for context in Context::configurations() {
             context.mine_blocks(1);
 
             let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: Inscription::wrapped(
+                    "bid-v1",
+                    serde_json::json!({ "name": "Alice_1" }),
+                    "text/plain;charset=utf-8",
+                    b"Alice_1",
+                )
+                .unwrap()
+                .to_witness(),
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
 
             context.mine_blocks(1);
 
-            context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(2, 1, 0)],
-                fee: 50 * COIN_VALUE,
-                ..Default::default()
-            });
-
-            let coinbase_tx = context.mine_blocks(1)[0].txdata[0].txid();
-
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: coinbase_tx,
-                        vout: 0,
-                    },
-                    offset: 50 * COIN_VALUE,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(txid.into())
+                    .unwrap(),
+                Some("invalid username `Alice_1`: only `a-z`, `0-9` and `_` are allowed".into())
             );
         }
     }
 
     #[test]
-    fn inscription_can_be_fee_spent_in_first_transaction() {
+    fn inscriptions_with_unknown_compression_codecs_are_flagged() {
         for context in Context::configurations() {
             context.mine_blocks(1);
 
+            let expansion = serde_json::json!({
+                "protocol": "ord-v1",
+                "protocol_version": "\"1.0.0\"",
+                "protocol_properties": "{}",
+                "compression": "lzma base64",
+                "offchain": null,
+                "content": "AAAA",
+                "content_hash": null,
+                "content_type": "text/plain;charset=utf-8",
+                "content_metadata": null,
+                "wrapped": true,
+            });
+
             let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
-                fee: 50 * COIN_VALUE,
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: Inscription::new(
+                    Some("application/json".into()),
+                    Some(expansion.to_string().into()),
+                )
+                .to_witness(),
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
 
-            let coinbase_tx = context.mine_blocks(1)[0].txdata[0].txid();
+            context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: coinbase_tx,
-                        vout: 0,
-                    },
-                    offset: 50 * COIN_VALUE,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(txid.into())
+                    .unwrap(),
+                Some("decompression failed: unknown compression codec `lzma base64`".into())
             );
         }
     }
 
     #[test]
-    fn lost_inscriptions() {
+    fn inscriptions_with_malformed_protocol_properties_are_flagged() {
         for context in Context::configurations() {
             context.mine_blocks(1);
 
+            let expansion = serde_json::json!({
+                "protocol": "ord-v1",
+                "protocol_version": "\"1.0.0\"",
+                "protocol_properties": "{",
+                "compression": null,
+                "offchain": null,
+                "content": "AAAA",
+                "content_hash": null,
+                "content_type": "text/plain;charset=utf-8",
+                "content_metadata": null,
+                "wrapped": true,
+            });
+
             let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
-                fee: 50 * COIN_VALUE,
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: Inscription::new(
+                    Some("application/json".into()),
+                    Some(expansion.to_string().into()),
+                )
+                .to_witness(),
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
 
-            context.mine_blocks_with_subsidy(1, 0);
+            context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint::null(),
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(txid.into())
+                    .unwrap(),
+                Some(
+                    "invalid envelope expansion: protocol properties are not valid JSON: EOF \
+                    while parsing an object at line 1 column 1"
+                        .into()
+                )
             );
+
+            assert!(context
+                .index
+                .get_inscription_by_id(txid.into())
+                .unwrap()
+                .is_some());
         }
     }
 
     #[test]
-    fn multiple_inscriptions_can_be_lost() {
+    fn bfs_inscriptions_build_file_trees() {
         for context in Context::configurations() {
-            context.mine_blocks(1);
+            context.mine_blocks(4);
 
-            let first_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            let bid = "alice_1".parse::<Username>().unwrap();
+
+            context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
-                fee: 50 * COIN_VALUE,
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: Inscription::wrapped(
+                    "bid-v1",
+                    serde_json::json!({ "name": "alice_1" }),
+                    "text/plain;charset=utf-8",
+                    b"alice_1",
+                )
+                .unwrap()
+                .to_witness(),
                 ..Default::default()
             });
-            let first_inscription_id = InscriptionId::from(first_txid);
 
-            context.mine_blocks_with_subsidy(1, 0);
             context.mine_blocks(1);
 
-            let second_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            let bfs = |action: &str, path: &str| {
+                Inscription::wrapped(
+                    "bfs-v1",
+                    serde_json::json!({ "bid": "alice_1", "path": path, "action": action }),
+                    "text/plain;charset=utf-8",
+                    b"hello",
+                )
+                .unwrap()
+                .to_witness()
+            };
+
+            let put = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0), (5, 1, 0)],
+                witness: bfs("put", "/docs/a.txt"),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            let a = "/docs/a.txt".parse::<BfsPath>().unwrap();
+
+            assert_eq!(context.index.get_bfs_file(&bid, &a).unwrap(), Some(put.into()));
+            assert_eq!(
+                context.index.get_bfs_files(&bid, &BfsPath::root()).unwrap(),
+                vec![(a.clone(), put.into())]
+            );
+
+            let unauthorized = context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(3, 0, 0)],
-                fee: 50 * COIN_VALUE,
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: bfs("put", "/b.txt"),
                 ..Default::default()
             });
-            let second_inscription_id = InscriptionId::from(second_txid);
 
-            context.mine_blocks_with_subsidy(1, 0);
+            context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                first_inscription_id,
-                SatPoint {
-                    outpoint: OutPoint::null(),
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(unauthorized.into())
+                    .unwrap(),
+                Some(
+                    "bfs inscription does not spend the registration inscription of bid `alice_1`"
+                        .into()
+                )
+            );
+            assert_eq!(
+                context
+                    .index
+                    .get_bfs_file(&bid, &"/b.txt".parse().unwrap())
+                    .unwrap(),
+                None
             );
 
-            context.index.assert_inscription_location(
-                second_inscription_id,
-                SatPoint {
-                    outpoint: OutPoint::null(),
-                    offset: 50 * COIN_VALUE,
-                },
-                150 * COIN_VALUE,
+            let conflict = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(4, 0, 0), (6, 1, 0)],
+                witness: bfs("put", "/docs/a.txt/b.txt"),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(conflict.into())
+                    .unwrap(),
+                Some("bfs path `/docs/a.txt` of bid `alice_1` is a file".into())
+            );
+
+            let rm = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(5, 0, 0), (8, 1, 0)],
+                witness: bfs("rm", "/docs"),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(rm.into())
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                context.index.get_bfs_files(&bid, &BfsPath::root()).unwrap(),
+                Vec::new()
             );
         }
     }
 
     #[test]
-    fn lost_sats_are_tracked_correctly() {
-        let context = Context::builder().arg("--index-sats").build();
-        assert_eq!(context.index.statistic(Statistic::LostSats), 0);
+    fn bnw_walls_list_known_inscriptions() {
+        for context in Context::configurations() {
+            context.mine_blocks(4);
 
-        context.mine_blocks(1);
-        assert_eq!(context.index.statistic(Statistic::LostSats), 0);
+            let bid = "alice_1".parse::<Username>().unwrap();
 
-        context.mine_blocks_with_subsidy(1, 0);
-        assert_eq!(
-            context.index.statistic(Statistic::LostSats),
-            50 * COIN_VALUE
-        );
+            context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: Inscription::wrapped(
+                    "bid-v1",
+                    serde_json::json!({ "name": "alice_1" }),
+                    "text/plain;charset=utf-8",
+                    b"alice_1",
+                )
+                .unwrap()
+                .to_witness(),
+                ..Default::default()
+            });
 
-        context.mine_blocks_with_subsidy(1, 0);
-        assert_eq!(
-            context.index.statistic(Statistic::LostSats),
-            100 * COIN_VALUE
-        );
+            let showcased = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
 
-        context.mine_blocks(1);
-        assert_eq!(
-            context.index.statistic(Statistic::LostSats),
-            100 * COIN_VALUE
-        );
+            context.mine_blocks(1);
+
+            let wall = |inscriptions: &[InscriptionId]| {
+                Inscription::wrapped(
+                    "bnw-v1",
+                    serde_json::json!({
+                        "bid": "alice_1",
+                        "wall": "main",
+                        "inscriptions": inscriptions,
+                    }),
+                    "text/plain;charset=utf-8",
+                    b"",
+                )
+                .unwrap()
+                .to_witness()
+            };
+
+            let unknown = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 0, 0), (5, 1, 0)],
+                witness: wall(&[inscription_id(1)]),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(unknown.into())
+                    .unwrap(),
+                Some(format!(
+                    "bnw wall lists unknown inscription {}",
+                    inscription_id(1)
+                ))
+            );
+            assert_eq!(context.index.get_bnw_walls(&bid).unwrap(), Vec::new());
+
+            let valid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(4, 0, 0), (6, 1, 0)],
+                witness: wall(&[showcased.into()]),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            assert_eq!(
+                context.index.get_bnw_walls(&bid).unwrap(),
+                vec![InscriptionId::from(valid)]
+            );
+            assert_eq!(
+                context.index.get_bnw_wall_satpoints(valid.into()).unwrap(),
+                vec![SatPoint {
+                    outpoint: OutPoint {
+                        txid: showcased,
+                        vout: 0,
+                    },
+                    offset: 0,
+                }]
+            );
+            assert_eq!(
+                context
+                    .index
+                    .get_bnw_wall(&bid, &"main".parse().unwrap())
+                    .unwrap(),
+                Some(valid.into())
+            );
+        }
     }
 
     #[test]
-    fn lost_sat_ranges_are_tracked_correctly() {
-        let context = Context::builder().arg("--index-sats").build();
+    fn multipart_inscriptions_are_reassembled_from_parts() {
+        for context in Context::configurations() {
+            context.mine_blocks(6);
 
-        let null_ranges = || match context.index.list(OutPoint::null()).unwrap().unwrap() {
-            List::Unspent(ranges) => ranges,
-            _ => panic!(),
-        };
+            let wrapped = Inscription::wrapped(
+                "ord-v1",
+                serde_json::json!({}),
+                "text/plain;charset=utf-8",
+                b"foobarbaz",
+            )
+            .unwrap();
 
-        assert!(null_ranges().is_empty());
+            let mut parts = Vec::new();
 
-        context.mine_blocks(1);
+            for (i, part) in wrapped.parts(3).unwrap().into_iter().enumerate() {
+                let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                    inputs: &[(i + 1, 0, 0)],
+                    witness: part.to_witness(),
+                    ..Default::default()
+                });
 
-        assert!(null_ranges().is_empty());
+                parts.push(multipart::Part {
+                    inscription_id: txid.into(),
+                    content_hash: multipart::content_hash(part.body().unwrap()),
+                });
+            }
 
-        context.mine_blocks_with_subsidy(1, 0);
+            context.mine_blocks(1);
 
-        assert_eq!(null_ranges(), [(100 * COIN_VALUE, 150 * COIN_VALUE)]);
+            let manifest = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(4, 0, 0)],
+                witness: wrapped.with_parts(parts.clone()).unwrap().to_witness(),
+                ..Default::default()
+            });
 
-        context.mine_blocks_with_subsidy(1, 0);
+            parts.reverse();
 
-        assert_eq!(
-            null_ranges(),
-            [
-                (100 * COIN_VALUE, 150 * COIN_VALUE),
-                (150 * COIN_VALUE, 200 * COIN_VALUE)
-            ]
-        );
+            let reordered = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(5, 0, 0)],
+                witness: wrapped.with_parts(parts).unwrap().to_witness(),
+                ..Default::default()
+            });
 
-        context.mine_blocks(1);
+            let unknown = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(6, 0, 0)],
+                witness: wrapped
+                    .with_parts(vec![multipart::Part {
+                        inscription_id: inscription_id(1),
+                        content_hash: multipart::content_hash(b"foobarbaz"),
+                    }])
+                    .unwrap()
+                    .to_witness(),
+                ..Default::default()
+            });
 
-        assert_eq!(
-            null_ranges(),
-            [
-                (100 * COIN_VALUE, 150 * COIN_VALUE),
-                (150 * COIN_VALUE, 200 * COIN_VALUE)
-            ]
-        );
+            context.mine_blocks(1);
 
-        context.mine_blocks_with_subsidy(1, 0);
+            let content = |inscription_id: Txid| {
+                context.index.get_multipart_content(
+                    &context
+                        .index
+                        .get_inscription_by_id(inscription_id.into())
+                        .unwrap()
+                        .unwrap()
+                        .manifest()
+                        .unwrap(),
+                )
+            };
 
-        assert_eq!(
-            null_ranges(),
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(manifest.into())
+                    .unwrap(),
+                None
+            );
+            assert_eq!(content(manifest).unwrap(), Ok(b"foobarbaz".to_vec()));
+
+            assert!(content(reordered).unwrap().is_err());
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(unknown.into())
+                    .unwrap(),
+                Some(format!(
+                    "multipart inscription lists unknown part {}",
+                    inscription_id(1)
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn content_hashes_refer_to_the_first_inscription_with_that_content() {
+        for context in Context::configurations() {
+            context.mine_blocks(4);
+
+            let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            let wrapped = Inscription::wrapped(
+                "ord-v1",
+                serde_json::json!({}),
+                "text/plain",
+                b"hello",
+            )
+            .unwrap();
+
+            context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                witness: wrapped.to_witness(),
+                ..Default::default()
+            });
+
+            let reference = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 0, 0)],
+                witness: wrapped
+                    .with_reference(multipart::content_hash(b"hello"))
+                    .unwrap()
+                    .to_witness(),
+                ..Default::default()
+            });
+
+            let dangling = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(4, 0, 0)],
+                witness: wrapped
+                    .with_reference(multipart::content_hash(b"world"))
+                    .unwrap()
+                    .to_witness(),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_id_by_content_hash(&multipart::content_hash(b"hello"))
+                    .unwrap(),
+                Some(first.into())
+            );
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(reference.into())
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                context
+                    .index
+                    .get_inscription_validation_error(dangling.into())
+                    .unwrap(),
+                Some(format!(
+                    "content hash {} does not match any inscription",
+                    multipart::content_hash(b"world")
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn unaligned_inscriptions_are_tracked_correctly() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint { txid, vout: 0 },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+
+            let send_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0), (2, 1, 0)],
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: send_txid,
+                        vout: 0,
+                    },
+                    offset: 50 * COIN_VALUE,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn merged_inscriptions_are_tracked_correctly() {
+        for context in Context::configurations() {
+            context.mine_blocks(2);
+
+            let first_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+
+            let first_inscription_id = InscriptionId::from(first_txid);
+
+            let second_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                witness: inscription("text/png", [1; 100]).to_witness(),
+                ..Default::default()
+            });
+            let second_inscription_id = InscriptionId::from(second_txid);
+
+            context.mine_blocks(1);
+
+            let merged_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 1, 0), (3, 2, 0)],
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                first_inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: merged_txid,
+                        vout: 0,
+                    },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+
+            context.index.assert_inscription_location(
+                second_inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: merged_txid,
+                        vout: 0,
+                    },
+                    offset: 50 * COIN_VALUE,
+                },
+                100 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn inscriptions_that_are_sent_to_second_output_are_are_tracked_correctly() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint { txid, vout: 0 },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+
+            let send_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0), (2, 1, 0)],
+                outputs: 2,
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: send_txid,
+                        vout: 1,
+                    },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn missing_inputs_are_fetched_from_bitcoin_core() {
+        for args in [
+            ["--first-inscription-height", "2"].as_slice(),
+            ["--first-inscription-height", "2", "--index-sats"].as_slice(),
+        ] {
+            let context = Context::builder().args(args).build();
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint { txid, vout: 0 },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+
+            let send_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0), (2, 1, 0)],
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: send_txid,
+                        vout: 0,
+                    },
+                    offset: 50 * COIN_VALUE,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn fee_spent_inscriptions_are_tracked_correctly() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+
+            context.mine_blocks(1);
+
+            context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 1, 0)],
+                fee: 50 * COIN_VALUE,
+                ..Default::default()
+            });
+
+            let coinbase_tx = context.mine_blocks(1)[0].txdata[0].txid();
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: coinbase_tx,
+                        vout: 0,
+                    },
+                    offset: 50 * COIN_VALUE,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn inscription_transfers_are_logged() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let inscribe = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(inscribe);
+            context.mine_blocks(1);
+
+            assert_eq!(context.index.get_transfers(inscription_id).unwrap(), []);
+
+            let transfer = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 1, 0)],
+                ..Default::default()
+            });
+            context.mine_blocks(1);
+
+            let fee_spend = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 1, 0)],
+                fee: 50 * COIN_VALUE,
+                ..Default::default()
+            });
+            let coinbase_tx = context.mine_blocks(1)[0].txdata[0].txid();
+
+            assert_eq!(
+                context.index.get_transfers(inscription_id).unwrap(),
+                [
+                    Transfer {
+                        height: 3,
+                        txid: transfer,
+                        old_satpoint: SatPoint {
+                            outpoint: OutPoint::new(inscribe, 0),
+                            offset: 0,
+                        },
+                        new_satpoint: SatPoint {
+                            outpoint: OutPoint::new(transfer, 0),
+                            offset: 0,
+                        },
+                        fee_spent: false,
+                    },
+                    Transfer {
+                        height: 4,
+                        txid: fee_spend,
+                        old_satpoint: SatPoint {
+                            outpoint: OutPoint::new(transfer, 0),
+                            offset: 0,
+                        },
+                        new_satpoint: SatPoint {
+                            outpoint: OutPoint::new(coinbase_tx, 0),
+                            offset: 50 * COIN_VALUE,
+                        },
+                        fee_spent: true,
+                    },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn lost_inscription_transfers_are_logged_as_fee_spent() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let inscribe = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(inscribe);
+            context.mine_blocks(1);
+
+            let fee_spend = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 1, 0)],
+                fee: 50 * COIN_VALUE,
+                ..Default::default()
+            });
+            context.mine_blocks_with_subsidy(1, 0);
+
+            assert_eq!(
+                context.index.get_transfers(inscription_id).unwrap(),
+                [Transfer {
+                    height: 3,
+                    txid: fee_spend,
+                    old_satpoint: SatPoint {
+                        outpoint: OutPoint::new(inscribe, 0),
+                        offset: 0,
+                    },
+                    new_satpoint: SatPoint {
+                        outpoint: OutPoint::null(),
+                        offset: 0,
+                    },
+                    fee_spent: true,
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn inscription_can_be_fee_spent_in_first_transaction() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                fee: 50 * COIN_VALUE,
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+
+            let coinbase_tx = context.mine_blocks(1)[0].txdata[0].txid();
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: coinbase_tx,
+                        vout: 0,
+                    },
+                    offset: 50 * COIN_VALUE,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn lost_inscriptions() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                fee: 50 * COIN_VALUE,
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+
+            context.mine_blocks_with_subsidy(1, 0);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint::null(),
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_inscriptions_can_be_lost() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let first_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                fee: 50 * COIN_VALUE,
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let first_inscription_id = InscriptionId::from(first_txid);
+
+            context.mine_blocks_with_subsidy(1, 0);
+            context.mine_blocks(1);
+
+            let second_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 0, 0)],
+                fee: 50 * COIN_VALUE,
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let second_inscription_id = InscriptionId::from(second_txid);
+
+            context.mine_blocks_with_subsidy(1, 0);
+
+            context.index.assert_inscription_location(
+                first_inscription_id,
+                SatPoint {
+                    outpoint: OutPoint::null(),
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+
+            context.index.assert_inscription_location(
+                second_inscription_id,
+                SatPoint {
+                    outpoint: OutPoint::null(),
+                    offset: 50 * COIN_VALUE,
+                },
+                150 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn lost_sats_are_tracked_correctly() {
+        let context = Context::builder().arg("--index-sats").build();
+        assert_eq!(context.index.statistic(Statistic::LostSats), 0);
+
+        context.mine_blocks(1);
+        assert_eq!(context.index.statistic(Statistic::LostSats), 0);
+
+        context.mine_blocks_with_subsidy(1, 0);
+        assert_eq!(
+            context.index.statistic(Statistic::LostSats),
+            50 * COIN_VALUE
+        );
+
+        context.mine_blocks_with_subsidy(1, 0);
+        assert_eq!(
+            context.index.statistic(Statistic::LostSats),
+            100 * COIN_VALUE
+        );
+
+        context.mine_blocks(1);
+        assert_eq!(
+            context.index.statistic(Statistic::LostSats),
+            100 * COIN_VALUE
+        );
+    }
+
+    #[test]
+    fn lost_sat_ranges_are_tracked_correctly() {
+        let context = Context::builder().arg("--index-sats").build();
+
+        let null_ranges = || match context.index.list(OutPoint::null()).unwrap().unwrap() {
+            List::Unspent(ranges) => ranges,
+            _ => panic!(),
+        };
+
+        assert!(null_ranges().is_empty());
+
+        context.mine_blocks(1);
+
+        assert!(null_ranges().is_empty());
+
+        context.mine_blocks_with_subsidy(1, 0);
+
+        assert_eq!(null_ranges(), [(100 * COIN_VALUE, 150 * COIN_VALUE)]);
+
+        context.mine_blocks_with_subsidy(1, 0);
+
+        assert_eq!(
+            null_ranges(),
+            [
+                (100 * COIN_VALUE, 150 * COIN_VALUE),
+                (150 * COIN_VALUE, 200 * COIN_VALUE)
+            ]
+        );
+
+        context.mine_blocks(1);
+
+        assert_eq!(
+            null_ranges(),
+            [
+                (100 * COIN_VALUE, 150 * COIN_VALUE),
+                (150 * COIN_VALUE, 200 * COIN_VALUE)
+            ]
+        );
+
+        context.mine_blocks_with_subsidy(1, 0);
+
+        assert_eq!(
+            null_ranges(),
             [
                 (100 * COIN_VALUE, 150 * COIN_VALUE),
                 (150 * COIN_VALUE, 200 * COIN_VALUE),
@@ -1869,300 +3446,866 @@ mod tests {
     }
 
     #[test]
-    fn lost_inscriptions_get_lost_satpoints() {
+    fn lost_inscriptions_get_lost_satpoints() {
+        for context in Context::configurations() {
+            context.mine_blocks_with_subsidy(1, 0);
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                outputs: 2,
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+            context.mine_blocks(1);
+
+            context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 1, 1), (3, 1, 0)],
+                fee: 50 * COIN_VALUE,
+                ..Default::default()
+            });
+            context.mine_blocks_with_subsidy(1, 0);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint::null(),
+                    offset: 75 * COIN_VALUE,
+                },
+                100 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn inscription_skips_zero_value_first_output_of_inscribe_transaction() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                outputs: 2,
+                witness: inscription("text/plain", "hello").to_witness(),
+                output_values: &[0, 50 * COIN_VALUE],
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint { txid, vout: 1 },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn inscription_can_be_lost_in_first_transaction() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                fee: 50 * COIN_VALUE,
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+            let inscription_id = InscriptionId::from(txid);
+            context.mine_blocks_with_subsidy(1, 0);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint::null(),
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+        }
+    }
+
+    #[test]
+    fn lost_rare_sats_are_tracked() {
+        let context = Context::builder().arg("--index-sats").build();
+        context.mine_blocks_with_subsidy(1, 0);
+        context.mine_blocks_with_subsidy(1, 0);
+
+        assert_eq!(
+            context
+                .index
+                .rare_sat_satpoint(Sat(50 * COIN_VALUE))
+                .unwrap()
+                .unwrap(),
+            SatPoint {
+                outpoint: OutPoint::null(),
+                offset: 0,
+            },
+        );
+
+        assert_eq!(
+            context
+                .index
+                .rare_sat_satpoint(Sat(100 * COIN_VALUE))
+                .unwrap()
+                .unwrap(),
+            SatPoint {
+                outpoint: OutPoint::null(),
+                offset: 50 * COIN_VALUE,
+            },
+        );
+    }
+
+    #[test]
+    fn old_schema_gives_correct_error() {
+        let tempdir = {
+            let context = Context::builder().build();
+
+            let wtx = context.index.database.begin_write().unwrap();
+
+            wtx.open_table(STATISTIC_TO_COUNT)
+                .unwrap()
+                .insert(&Statistic::Schema.key(), &0)
+                .unwrap();
+
+            wtx.commit().unwrap();
+
+            context.tempdir
+        };
+
+        let path = tempdir.path().to_owned();
+
+        let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+        assert_eq!(
+      Context::builder().tempdir(tempdir).try_build().err().unwrap().to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with an older, incompatible version of arb, consider deleting and rebuilding the index: index schema 0, arb schema {SCHEMA_VERSION}", path.display()));
+    }
+
+    #[test]
+    fn different_protocol_specs_give_correct_error() {
+        let tempdir = {
+            let context = Context::builder().build();
+
+            let wtx = context.index.database.begin_write().unwrap();
+
+            wtx.open_table(STATISTIC_TO_COUNT)
+                .unwrap()
+                .insert(&Statistic::ProtocolsDigest.key(), &1)
+                .unwrap();
+
+            wtx.commit().unwrap();
+
+            context.tempdir
+        };
+
+        let path = tempdir.path().to_owned();
+
+        let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+        assert_eq!(
+      Context::builder().tempdir(tempdir).try_build().err().unwrap().to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` was built with different protocol specs, which change how inscriptions are indexed, consider rebuilding the index, or loading the same specs from the data dir and `--protocols-dir`: index protocols digest 0000000000000001, loaded protocols digest {:016x}", path.display(), ProtocolRegistry::current().digest()));
+    }
+
+    #[test]
+    fn same_protocol_specs_reopen() {
+        let tempdir = Context::builder().build().tempdir;
+        assert!(Context::builder().tempdir(tempdir).try_build().is_ok());
+    }
+
+    #[test]
+    fn new_schema_gives_correct_error() {
+        let tempdir = {
+            let context = Context::builder().build();
+
+            let wtx = context.index.database.begin_write().unwrap();
+
+            wtx.open_table(STATISTIC_TO_COUNT)
+                .unwrap()
+                .insert(&Statistic::Schema.key(), &u64::MAX)
+                .unwrap();
+
+            wtx.commit().unwrap();
+
+            context.tempdir
+        };
+
+        let path = tempdir.path().to_owned();
+
+        let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+        assert_eq!(
+      Context::builder().tempdir(tempdir).try_build().err().unwrap().to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with a newer, incompatible version of arb, consider updating arb: index schema {}, arb schema {SCHEMA_VERSION}", path.display(), u64::MAX));
+    }
+
+    #[test]
+    fn inscriptions_on_output() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+
+            let inscription_id = InscriptionId::from(txid);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscriptions_on_output(OutPoint { txid, vout: 0 })
+                    .unwrap(),
+                []
+            );
+
+            context.mine_blocks(1);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscriptions_on_output(OutPoint { txid, vout: 0 })
+                    .unwrap(),
+                [inscription_id]
+            );
+
+            let send_id = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 1, 0)],
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscriptions_on_output(OutPoint { txid, vout: 0 })
+                    .unwrap(),
+                []
+            );
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscriptions_on_output(OutPoint {
+                        txid: send_id,
+                        vout: 0,
+                    })
+                    .unwrap(),
+                [inscription_id]
+            );
+        }
+    }
+
+    #[test]
+    fn inscriptions_on_same_sat_after_the_first_are_ignored() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            let inscription_id = InscriptionId::from(first);
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscriptions_on_output(OutPoint {
+                        txid: first,
+                        vout: 0
+                    })
+                    .unwrap(),
+                [inscription_id]
+            );
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: first,
+                        vout: 0,
+                    },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+
+            let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 1, 0)],
+                witness: inscription("text/plain", "hello").to_witness(),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            context.index.assert_inscription_location(
+                inscription_id,
+                SatPoint {
+                    outpoint: OutPoint {
+                        txid: second,
+                        vout: 0,
+                    },
+                    offset: 0,
+                },
+                50 * COIN_VALUE,
+            );
+
+            assert!(context
+                .index
+                .get_inscription_entry(second.into())
+                .unwrap()
+                .is_none());
+
+            assert!(context
+                .index
+                .get_inscription_by_id(second.into())
+                .unwrap()
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn every_inscription_in_a_transaction_is_made_on_its_own_output() {
         for context in Context::configurations() {
-            context.mine_blocks_with_subsidy(1, 0);
             context.mine_blocks(1);
 
+            let mut builder = bitcoin::blockdata::script::Builder::new();
+            builder = inscription("text/plain", "foo").append_reveal_script_to_builder(builder);
+            builder = inscription("text/plain", "bar").append_reveal_script_to_builder(builder);
+
             let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(2, 0, 0)],
+                inputs: &[(1, 0, 0)],
                 outputs: 2,
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: Witness::from_vec(vec![builder.into_script().into_bytes(), Vec::new()]),
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
+
             context.mine_blocks(1);
 
-            context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(3, 1, 1), (3, 1, 0)],
-                fee: 50 * COIN_VALUE,
-                ..Default::default()
-            });
-            context.mine_blocks_with_subsidy(1, 0);
+            for (index, body) in ["foo", "bar"].into_iter().enumerate() {
+                let inscription_id = InscriptionId {
+                    txid,
+                    index: index.try_into().unwrap(),
+                };
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint::null(),
-                    offset: 75 * COIN_VALUE,
-                },
-                100 * COIN_VALUE,
-            );
+                assert_eq!(
+                    context
+                        .index
+                        .get_inscription_satpoint_by_id(inscription_id)
+                        .unwrap(),
+                    Some(SatPoint {
+                        outpoint: OutPoint {
+                            txid,
+                            vout: index.try_into().unwrap(),
+                        },
+                        offset: 0,
+                    }),
+                );
+
+                assert_eq!(
+                    context
+                        .index
+                        .get_inscription_by_id(inscription_id)
+                        .unwrap()
+                        .unwrap(),
+                    inscription("text/plain", body)
+                );
+            }
         }
     }
 
     #[test]
-    fn inscription_skips_zero_value_first_output_of_inscribe_transaction() {
+    fn children_are_listed_in_the_order_they_were_inscribed() {
         for context in Context::configurations() {
             context.mine_blocks(1);
 
-            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            let parent = InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "parent").to_witness(),
+                ..Default::default()
+            }));
+
+            context.mine_blocks(1);
+
+            let mut children = Vec::new();
+
+            // Each child moves the parent to its second output
+            for height in 2..6 {
+                children.push(InscriptionId::from(context.rpc_server.broadcast_tx(
+                    TransactionTemplate {
+                        inputs: &[(height, 0, 0), (height, 1, usize::from(height > 2))],
+                        outputs: 2,
+                        witness: inscription("text/plain", "child")
+                            .with_parent(parent)
+                            .to_witness(),
+                        ..Default::default()
+                    },
+                )));
+
+                context.mine_blocks(1);
+            }
+
+            assert_eq!(context.index.get_children(parent).unwrap(), children);
+        }
+    }
+
+    #[test]
+    fn children_must_spend_their_parent() {
+        for context in Context::configurations() {
+            context.mine_blocks(2);
+
+            let parent = InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain", "parent").to_witness(),
+                ..Default::default()
+            }));
+
+            context.mine_blocks(1);
+
+            let child = InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 0, 0), (3, 1, 0)],
                 outputs: 2,
-                witness: inscription("text/plain", "hello").to_witness(),
-                output_values: &[0, 50 * COIN_VALUE],
+                witness: inscription("text/plain", "child")
+                    .with_parent(parent)
+                    .to_witness(),
                 ..Default::default()
-            });
-            let inscription_id = InscriptionId::from(txid);
+            }));
+
+            let orphan = InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                witness: inscription("text/plain", "orphan")
+                    .with_parent(parent)
+                    .to_witness(),
+                ..Default::default()
+            }));
+
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint { txid, vout: 1 },
+            assert_eq!(context.index.get_parent(child).unwrap(), Some(parent));
+            assert_eq!(context.index.get_children(parent).unwrap(), [child]);
+
+            assert_eq!(
+                context.index.get_inscription_satpoint_by_id(parent).unwrap(),
+                Some(SatPoint {
+                    outpoint: OutPoint {
+                        txid: child.txid,
+                        vout: 1,
+                    },
                     offset: 0,
-                },
-                50 * COIN_VALUE,
+                })
             );
+
+            assert!(context.index.get_inscription_entry(orphan).unwrap().is_some());
+            assert_eq!(context.index.get_parent(orphan).unwrap(), None);
         }
     }
 
     #[test]
-    fn inscription_can_be_lost_in_first_transaction() {
+    fn inscription_events_are_stored_and_published() {
         for context in Context::configurations() {
+            let mut receiver = context.index.subscribe_events();
+
+            context.mine_blocks(2);
+
+            let inscription_id =
+                InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
+                    inputs: &[(1, 0, 0)],
+                    witness: inscription("text/plain", "hello").to_witness(),
+                    ..Default::default()
+                }));
+
             context.mine_blocks(1);
 
-            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(1, 0, 0)],
+            let transfer = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 1, 0)],
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(4, 1, 0)],
                 fee: 50 * COIN_VALUE,
-                witness: inscription("text/plain", "hello").to_witness(),
                 ..Default::default()
             });
-            let inscription_id = InscriptionId::from(txid);
+
             context.mine_blocks_with_subsidy(1, 0);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint::null(),
-                    offset: 0,
+            let satpoint = |txid| SatPoint {
+                outpoint: OutPoint { txid, vout: 0 },
+                offset: 0,
+            };
+
+            let events = vec![
+                Event::Inscribed {
+                    height: 3,
+                    inscription_id,
+                    satpoint: Some(satpoint(inscription_id.txid)),
                 },
-                50 * COIN_VALUE,
-            );
+                Event::Transferred {
+                    height: 4,
+                    inscription_id,
+                    old_satpoint: satpoint(inscription_id.txid),
+                    new_satpoint: satpoint(transfer),
+                },
+                Event::Burned {
+                    height: 5,
+                    inscription_id,
+                    old_satpoint: satpoint(transfer),
+                    new_satpoint: SatPoint {
+                        outpoint: OutPoint::null(),
+                        offset: 0,
+                    },
+                },
+            ];
+
+            assert_eq!(context.index.get_events(0..6).unwrap(), events);
+            assert_eq!(context.index.get_events(4..5).unwrap(), events[1..2]);
+
+            for event in events {
+                assert_eq!(receiver.try_recv().unwrap(), event);
+            }
+
+            assert!(receiver.try_recv().is_err());
         }
     }
 
     #[test]
-    fn lost_rare_sats_are_tracked() {
-        let context = Context::builder().arg("--index-sats").build();
-        context.mine_blocks_with_subsidy(1, 0);
-        context.mine_blocks_with_subsidy(1, 0);
+    fn reorgs_are_published_as_events() {
+        let context = Context::builder().build();
 
-        assert_eq!(
-            context
-                .index
-                .rare_sat_satpoint(Sat(50 * COIN_VALUE))
-                .unwrap()
-                .unwrap(),
-            SatPoint {
-                outpoint: OutPoint::null(),
-                offset: 0,
-            },
-        );
+        context.mine_blocks(1);
 
-        assert_eq!(
-            context
-                .index
-                .rare_sat_satpoint(Sat(100 * COIN_VALUE))
-                .unwrap()
-                .unwrap(),
-            SatPoint {
-                outpoint: OutPoint::null(),
-                offset: 50 * COIN_VALUE,
-            },
-        );
+        let mut receiver = context.index.subscribe_events();
+
+        context.rpc_server.invalidate_tip();
+        context.mine_blocks(2);
+
+        assert_eq!(receiver.try_recv().unwrap(), Event::Reorged { height: 0 });
     }
 
     #[test]
-    fn old_schema_gives_correct_error() {
-        let tempdir = {
-            let context = Context::builder().build();
+    fn mempool_transactions_are_projected_onto_the_index() {
+        for context in Context::configurations() {
+            context.mine_blocks(3);
 
-            let wtx = context.index.database.begin_write().unwrap();
+            let inscription_id =
+                InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
+                    inputs: &[(1, 0, 0)],
+                    witness: inscription("text/plain", "hello").to_witness(),
+                    ..Default::default()
+                }));
 
-            wtx.open_table(STATISTIC_TO_COUNT)
-                .unwrap()
-                .insert(&Statistic::Schema.key(), &0)
-                .unwrap();
+            context.mine_blocks(1);
 
-            wtx.commit().unwrap();
+            assert_eq!(context.index.get_pending(), None);
 
-            context.tempdir
-        };
+            let transfer = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 0, 0), (4, 1, 0)],
+                outputs: 2,
+                ..Default::default()
+            });
 
-        let path = tempdir.path().to_owned();
+            let lost = InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 0, 0)],
+                witness: inscription("text/plain", "lost").to_witness(),
+                fee: 50 * COIN_VALUE,
+                ..Default::default()
+            }));
 
-        let delimiter = if cfg!(windows) { '\\' } else { '/' };
+            context.index.update_mempool().unwrap();
 
-        assert_eq!(
-      Context::builder().tempdir(tempdir).try_build().err().unwrap().to_string(),
-      format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with an older, incompatible version of arb, consider deleting and rebuilding the index: index schema 0, arb schema {SCHEMA_VERSION}", path.display()));
+            assert_eq!(
+                context
+                    .index
+                    .mempool
+                    .lock()
+                    .unwrap()
+                    .transactions
+                    .keys()
+                    .copied()
+                    .collect::<HashSet<Txid>>(),
+                HashSet::from([transfer, lost.txid])
+            );
+
+            context.index.update_mempool().unwrap();
+
+            assert_eq!(
+                context.index.get_pending_by_id(inscription_id),
+                Some(Pending {
+                    kind: PendingKind::Transfer,
+                    satpoint: Some(SatPoint {
+                        outpoint: OutPoint {
+                            txid: transfer,
+                            vout: 1,
+                        },
+                        offset: 0,
+                    }),
+                    txid: transfer,
+                })
+            );
+
+            assert_eq!(
+                context.index.get_pending_by_id(lost),
+                Some(Pending {
+                    kind: PendingKind::Inscription,
+                    satpoint: None,
+                    txid: lost.txid,
+                })
+            );
+
+            context.mine_blocks(1);
+            context.index.update_mempool().unwrap();
+
+            assert_eq!(context.index.get_pending(), Some(BTreeMap::new()));
+            assert!(context.index.mempool.lock().unwrap().transactions.is_empty());
+        }
     }
 
     #[test]
-    fn new_schema_gives_correct_error() {
-        let tempdir = {
-            let context = Context::builder().build();
+    fn reorgs_are_rolled_back_to_a_savepoint() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
 
-            let wtx = context.index.database.begin_write().unwrap();
+            let inscription_id =
+                InscriptionId::from(context.rpc_server.broadcast_tx(TransactionTemplate {
+                    inputs: &[(1, 0, 0)],
+                    witness: inscription("text/plain", "hello").to_witness(),
+                    ..Default::default()
+                }));
 
-            wtx.open_table(STATISTIC_TO_COUNT)
+            context.mine_blocks(1);
+
+            assert!(context
+                .index
+                .get_inscription_entry(inscription_id)
                 .unwrap()
-                .insert(&Statistic::Schema.key(), &u64::MAX)
-                .unwrap();
+                .is_some());
 
-            wtx.commit().unwrap();
+            context.rpc_server.invalidate_tip();
+            let blocks = context.mine_blocks(2);
 
-            context.tempdir
-        };
+            assert!(!context.index.is_reorged());
 
-        let path = tempdir.path().to_owned();
+            assert_eq!(
+                context.index.blocks(2).unwrap(),
+                [(3, blocks[1].block_hash()), (2, blocks[0].block_hash())]
+            );
 
-        let delimiter = if cfg!(windows) { '\\' } else { '/' };
+            assert!(context
+                .index
+                .get_inscription_entry(inscription_id)
+                .unwrap()
+                .is_none());
+        }
+    }
 
-        assert_eq!(
-      Context::builder().tempdir(tempdir).try_build().err().unwrap().to_string(),
-      format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with a newer, incompatible version of arb, consider updating arb: index schema {}, arb schema {SCHEMA_VERSION}", path.display(), u64::MAX));
+    #[test]
+    fn savepoint_is_taken_by_the_first_update_after_the_index_is_opened() {
+        let Context {
+            options,
+            rpc_server,
+            tempdir: _tempdir,
+            index,
+        } = Context::builder().build();
+
+        rpc_server.mine_blocks(2);
+        index.update().unwrap();
+        drop(index);
+
+        let index = Index::open(&options).unwrap();
+        assert_eq!(index.oldest_savepoint(), None);
+
+        rpc_server.mine_blocks(1);
+        index.update().unwrap();
+        assert_eq!(index.oldest_savepoint(), Some(3));
+
+        rpc_server.invalidate_tip();
+        rpc_server.mine_blocks(2);
+        index.update().unwrap();
+
+        assert!(!index.is_reorged());
+        assert_eq!(index.block_count().unwrap(), 5);
     }
 
     #[test]
-    fn inscriptions_on_output() {
-        for context in Context::configurations() {
+    fn reorgs_deeper_than_the_oldest_savepoint_are_unrecoverable() {
+        let context = Context::builder().build();
+
+        for _ in 0..35 {
             context.mine_blocks(1);
+        }
 
-            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(1, 0, 0)],
-                witness: inscription("text/plain", "hello").to_witness(),
-                ..Default::default()
-            });
+        for _ in 0..30 {
+            context.rpc_server.invalidate_tip();
+        }
 
-            let inscription_id = InscriptionId::from(txid);
+        context.rpc_server.mine_blocks(31);
 
-            assert_eq!(
-                context
-                    .index
-                    .get_inscriptions_on_output(OutPoint { txid, vout: 0 })
-                    .unwrap(),
-                []
-            );
+        assert_eq!(
+            context
+                .index
+                .update()
+                .unwrap_err()
+                .downcast_ref::<ReorgError>(),
+            Some(&ReorgError::Unrecoverable)
+        );
+
+        assert!(context.index.is_reorged());
+    }
 
+    #[test]
+    fn inscriptions_on_sats_beyond_the_inputs_are_not_made() {
+        for context in Context::configurations() {
             context.mine_blocks(1);
 
-            assert_eq!(
-                context
-                    .index
-                    .get_inscriptions_on_output(OutPoint { txid, vout: 0 })
-                    .unwrap(),
-                [inscription_id]
-            );
+            let mut builder = bitcoin::blockdata::script::Builder::new();
+            for body in ["foo", "bar", "baz"] {
+                builder = inscription("text/plain", body).append_reveal_script_to_builder(builder);
+            }
 
-            let send_id = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(2, 1, 0)],
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                outputs: 1,
+                fee: 1,
+                witness: Witness::from_vec(vec![builder.into_script().into_bytes(), Vec::new()]),
                 ..Default::default()
             });
 
             context.mine_blocks(1);
 
+            let inscription_id = |index| InscriptionId { txid, index };
+
             assert_eq!(
                 context
                     .index
-                    .get_inscriptions_on_output(OutPoint { txid, vout: 0 })
+                    .get_inscription_satpoint_by_id(inscription_id(0))
                     .unwrap(),
-                []
+                Some(SatPoint {
+                    outpoint: OutPoint { txid, vout: 0 },
+                    offset: 0,
+                }),
             );
 
+            // The second inscription is made on the one sat paid as fee
             assert_eq!(
                 context
                     .index
-                    .get_inscriptions_on_output(OutPoint {
-                        txid: send_id,
-                        vout: 0,
-                    })
-                    .unwrap(),
-                [inscription_id]
+                    .get_inscription_satpoint_by_id(inscription_id(1))
+                    .unwrap()
+                    .unwrap()
+                    .outpoint,
+                OutPoint::null(),
+            );
+
+            // The third would be made on a sat that the transaction doesn't have
+            assert!(context
+                .index
+                .get_inscription_entry(inscription_id(2))
+                .unwrap()
+                .is_none());
+
+            assert_eq!(
+                context
+                    .index
+                    .get_inscriptions(None)
+                    .unwrap()
+                    .into_values()
+                    .collect::<std::collections::BTreeSet<InscriptionId>>(),
+                [inscription_id(0), inscription_id(1)].into()
             );
         }
     }
 
     #[test]
-    fn inscriptions_on_same_sat_after_the_first_are_ignored() {
+    fn fee_is_split_between_the_inscriptions_made() {
         for context in Context::configurations() {
             context.mine_blocks(1);
 
-            let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+            context.rpc_server.broadcast_tx(TransactionTemplate {
                 inputs: &[(1, 0, 0)],
-                witness: inscription("text/plain", "hello").to_witness(),
+                witness: inscription("text/plain", "foo").to_witness(),
                 ..Default::default()
             });
 
             context.mine_blocks(1);
 
-            let inscription_id = InscriptionId::from(first);
+            let mut builder = bitcoin::blockdata::script::Builder::new();
+            for body in ["bar", "baz"] {
+                builder = inscription("text/plain", body).append_reveal_script_to_builder(builder);
+            }
+
+            // The first envelope is on the sat of the spent inscription, so only the
+            // second makes an inscription
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(2, 1, 0)],
+                outputs: 2,
+                fee: 10,
+                witness: Witness::from_vec(vec![builder.into_script().into_bytes(), Vec::new()]),
+                ..Default::default()
+            });
+
+            context.mine_blocks(1);
+
+            assert!(context
+                .index
+                .get_inscription_entry(InscriptionId { txid, index: 0 })
+                .unwrap()
+                .is_none());
 
             assert_eq!(
                 context
                     .index
-                    .get_inscriptions_on_output(OutPoint {
-                        txid: first,
-                        vout: 0
-                    })
-                    .unwrap(),
-                [inscription_id]
+                    .get_inscription_entry(InscriptionId { txid, index: 1 })
+                    .unwrap()
+                    .unwrap()
+                    .fee,
+                10
             );
+        }
+    }
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: first,
-                        vout: 0,
-                    },
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
-            );
+    #[test]
+    fn fee_remainder_goes_to_the_first_inscription() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
 
-            let second = context.rpc_server.broadcast_tx(TransactionTemplate {
-                inputs: &[(2, 1, 0)],
-                witness: inscription("text/plain", "hello").to_witness(),
+            let mut builder = bitcoin::blockdata::script::Builder::new();
+            for body in ["foo", "bar", "baz"] {
+                builder = inscription("text/plain", body).append_reveal_script_to_builder(builder);
+            }
+
+            let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                fee: 10,
+                witness: Witness::from_vec(vec![builder.into_script().into_bytes(), Vec::new()]),
                 ..Default::default()
             });
 
             context.mine_blocks(1);
 
-            context.index.assert_inscription_location(
-                inscription_id,
-                SatPoint {
-                    outpoint: OutPoint {
-                        txid: second,
-                        vout: 0,
-                    },
-                    offset: 0,
-                },
-                50 * COIN_VALUE,
+            assert_eq!(
+                (0..3)
+                    .map(|index| context
+                        .index
+                        .get_inscription_entry(InscriptionId { txid, index })
+                        .unwrap()
+                        .unwrap()
+                        .fee)
+                    .collect::<Vec<u64>>(),
+                [4, 3, 3]
             );
-
-            assert!(context
-                .index
-                .get_inscription_entry(second.into())
-                .unwrap()
-                .is_none());
-
-            assert!(context
-                .index
-                .get_inscription_by_id(second.into())
-                .unwrap()
-                .is_none());
         }
     }
 
@@ -2235,6 +4378,234 @@ mod tests {
         }
     }
 
+    #[test]
+    fn wrapped_inscriptions_are_queried_by_their_wrapped_content_type() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let inscription_id = InscriptionId::from(context.rpc_server.broadcast_tx(
+                TransactionTemplate {
+                    inputs: &[(1, 0, 0)],
+                    witness: Inscription::wrapped(
+                        "ord-v1",
+                        serde_json::json!({}),
+                        "image/png",
+                        &[1; 100],
+                    )
+                    .unwrap()
+                    .to_witness(),
+                    ..Default::default()
+                },
+            ));
+
+            context.mine_blocks(1);
+
+            let query = |query: InscriptionQuery| context.index.query_inscriptions(&query).unwrap();
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    content_type: Some("image/png".into()),
+                    ..Default::default()
+                })
+                .0,
+                [inscription_id]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    media: Some(Media::Image),
+                    ..Default::default()
+                })
+                .0,
+                [inscription_id]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    content_type: Some("application/json".into()),
+                    ..Default::default()
+                })
+                .0,
+                []
+            );
+        }
+    }
+
+    #[test]
+    fn inscriptions_can_be_queried() {
+        for context in Context::configurations() {
+            context.mine_blocks(1);
+
+            let inscribe = |input: usize, witness: Witness, fee: u64| {
+                let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+                    inputs: &[(input, 0, 0)],
+                    witness,
+                    fee,
+                    ..Default::default()
+                });
+                context.mine_blocks(1);
+                InscriptionId::from(txid)
+            };
+
+            let a = inscribe(
+                1,
+                inscription("text/plain;charset=utf-8", "a").to_witness(),
+                10,
+            );
+            let b = inscribe(2, inscription("image/png", [1; 100]).to_witness(), 30);
+            let c = inscribe(
+                3,
+                inscription("text/plain;charset=utf-8", "c").to_witness(),
+                20,
+            );
+            let d = inscribe(
+                4,
+                inscription("text/plain;charset=utf-8", "d").to_witness(),
+                40,
+            );
+
+            let query = |query: InscriptionQuery| context.index.query_inscriptions(&query).unwrap();
+
+            assert_eq!(
+                query(InscriptionQuery::default()),
+                (vec![d, c, b, a], None)
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    content_type: Some("text/plain;charset=utf-8".into()),
+                    ..Default::default()
+                })
+                .0,
+                [d, c, a]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    media: Some(Media::Image),
+                    ..Default::default()
+                })
+                .0,
+                [b]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    protocol_id: Some("ord-v0".into()),
+                    ..Default::default()
+                })
+                .0,
+                [d, c, b, a]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    min_height: Some(3),
+                    max_height: Some(4),
+                    ascending: true,
+                    ..Default::default()
+                })
+                .0,
+                [b, c]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    order: Order::Fee,
+                    ..Default::default()
+                })
+                .0,
+                [d, b, c, a]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    order: Order::Fee,
+                    min_fee: Some(15),
+                    max_fee: Some(35),
+                    ascending: true,
+                    ..Default::default()
+                })
+                .0,
+                [c, b]
+            );
+
+            assert_eq!(
+                query(InscriptionQuery {
+                    rarity: Some(Rarity::Uncommon),
+                    ..Default::default()
+                })
+                .0,
+                if context.index.has_sat_index().unwrap() {
+                    vec![d, c, b, a]
+                } else {
+                    Vec::new()
+                }
+            );
+
+            for order in [Order::Number, Order::Fee] {
+                for ascending in [false, true] {
+                    let mut pages = Vec::new();
+                    let mut cursor = None;
+
+                    loop {
+                        let (inscriptions, next) = query(InscriptionQuery {
+                            order,
+                            ascending,
+                            cursor,
+                            limit: Some(3),
+                            ..Default::default()
+                        });
+
+                        pages.push(inscriptions);
+
+                        match next {
+                            Some(next) => cursor = Some(next),
+                            None => break,
+                        }
+                    }
+
+                    assert_eq!(pages.len(), 2);
+
+                    assert_eq!(
+                        pages.concat(),
+                        query(InscriptionQuery {
+                            order,
+                            ascending,
+                            ..Default::default()
+                        })
+                        .0
+                    );
+                }
+            }
+
+            // Pages stop after visiting a bounded number of inscriptions, even if none
+            // of them match
+            let mut pages = Vec::new();
+            let mut cursor = None;
+
+            loop {
+                let (inscriptions, next) = InscriptionQuery {
+                    min_height: Some(4),
+                    ascending: true,
+                    cursor,
+                    ..Default::default()
+                }
+                .run_bounded(&context.index, 2)
+                .unwrap();
+
+                pages.push(inscriptions);
+
+                match next {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+
+            assert_eq!(pages, [Vec::new(), vec![c, d]]);
+        }
+    }
+
     #[test]
     fn unsynced_index_fails() {
         for context in Context::configurations() {
@@ -2254,4 +4625,83 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn outputs_are_only_indexed_by_address_with_index_addresses_flag() {
+        let context = Context::builder().build();
+        context.mine_blocks(1);
+        assert_eq!(
+            context.index.get_address_outputs(&Script::new()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn outputs_with_inscriptions_are_indexed_by_address() {
+        let context = Context::builder().arg("--index-addresses").build();
+        context.mine_blocks(1);
+
+        let outputs = || {
+            context
+                .index
+                .get_address_outputs(&Script::new())
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(outputs(), []);
+
+        let inscribe = context.rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            witness: inscription("text/plain", "hello").to_witness(),
+            ..Default::default()
+        });
+        context.mine_blocks(1);
+
+        assert_eq!(outputs(), [OutPoint::new(inscribe, 0)]);
+
+        let transfer = context.rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(2, 1, 0)],
+            ..Default::default()
+        });
+        context.mine_blocks(1);
+
+        assert_eq!(outputs(), [OutPoint::new(transfer, 0)]);
+    }
+
+    #[test]
+    fn outputs_with_rare_sats_are_indexed_by_address() {
+        let context = Context::builder()
+            .args(["--index-addresses", "--index-sats"])
+            .build();
+        let coinbase = context.mine_blocks(1)[0].txdata[0].txid();
+
+        let outputs = || {
+            context
+                .index
+                .get_address_outputs(&Script::new())
+                .unwrap()
+                .unwrap()
+        };
+
+        assert!(outputs().contains(&OutPoint::new(coinbase, 0)));
+
+        let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            outputs: 2,
+            ..Default::default()
+        });
+        context.mine_blocks(1);
+
+        assert!(!outputs().contains(&OutPoint::new(coinbase, 0)));
+        assert!(outputs().contains(&OutPoint::new(txid, 0)));
+        assert!(!outputs().contains(&OutPoint::new(txid, 1)));
+        assert_eq!(
+            context
+                .index
+                .get_rare_sats_on_output(OutPoint::new(txid, 0))
+                .unwrap(),
+            [Sat(50 * COIN_VALUE)]
+        );
+    }
 }
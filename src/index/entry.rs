@@ -22,6 +22,38 @@ impl Entry for BlockHash {
     }
 }
 
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) struct BidEntry {
+    pub(crate) registered: u64,
+    pub(crate) expires: u64,
+}
+
+pub(crate) type BidEntryValue = (u64, u64);
+
+impl BidEntry {
+    pub(crate) fn is_active(
+        self,
+        height: u64,
+    ) -> bool {
+        height < self.expires
+    }
+}
+
+impl Entry for BidEntry {
+    type Value = BidEntryValue;
+
+    fn load((registered, expires): BidEntryValue) -> Self {
+        Self {
+            registered,
+            expires,
+        }
+    }
+
+    fn store(self) -> Self::Value {
+        (self.registered, self.expires)
+    }
+}
+
 pub(crate) struct InscriptionEntry {
     pub(crate) fee: u64,
     pub(crate) height: u64,
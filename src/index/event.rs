@@ -0,0 +1,193 @@
+use super::*;
+
+/// Something that happened to an inscription while a block was indexed, or to
+/// the index itself.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Event {
+    /// An inscription was made, at `satpoint`, or without one if it's a
+    /// non-tracked `pub` inscription.
+    Inscribed {
+        height: u64,
+        inscription_id: InscriptionId,
+        satpoint: Option<SatPoint>,
+    },
+    Transferred {
+        height: u64,
+        inscription_id: InscriptionId,
+        old_satpoint: SatPoint,
+        new_satpoint: SatPoint,
+    },
+    /// An inscription was spent as fee, and not claimed by the coinbase
+    /// transaction, so it's lost for good.
+    Burned {
+        height: u64,
+        inscription_id: InscriptionId,
+        old_satpoint: SatPoint,
+        new_satpoint: SatPoint,
+    },
+    /// The index was rolled back to `height` after a reorg, so the events of
+    /// blocks at or after `height` no longer apply. Subscribers that fall
+    /// behind are sent this too, since they may have missed a reorg.
+    Reorged { height: u64 },
+}
+
+const INSCRIBED: u8 = 0;
+const TRANSFERRED: u8 = 1;
+const BURNED: u8 = 2;
+const PUB_INSCRIBED: u8 = 3;
+
+/// Kind byte, inscription id, old satpoint and new satpoint
+const RECORD_LEN: usize = 1 + 36 + 44 + 44;
+
+impl Event {
+    pub(crate) fn height(&self) -> u64 {
+        match self {
+            Self::Inscribed { height, .. }
+            | Self::Transferred { height, .. }
+            | Self::Burned { height, .. }
+            | Self::Reorged { height } => *height,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Inscribed { .. } => "inscribed",
+            Self::Transferred { .. } => "transferred",
+            Self::Burned { .. } => "burned",
+            Self::Reorged { .. } => "reorged",
+        }
+    }
+
+    /// The events of a block, as stored in `HEIGHT_TO_EVENTS`. Reorgs aren't
+    /// stored, since they're undone along with the blocks they void.
+    pub(super) fn store_all(events: &[Event]) -> Vec<u8> {
+        let mut value = Vec::with_capacity(events.len() * RECORD_LEN);
+
+        for event in events {
+            let (kind, inscription_id, old_satpoint, new_satpoint) = match *event {
+                Self::Inscribed {
+                    inscription_id,
+                    satpoint: Some(satpoint),
+                    ..
+                } => (INSCRIBED, inscription_id, satpoint, satpoint),
+                Self::Inscribed {
+                    inscription_id,
+                    satpoint: None,
+                    ..
+                } => {
+                    let satpoint = SatPoint {
+                        outpoint: OutPoint::null(),
+                        offset: 0,
+                    };
+                    (PUB_INSCRIBED, inscription_id, satpoint, satpoint)
+                },
+                Self::Transferred {
+                    inscription_id,
+                    old_satpoint,
+                    new_satpoint,
+                    ..
+                } => (TRANSFERRED, inscription_id, old_satpoint, new_satpoint),
+                Self::Burned {
+                    inscription_id,
+                    old_satpoint,
+                    new_satpoint,
+                    ..
+                } => (BURNED, inscription_id, old_satpoint, new_satpoint),
+                Self::Reorged { .. } => continue,
+            };
+
+            value.push(kind);
+            value.extend_from_slice(&inscription_id.store());
+            value.extend_from_slice(&old_satpoint.store());
+            value.extend_from_slice(&new_satpoint.store());
+        }
+
+        value
+    }
+
+    pub(super) fn load_all(
+        height: u64,
+        value: &[u8],
+    ) -> Vec<Event> {
+        value
+            .chunks_exact(RECORD_LEN)
+            .map(|record| {
+                let inscription_id = InscriptionId::load(record[1..37].try_into().unwrap());
+                let old_satpoint = SatPoint::load(record[37..81].try_into().unwrap());
+                let new_satpoint = SatPoint::load(record[81..].try_into().unwrap());
+
+                match record[0] {
+                    INSCRIBED => Self::Inscribed {
+                        height,
+                        inscription_id,
+                        satpoint: Some(new_satpoint),
+                    },
+                    PUB_INSCRIBED => Self::Inscribed {
+                        height,
+                        inscription_id,
+                        satpoint: None,
+                    },
+                    TRANSFERRED => Self::Transferred {
+                        height,
+                        inscription_id,
+                        old_satpoint,
+                        new_satpoint,
+                    },
+                    _ => Self::Burned {
+                        height,
+                        inscription_id,
+                        old_satpoint,
+                        new_satpoint,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_round_trip() {
+        let events = vec![
+            Event::Inscribed {
+                height: 1,
+                inscription_id: inscription_id(1),
+                satpoint: Some(satpoint(1, 0)),
+            },
+            Event::Inscribed {
+                height: 1,
+                inscription_id: inscription_id(4),
+                satpoint: None,
+            },
+            Event::Transferred {
+                height: 1,
+                inscription_id: inscription_id(2),
+                old_satpoint: satpoint(2, 0),
+                new_satpoint: satpoint(3, 1),
+            },
+            Event::Burned {
+                height: 1,
+                inscription_id: inscription_id(3),
+                old_satpoint: satpoint(4, 0),
+                new_satpoint: SatPoint {
+                    outpoint: OutPoint::null(),
+                    offset: 5,
+                },
+            },
+        ];
+
+        assert_eq!(Event::load_all(1, &Event::store_all(&events)), events);
+    }
+
+    #[test]
+    fn reorgs_are_not_stored() {
+        assert_eq!(
+            Event::store_all(&[Event::Reorged { height: 1 }]),
+            Vec::<u8>::new()
+        );
+    }
+}
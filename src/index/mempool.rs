@@ -0,0 +1,226 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PendingKind {
+    Inscription,
+    Transfer,
+}
+
+/// An inscription made or moved by a transaction in the mempool, and where it
+/// will be once that transaction confirms. Inscriptions that will be lost to
+/// fees have no satpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub(crate) struct Pending {
+    pub(crate) kind: PendingKind,
+    pub(crate) satpoint: Option<SatPoint>,
+    pub(crate) txid: Txid,
+}
+
+impl Pending {
+    pub(crate) fn destination(&self) -> String {
+        match self.satpoint {
+            Some(satpoint) => satpoint.to_string(),
+            None => "fees".into(),
+        }
+    }
+}
+
+/// The transactions in bitcoind's mempool as of the last scan, which are kept
+/// so that each scan only fetches the transactions that arrived since.
+#[derive(Default)]
+pub(super) struct Mempool {
+    pub(super) transactions: HashMap<Txid, Transaction>,
+}
+
+impl Mempool {
+    /// Project where the transactions in bitcoind's mempool will move
+    /// inscriptions, as if they were all mined on top of the index, in the
+    /// same way that `InscriptionUpdater` moves them when they are.
+    pub(super) fn scan(
+        &mut self,
+        index: &Index,
+    ) -> Result<BTreeMap<InscriptionId, Pending>> {
+        let txids = index
+            .client
+            .get_raw_mempool()?
+            .into_iter()
+            .collect::<HashSet<Txid>>();
+
+        // Transactions that were mined, replaced, or evicted since the last scan
+        self.transactions.retain(|txid, _| txids.contains(txid));
+
+        for txid in txids {
+            if self.transactions.contains_key(&txid) {
+                continue;
+            }
+
+            // Transactions may leave the mempool while it's scanned
+            if let Some(tx) = index
+                .client
+                .get_raw_transaction(&txid, None)
+                .into_option()?
+            {
+                self.transactions.insert(txid, tx);
+            }
+        }
+
+        let transactions = &self.transactions;
+
+        let rtx = index.database.begin_read()?;
+        let satpoint_to_id = rtx.open_table(SATPOINT_TO_INSCRIPTION_ID)?;
+
+        let mut pending = BTreeMap::new();
+
+        // Inscriptions on the outputs of mempool transactions, by offset
+        let mut unconfirmed: HashMap<OutPoint, Vec<(u64, InscriptionId)>> = HashMap::new();
+
+        for txid in Self::parents_first(transactions) {
+            let tx = &transactions[&txid];
+
+            let mut inputs = Vec::new();
+            for tx_in in &tx.input {
+                let outpoint = tx_in.previous_output;
+                inputs.push(match unconfirmed.remove(&outpoint) {
+                    Some(inscriptions) => inscriptions,
+                    None => Index::inscriptions_on_output(&satpoint_to_id, outpoint)?
+                        .map(|(satpoint, inscription_id)| (satpoint.offset, inscription_id))
+                        .collect(),
+                });
+            }
+
+            let envelopes = Inscription::from_transaction(tx);
+
+            // Only the values of inputs before the last one with inscriptions on it are
+            // needed, which saves looking up the value of most inputs, unless the
+            // transaction makes inscriptions, which are only made on sats its inputs
+            // have
+            let inscribed_inputs = if !envelopes.is_empty() {
+                tx.input.len()
+            } else {
+                inputs
+                    .iter()
+                    .rposition(|inscriptions| !inscriptions.is_empty())
+                    .map(|last| last + 1)
+                    .unwrap_or_default()
+            };
+
+            let mut flotsam = Vec::new();
+            let mut input_value = 0;
+            for (tx_in, inscriptions) in tx.input.iter().zip(inputs).take(inscribed_inputs) {
+                for (offset, inscription_id) in inscriptions {
+                    flotsam.push((input_value + offset, inscription_id, PendingKind::Transfer));
+                }
+
+                input_value += Self::value(index, transactions, tx_in.previous_output)?;
+            }
+
+            for (i, inscription) in envelopes.into_iter().enumerate() {
+                // Non-tracked inscriptions aren't on a sat
+                if !inscription.tracking() {
+                    continue;
+                }
+
+                let Some(offset) = Inscription::offset(tx, i, input_value) else {
+                    continue;
+                };
+
+                // Sats can't be inscribed again, nor hold more than one inscription
+                if flotsam.iter().any(|(taken, _, _)| *taken == offset) {
+                    continue;
+                }
+
+                flotsam.push((
+                    offset,
+                    InscriptionId {
+                        txid,
+                        index: i.try_into().unwrap(),
+                    },
+                    PendingKind::Inscription,
+                ));
+            }
+
+            let (located, lost) =
+                Index::locate_flotsam(txid, &tx.output, flotsam, |(offset, _, _)| *offset);
+
+            for ((_, inscription_id, kind), satpoint) in located
+                .into_iter()
+                .map(|(flotsam, satpoint)| (flotsam, Some(satpoint)))
+                .chain(lost.into_iter().map(|flotsam| (flotsam, None)))
+            {
+                if let Some(satpoint) = satpoint {
+                    unconfirmed
+                        .entry(satpoint.outpoint)
+                        .or_default()
+                        .push((satpoint.offset, inscription_id));
+                }
+
+                // A chain of mempool transactions is reported as one pending move, made by
+                // the first of them, to where the last of them puts the inscription
+                pending
+                    .entry(inscription_id)
+                    .or_insert(Pending {
+                        kind,
+                        satpoint,
+                        txid,
+                    })
+                    .satpoint = satpoint;
+            }
+        }
+
+        Ok(pending)
+    }
+
+    /// The txids of `transactions`, with every transaction after the ones it
+    /// spends.
+    fn parents_first(transactions: &HashMap<Txid, Transaction>) -> Vec<Txid> {
+        fn visit(
+            txid: Txid,
+            transactions: &HashMap<Txid, Transaction>,
+            visited: &mut HashSet<Txid>,
+            ordered: &mut Vec<Txid>,
+        ) {
+            if !visited.insert(txid) {
+                return;
+            }
+
+            for tx_in in &transactions[&txid].input {
+                if transactions.contains_key(&tx_in.previous_output.txid) {
+                    visit(tx_in.previous_output.txid, transactions, visited, ordered);
+                }
+            }
+
+            ordered.push(txid);
+        }
+
+        let mut txids = transactions.keys().copied().collect::<Vec<Txid>>();
+        txids.sort();
+
+        let mut visited = HashSet::new();
+        let mut ordered = Vec::new();
+        for txid in txids {
+            visit(txid, transactions, &mut visited, &mut ordered);
+        }
+
+        ordered
+    }
+
+    fn value(
+        index: &Index,
+        transactions: &HashMap<Txid, Transaction>,
+        outpoint: OutPoint,
+    ) -> Result<u64> {
+        let vout = usize::try_from(outpoint.vout).unwrap();
+
+        let output = match transactions.get(&outpoint.txid) {
+            Some(tx) => tx.output.get(vout).cloned(),
+            None => index
+                .get_transaction(outpoint.txid)?
+                .and_then(|tx| tx.output.into_iter().nth(vout)),
+        };
+
+        Ok(output
+            .ok_or_else(|| anyhow!("failed to get transaction output {outpoint}"))?
+            .value)
+    }
+}
@@ -0,0 +1,442 @@
+use {
+    super::*,
+    base64::Engine,
+    clap::ValueEnum,
+};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+/// How many inscriptions are visited per page, so that filters without an
+/// index to visit them in, like rarity, or minimum height in ascending order,
+/// can't scan the whole index in one request.
+const MAX_VISITED: usize = 10 * MAX_LIMIT;
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Order {
+    #[default]
+    Number,
+    Fee,
+}
+
+impl Display for Order {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        match self {
+            Self::Number => write!(f, "number"),
+            Self::Fee => write!(f, "fee"),
+        }
+    }
+}
+
+/// Where the next page of a query's results starts. Cursors are opaque, and
+/// are only valid for the query that returned them.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) struct Cursor {
+    order: Order,
+    fee: u64,
+    number: u64,
+}
+
+impl Display for Cursor {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        let mut bytes = vec![match self.order {
+            Order::Number => 0,
+            Order::Fee => 1,
+        }];
+        bytes.extend_from_slice(&self.fee.to_be_bytes());
+        bytes.extend_from_slice(&self.number.to_be_bytes());
+
+        write!(
+            f,
+            "{}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+        )
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(s)
+            .ok()
+            .filter(|bytes| bytes.len() == 17)
+            .ok_or_else(|| anyhow!("invalid cursor: {s}"))?;
+
+        Ok(Self {
+            order: match bytes[0] {
+                0 => Order::Number,
+                1 => Order::Fee,
+                _ => bail!("invalid cursor: {s}"),
+            },
+            fee: u64::from_be_bytes(bytes[1..9].try_into().unwrap()),
+            number: u64::from_be_bytes(bytes[9..].try_into().unwrap()),
+        })
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(DeserializeFromStr::deserialize(deserializer)?.0)
+    }
+}
+
+/// A query for inscriptions matching every filter given, used by both
+/// `/inscriptions` and `arb inscriptions query`.
+#[derive(Debug, Default, Parser, Deserialize)]
+#[serde(default)]
+pub(crate) struct InscriptionQuery {
+    #[clap(long, help = "Only return inscriptions with <CONTENT_TYPE>.")]
+    pub(crate) content_type: Option<String>,
+    #[clap(long, arg_enum, help = "Only return inscriptions of <MEDIA> kind.")]
+    pub(crate) media: Option<Media>,
+    #[clap(
+        long,
+        help = "Only return inscriptions of protocol <PROTOCOL_ID>, e.g. `ord-v0`."
+    )]
+    pub(crate) protocol_id: Option<String>,
+    #[clap(long, help = "Only return inscriptions made at or after <MIN_HEIGHT>.")]
+    pub(crate) min_height: Option<u64>,
+    #[clap(
+        long,
+        help = "Only return inscriptions made at or before <MAX_HEIGHT>."
+    )]
+    pub(crate) max_height: Option<u64>,
+    #[clap(
+        long,
+        help = "Only return inscriptions that paid at least <MIN_FEE> sats."
+    )]
+    pub(crate) min_fee: Option<u64>,
+    #[clap(
+        long,
+        help = "Only return inscriptions that paid at most <MAX_FEE> sats."
+    )]
+    pub(crate) max_fee: Option<u64>,
+    #[clap(
+        long,
+        help = "Only return inscriptions on sats of <RARITY>. Requires index created with \
+    `--index-sats` flag."
+    )]
+    pub(crate) rarity: Option<Rarity>,
+    #[clap(
+        long,
+        help = "Only return tracked inscriptions if <TRACKED> is `true`, or non-tracked `pub` \
+    inscriptions, which are numbered separately, if it's `false`. [default: true]"
+    )]
+    pub(crate) tracked: Option<bool>,
+    #[clap(
+        long,
+        arg_enum,
+        default_value = "number",
+        help = "Order inscriptions by <ORDER>, newest or highest first."
+    )]
+    pub(crate) order: Order,
+    #[clap(long, help = "Return oldest or lowest first.")]
+    pub(crate) ascending: bool,
+    #[clap(long, help = "Return the page of results starting at <CURSOR>.")]
+    pub(crate) cursor: Option<Cursor>,
+    #[clap(
+        long,
+        help = "Return at most <LIMIT> inscriptions per page. [default: 100, maximum: 1000]"
+    )]
+    pub(crate) limit: Option<usize>,
+}
+
+impl InscriptionQuery {
+    /// Why the query can't be run, if it can't.
+    pub(crate) fn error(&self) -> Option<String> {
+        if let Some(cursor) = self.cursor {
+            if cursor.order != self.order {
+                return Some(format!(
+                    "cursor is for inscriptions ordered by {}",
+                    cursor.order
+                ));
+            }
+        }
+
+        if self
+            .limit
+            .map_or(false, |limit| limit == 0 || limit > MAX_LIMIT)
+        {
+            return Some(format!("limit must be between 1 and {MAX_LIMIT}"));
+        }
+
+        None
+    }
+
+    /// The inscriptions matching the query, and a cursor to the next page of
+    /// them, if there is one.
+    ///
+    /// Inscriptions are visited in the order of the most selective index
+    /// available, and the remaining filters are checked against the other
+    /// indexes, so queries that only filter on content type, media or protocol
+    /// id, in order of number, only visit inscriptions that match. Other
+    /// queries stop after visiting `MAX_VISITED` inscriptions, so a page may
+    /// have fewer than `limit` inscriptions, or none, and still have a cursor.
+    pub(super) fn run(
+        &self,
+        index: &Index,
+    ) -> Result<(Vec<InscriptionId>, Option<Cursor>)> {
+        self.run_bounded(index, MAX_VISITED)
+    }
+
+    pub(super) fn run_bounded(
+        &self,
+        index: &Index,
+        max_visited: usize,
+    ) -> Result<(Vec<InscriptionId>, Option<Cursor>)> {
+        if let Some(error) = self.error() {
+            bail!(error);
+        }
+
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+
+        let rtx = index.database.begin_read()?;
+
+        // Non-tracked inscriptions have their own numbers, and their own indexes
+        let (
+            content_type_to_number,
+            fee_to_number,
+            id_to_entry,
+            media_to_number,
+            number_to_id,
+            protocol_id_to_number,
+        ) = if self.tracked.unwrap_or(true) {
+            (
+                rtx.open_table(CONTENT_TYPE_TO_INSCRIPTION_NUMBER)?,
+                rtx.open_table(FEE_TO_INSCRIPTION_NUMBER)?,
+                rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?,
+                rtx.open_table(MEDIA_TO_INSCRIPTION_NUMBER)?,
+                rtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?,
+                rtx.open_table(PROTOCOL_ID_TO_INSCRIPTION_NUMBER)?,
+            )
+        } else {
+            (
+                rtx.open_table(PUB_CONTENT_TYPE_TO_INSCRIPTION_NUMBER)?,
+                rtx.open_table(PUB_FEE_TO_INSCRIPTION_NUMBER)?,
+                rtx.open_table(PUB_INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?,
+                rtx.open_table(PUB_MEDIA_TO_INSCRIPTION_NUMBER)?,
+                rtx.open_table(PUB_INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?,
+                rtx.open_table(PUB_PROTOCOL_ID_TO_INSCRIPTION_NUMBER)?,
+            )
+        };
+
+        let media = self.media.map(|media| media.to_string());
+
+        // Inscriptions are visited in order of fee and number, with a fee of zero
+        // when ordering by number
+        let (mut start, mut end) = match self.order {
+            Order::Number => ((0, 0), (0, u64::MAX)),
+            Order::Fee => (
+                (self.min_fee.unwrap_or(0), 0),
+                (self.max_fee.unwrap_or(u64::MAX), u64::MAX),
+            ),
+        };
+
+        if let Some(cursor) = self.cursor {
+            if self.ascending {
+                start = start.max((cursor.fee, cursor.number));
+            } else {
+                end = end.min((cursor.fee, cursor.number));
+            }
+        }
+
+        if start > end {
+            return Ok((Vec::new(), None));
+        }
+
+        let keys: Box<dyn Iterator<Item = (u64, u64)>> = match self.order {
+            Order::Fee => self.directed(
+                fee_to_number
+                    .range(start..=end)?
+                    .map(|(key, _)| key.value()),
+            ),
+            Order::Number => {
+                let secondary = [
+                    (&self.content_type, &content_type_to_number),
+                    (&media, &media_to_number),
+                    (&self.protocol_id, &protocol_id_to_number),
+                ]
+                .into_iter()
+                .find_map(|(value, table)| Some((value.as_deref()?, table)));
+
+                match secondary {
+                    Some((value, table)) => self.directed(
+                        table
+                            .range((value, start.1)..=(value, end.1))?
+                            .map(|(key, _)| (0, key.value().1)),
+                    ),
+                    None => self.directed(
+                        number_to_id
+                            .range(start.1..=end.1)?
+                            .map(|(number, _)| (0, number.value())),
+                    ),
+                }
+            },
+        };
+
+        let mut inscriptions = Vec::new();
+
+        'keys: for (visited, (fee, number)) in keys.enumerate() {
+            if visited == max_visited {
+                return Ok((
+                    inscriptions,
+                    Some(Cursor {
+                        order: self.order,
+                        fee,
+                        number,
+                    }),
+                ));
+            }
+
+            for (value, table) in [
+                (&self.content_type, &content_type_to_number),
+                (&media, &media_to_number),
+                (&self.protocol_id, &protocol_id_to_number),
+            ] {
+                if let Some(value) = value {
+                    if table.get(&(value.as_str(), number))?.is_none() {
+                        continue 'keys;
+                    }
+                }
+            }
+
+            let inscription_id = InscriptionId::load(
+                *number_to_id
+                    .get(&number)?
+                    .ok_or_else(|| anyhow!("failed to get id of inscription {number}"))?
+                    .value(),
+            );
+
+            let entry = InscriptionEntry::load(
+                id_to_entry
+                    .get(&inscription_id.store())?
+                    .ok_or_else(|| anyhow!("failed to get entry of inscription {inscription_id}"))?
+                    .value(),
+            );
+
+            // Inscriptions are numbered in order of height, so once one is outside of
+            // the height range in the direction of the query, all later ones are too
+            let before = self.min_height.map_or(false, |min| entry.height < min);
+            let after = self.max_height.map_or(false, |max| entry.height > max);
+
+            if self.order == Order::Number && (before && !self.ascending || after && self.ascending)
+            {
+                break;
+            }
+
+            if before
+                || after
+                || self.min_fee.map_or(false, |min| entry.fee < min)
+                || self.max_fee.map_or(false, |max| entry.fee > max)
+            {
+                continue;
+            }
+
+            if let Some(rarity) = &self.rarity {
+                if entry.sat.map(Sat::rarity).as_ref() != Some(rarity) {
+                    continue;
+                }
+            }
+
+            if inscriptions.len() == limit {
+                return Ok((
+                    inscriptions,
+                    Some(Cursor {
+                        order: self.order,
+                        fee,
+                        number,
+                    }),
+                ));
+            }
+
+            inscriptions.push(inscription_id);
+        }
+
+        Ok((inscriptions, None))
+    }
+
+    fn directed<'a>(
+        &self,
+        keys: impl DoubleEndedIterator<Item = (u64, u64)> + 'a,
+    ) -> Box<dyn Iterator<Item = (u64, u64)> + 'a> {
+        if self.ascending {
+            Box::new(keys)
+        } else {
+            Box::new(keys.rev())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_round_trip() {
+        for cursor in [
+            Cursor {
+                order: Order::Number,
+                fee: 0,
+                number: 7,
+            },
+            Cursor {
+                order: Order::Fee,
+                fee: u64::MAX,
+                number: 0,
+            },
+        ] {
+            assert_eq!(cursor.to_string().parse::<Cursor>().unwrap(), cursor);
+        }
+    }
+
+    #[test]
+    fn invalid_cursors() {
+        assert!("".parse::<Cursor>().is_err());
+        assert!("!".parse::<Cursor>().is_err());
+        assert!(base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode([2; 17])
+            .parse::<Cursor>()
+            .is_err());
+    }
+
+    #[test]
+    fn cursors_must_match_order() {
+        assert_eq!(
+            InscriptionQuery {
+                order: Order::Fee,
+                cursor: Some(Cursor {
+                    order: Order::Number,
+                    fee: 0,
+                    number: 0,
+                }),
+                ..Default::default()
+            }
+            .error(),
+            Some("cursor is for inscriptions ordered by number".into())
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use {
+    super::*,
+    redb::Savepoint,
+};
+
+/// How many blocks apart savepoints are taken.
+const SAVEPOINT_INTERVAL: u64 = 10;
+
+/// How many savepoints are kept, so that reorgs at least `(MAX_SAVEPOINTS - 1)
+/// * SAVEPOINT_INTERVAL` blocks deep can be recovered from.
+const MAX_SAVEPOINTS: usize = 3;
+
+/// Savepoints are only taken when the index is this close to the chain tip,
+/// since reorgs don't happen deeper than that, and taking them while catching
+/// up would only slow indexing down.
+const CHAIN_TIP_DISTANCE: u64 = 21;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ReorgError {
+    /// The last `depth` blocks indexed before `height` are no longer in the
+    /// best chain, and a savepoint from before them is available.
+    Recoverable {
+        height: u64,
+        depth: u64,
+    },
+    Unrecoverable,
+}
+
+impl Display for ReorgError {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        match self {
+            Self::Recoverable { height, depth } => {
+                write!(f, "{depth} block deep reorg detected at height {height}")
+            },
+            Self::Unrecoverable => write!(f, "unrecoverable reorg detected"),
+        }
+    }
+}
+
+impl std::error::Error for ReorgError {}
+
+/// Savepoints of the index, each taken before the block at its height was
+/// indexed, oldest first. The version of redb used can't persist savepoints,
+/// so they only live as long as the process. The first is taken by the first
+/// update after the index is opened, if it's close to the chain tip, so reorgs
+/// of blocks indexed before then can't be recovered from, and need the index
+/// to be rebuilt.
+#[derive(Default)]
+pub(super) struct Savepoints(Mutex<VecDeque<(u64, Savepoint)>>);
+
+pub(super) struct Reorg;
+
+impl Reorg {
+    /// Check that `prev_blockhash`, the previous block hash of the block at
+    /// `height`, is the hash of the last block indexed. If it isn't, walk back
+    /// until a block indexed is also in bitcoind's best chain, to find how deep
+    /// the reorg is.
+    pub(super) fn detect_reorg(
+        index: &Index,
+        height_to_block_hash: &Table<u64, &BlockHashValue>,
+        prev_blockhash: BlockHash,
+        height: u64,
+    ) -> Result {
+        let Some(prev_height) = height.checked_sub(1) else {
+            return Ok(());
+        };
+
+        let indexed_block_hash = |height: u64| -> Result<Option<BlockHash>> {
+            Ok(height_to_block_hash
+                .get(&height)?
+                .map(|hash| BlockHash::load(*hash.value())))
+        };
+
+        if indexed_block_hash(prev_height)? == Some(prev_blockhash) {
+            return Ok(());
+        }
+
+        let oldest_savepoint = index.savepoints.oldest();
+
+        for depth in 1..=prev_height {
+            let ancestor = prev_height - depth;
+
+            if oldest_savepoint.map_or(true, |oldest| ancestor + 1 < oldest) {
+                break;
+            }
+
+            if indexed_block_hash(ancestor)? == Some(index.client.get_block_hash(ancestor)?) {
+                return Err(ReorgError::Recoverable { height, depth }.into());
+            }
+        }
+
+        Err(ReorgError::Unrecoverable.into())
+    }
+
+    /// Roll the index back to the newest savepoint taken before the first block
+    /// of a reorg `depth` blocks deep at `height`, so that the blocks of the
+    /// new best chain are indexed from there, returning the height of the
+    /// savepoint.
+    pub(super) fn handle_reorg(
+        index: &Index,
+        height: u64,
+        depth: u64,
+    ) -> Result<u64> {
+        log::info!("rolling back index after {depth} block deep reorg at height {height}");
+
+        let fork = height - depth;
+
+        let mut savepoints = index.savepoints.0.lock().unwrap();
+
+        // Restoring a savepoint invalidates the savepoints taken after it
+        while savepoints
+            .back()
+            .map_or(false, |(savepoint_height, _savepoint)| {
+                *savepoint_height > fork
+            })
+        {
+            savepoints.pop_back();
+        }
+
+        let Some((savepoint_height, savepoint)) = savepoints.back() else {
+            return Err(ReorgError::Unrecoverable.into());
+        };
+
+        let mut wtx = index.begin_write()?;
+        wtx.restore_savepoint(savepoint)?;
+        Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
+        wtx.commit()?;
+
+        log::info!("rolled back index to height {savepoint_height}");
+
+        Ok(*savepoint_height)
+    }
+
+    /// Take a savepoint before indexing the block at `height`, if the index is
+    /// close to the chain tip, and the last savepoint was taken at least
+    /// `SAVEPOINT_INTERVAL` blocks ago.
+    pub(super) fn update_savepoints(
+        index: &Index,
+        height: u64,
+    ) -> Result {
+        let mut savepoints = index.savepoints.0.lock().unwrap();
+
+        if savepoints.back().map_or(false, |(last, _savepoint)| {
+            height < last + SAVEPOINT_INTERVAL
+        }) {
+            return Ok(());
+        }
+
+        if index.client.get_block_count()?.saturating_sub(height) > CHAIN_TIP_DISTANCE {
+            return Ok(());
+        }
+
+        log::debug!("taking savepoint at height {height}");
+
+        let wtx = index.begin_write()?;
+        savepoints.push_back((height, wtx.savepoint()?));
+        wtx.abort()?;
+
+        if savepoints.len() > MAX_SAVEPOINTS {
+            savepoints.pop_front();
+        }
+
+        Ok(())
+    }
+}
+
+impl Savepoints {
+    pub(super) fn oldest(&self) -> Option<u64> {
+        self.0
+            .lock()
+            .unwrap()
+            .front()
+            .map(|(height, _savepoint)| *height)
+    }
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+/// A move of an inscription from one sat point to another, as logged in
+/// `INSCRIPTION_ID_TO_TRANSFERS`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub(crate) struct Transfer {
+    pub(crate) height: u64,
+    /// The transaction that spent the inscription
+    pub(crate) txid: Txid,
+    pub(crate) old_satpoint: SatPoint,
+    pub(crate) new_satpoint: SatPoint,
+    /// Whether the inscription was spent as fee, and so was claimed by the
+    /// coinbase transaction or lost
+    pub(crate) fee_spent: bool,
+}
+
+pub(super) type TransferValue = [u8; 129];
+
+impl Entry for Transfer {
+    type Value = TransferValue;
+
+    fn load(value: Self::Value) -> Self {
+        Self {
+            height: u64::from_be_bytes(value[0..8].try_into().unwrap()),
+            txid: Txid::from_inner(value[8..40].try_into().unwrap()),
+            old_satpoint: SatPoint::load(value[40..84].try_into().unwrap()),
+            new_satpoint: SatPoint::load(value[84..128].try_into().unwrap()),
+            fee_spent: value[128] != 0,
+        }
+    }
+
+    fn store(self) -> Self::Value {
+        let mut value = [0; 129];
+        value[0..8].copy_from_slice(&self.height.to_be_bytes());
+        value[8..40].copy_from_slice(self.txid.as_inner());
+        value[40..84].copy_from_slice(&self.old_satpoint.store());
+        value[84..128].copy_from_slice(&self.new_satpoint.store());
+        value[128] = self.fee_spent.into();
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfers_round_trip() {
+        for transfer in [
+            Transfer {
+                height: 1,
+                txid: txid(1),
+                old_satpoint: satpoint(1, 0),
+                new_satpoint: satpoint(2, 3),
+                fee_spent: false,
+            },
+            Transfer {
+                height: u64::MAX,
+                txid: txid(2),
+                old_satpoint: satpoint(3, 0),
+                new_satpoint: SatPoint {
+                    outpoint: OutPoint::null(),
+                    offset: 5,
+                },
+                fee_spent: true,
+            },
+        ] {
+            assert_eq!(Transfer::load(transfer.store()), transfer);
+        }
+    }
+}
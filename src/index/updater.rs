@@ -37,8 +37,10 @@ impl From<Block> for BlockData {
 }
 
 pub(crate) struct Updater {
+    events: Vec<Event>,
     range_cache: HashMap<OutPointValue, Vec<u8>>,
     height: u64,
+    index_addresses: bool,
     index_sats: bool,
     sat_ranges_since_flush: u64,
     outputs_cached: u64,
@@ -48,6 +50,8 @@ pub(crate) struct Updater {
 
 impl Updater {
     pub(crate) fn update(index: &Index) -> Result {
+        Reorg::update_savepoints(index, index.begin_read()?.block_count()?)?;
+
         let wtx = index.begin_write()?;
 
         let height = wtx
@@ -68,8 +72,10 @@ impl Updater {
             )?;
 
         let mut updater = Self {
+            events: Vec::new(),
             range_cache: HashMap::new(),
             height,
+            index_addresses: index.has_address_index()?,
             index_sats: index.has_sat_index()?,
             sat_ranges_since_flush: 0,
             outputs_cached: 0,
@@ -138,7 +144,7 @@ impl Updater {
             uncommitted += 1;
 
             if uncommitted == 5000 {
-                self.commit(wtx, value_cache)?;
+                self.commit(index, wtx, value_cache)?;
                 value_cache = HashMap::new();
                 uncommitted = 0;
                 wtx = index.begin_write()?;
@@ -170,7 +176,7 @@ impl Updater {
         }
 
         if uncommitted > 0 {
-            self.commit(wtx, value_cache)?;
+            self.commit(index, wtx, value_cache)?;
         }
 
         if let Some(progress_bar) = &mut progress_bar {
@@ -410,20 +416,47 @@ impl Updater {
             block.txdata.len()
         );
 
-        if let Some(prev_height) = self.height.checked_sub(1) {
-            let prev_hash = height_to_block_hash.get(&prev_height)?.unwrap();
-
-            if prev_hash.value() != block.header.prev_blockhash.as_ref() {
-                index.reorged.store(true, atomic::Ordering::Relaxed);
-                return Err(anyhow!("reorg detected at or before {prev_height}"));
-            }
-        }
+        Reorg::detect_reorg(
+            index,
+            &height_to_block_hash,
+            block.header.prev_blockhash,
+            self.height,
+        )?;
 
+        let mut bfs_path_to_inscription_id = wtx.open_table(BFS_PATH_TO_INSCRIPTION_ID)?;
+        let mut bid_name_to_inscription_id = wtx.open_table(BID_NAME_TO_INSCRIPTION_ID)?;
+        let mut bnw_wall_to_inscription_id = wtx.open_table(BNW_WALL_TO_INSCRIPTION_ID)?;
+        let mut child_to_parent = wtx.open_table(CHILD_TO_PARENT)?;
+        let mut content_hash_to_inscription_id = wtx.open_table(CONTENT_HASH_TO_INSCRIPTION_ID)?;
+        let mut content_type_to_inscription_number =
+            wtx.open_table(CONTENT_TYPE_TO_INSCRIPTION_NUMBER)?;
+        let mut fee_to_inscription_number = wtx.open_table(FEE_TO_INSCRIPTION_NUMBER)?;
+        let mut inscription_id_to_bid_entry = wtx.open_table(INSCRIPTION_ID_TO_BID_ENTRY)?;
+        let mut inscription_id_to_bnw_satpoints =
+            wtx.open_table(INSCRIPTION_ID_TO_BNW_SATPOINTS)?;
         let mut inscription_id_to_inscription_entry =
             wtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+        let mut inscription_id_to_protocol_id = wtx.open_table(INSCRIPTION_ID_TO_PROTOCOL_ID)?;
         let mut inscription_id_to_satpoint = wtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+        let mut inscription_id_to_transfers = wtx.open_table(INSCRIPTION_ID_TO_TRANSFERS)?;
+        let mut inscription_id_to_validation_error =
+            wtx.open_table(INSCRIPTION_ID_TO_VALIDATION_ERROR)?;
         let mut inscription_number_to_inscription_id =
             wtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+        let mut media_to_inscription_number = wtx.open_table(MEDIA_TO_INSCRIPTION_NUMBER)?;
+        let mut parent_to_children = wtx.open_table(PARENT_TO_CHILDREN)?;
+        let mut protocol_id_to_inscription_number =
+            wtx.open_table(PROTOCOL_ID_TO_INSCRIPTION_NUMBER)?;
+        let mut pub_content_type_to_inscription_number =
+            wtx.open_table(PUB_CONTENT_TYPE_TO_INSCRIPTION_NUMBER)?;
+        let mut pub_fee_to_inscription_number = wtx.open_table(PUB_FEE_TO_INSCRIPTION_NUMBER)?;
+        let mut pub_inscription_id_to_inscription_entry =
+            wtx.open_table(PUB_INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+        let mut pub_inscription_number_to_inscription_id =
+            wtx.open_table(PUB_INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+        let mut pub_media_to_inscription_number = wtx.open_table(PUB_MEDIA_TO_INSCRIPTION_NUMBER)?;
+        let mut pub_protocol_id_to_inscription_number =
+            wtx.open_table(PUB_PROTOCOL_ID_TO_INSCRIPTION_NUMBER)?;
         let mut sat_to_inscription_id = wtx.open_table(SAT_TO_INSCRIPTION_ID)?;
         let mut satpoint_to_inscription_id = wtx.open_table(SATPOINT_TO_INSCRIPTION_ID)?;
         let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
@@ -435,12 +468,34 @@ impl Updater {
 
         let mut inscription_updater = InscriptionUpdater::new(
             self.height,
+            index.chain,
+            &mut bfs_path_to_inscription_id,
+            &mut bid_name_to_inscription_id,
+            &mut bnw_wall_to_inscription_id,
+            &mut child_to_parent,
+            &mut content_hash_to_inscription_id,
+            &mut content_type_to_inscription_number,
+            &mut fee_to_inscription_number,
+            &mut inscription_id_to_bid_entry,
+            &mut inscription_id_to_bnw_satpoints,
             &mut inscription_id_to_satpoint,
+            &mut inscription_id_to_transfers,
             value_receiver,
             &mut inscription_id_to_inscription_entry,
+            &mut inscription_id_to_protocol_id,
+            &mut inscription_id_to_validation_error,
             lost_sats,
+            &mut media_to_inscription_number,
             &mut inscription_number_to_inscription_id,
             &mut outpoint_to_value,
+            &mut parent_to_children,
+            &mut protocol_id_to_inscription_number,
+            &mut pub_content_type_to_inscription_number,
+            &mut pub_fee_to_inscription_number,
+            &mut pub_inscription_id_to_inscription_entry,
+            &mut pub_inscription_number_to_inscription_id,
+            &mut pub_media_to_inscription_number,
+            &mut pub_protocol_id_to_inscription_number,
             &mut sat_to_inscription_id,
             &mut satpoint_to_inscription_id,
             block.header.time,
@@ -551,6 +606,18 @@ impl Updater {
 
         statistic_to_count.insert(&Statistic::LostSats.key(), &lost_sats)?;
 
+        let events = inscription_updater.events;
+
+        if self.index_addresses {
+            self.index_block_addresses(wtx, &block, &satpoint_to_inscription_id)?;
+        }
+
+        if !events.is_empty() {
+            wtx.open_table(HEIGHT_TO_EVENTS)?
+                .insert(&self.height, Event::store_all(&events).as_slice())?;
+            self.events.extend(events);
+        }
+
         height_to_block_hash.insert(&self.height, &block.header.block_hash().store())?;
 
         self.height += 1;
@@ -564,6 +631,66 @@ impl Updater {
         Ok(())
     }
 
+    /// Stop tracking the outputs spent by `block`, and track the outputs it
+    /// creates that carry inscriptions, or rare sats when sats are indexed, by
+    /// script pubkey.
+    fn index_block_addresses(
+        &self,
+        wtx: &WriteTransaction,
+        block: &BlockData,
+        satpoint_to_inscription_id: &Table<&SatPointValue, &InscriptionIdValue>,
+    ) -> Result {
+        let mut outpoint_to_script_pubkey = wtx.open_table(OUTPOINT_TO_SCRIPT_PUBKEY)?;
+        let mut script_pubkey_to_outpoint = wtx.open_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+
+        for (tx, txid) in &block.txdata {
+            for input in &tx.input {
+                let outpoint = input.previous_output.store();
+
+                let Some(script_pubkey) = outpoint_to_script_pubkey
+                    .remove(&outpoint)?
+                    .map(|script_pubkey| script_pubkey.value().to_vec())
+                else {
+                    continue;
+                };
+
+                script_pubkey_to_outpoint.remove(&(script_pubkey.as_slice(), &outpoint))?;
+            }
+
+            for (vout, output) in tx.output.iter().enumerate() {
+                let outpoint = OutPoint {
+                    vout: vout.try_into().unwrap(),
+                    txid: *txid,
+                };
+
+                let inscribed = Index::inscriptions_on_output(satpoint_to_inscription_id, outpoint)?
+                    .next()
+                    .is_some();
+
+                let rare = self
+                    .range_cache
+                    .get(&outpoint.store())
+                    .map_or(false, |sat_ranges| {
+                        sat_ranges.chunks_exact(11).any(|chunk| {
+                            !Sat(SatRange::load(chunk.try_into().unwrap()).0).is_common()
+                        })
+                    });
+
+                if !inscribed && !rare {
+                    continue;
+                }
+
+                let script_pubkey = output.script_pubkey.as_bytes();
+                let outpoint = outpoint.store();
+
+                outpoint_to_script_pubkey.insert(&outpoint, script_pubkey)?;
+                script_pubkey_to_outpoint.insert(&(script_pubkey, &outpoint), &())?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn index_transaction_sats(
         &mut self,
         tx: &Transaction,
@@ -632,6 +759,7 @@ impl Updater {
 
     fn commit(
         &mut self,
+        index: &Index,
         wtx: WriteTransaction,
         value_cache: HashMap<OutPoint, u64>,
     ) -> Result {
@@ -675,6 +803,12 @@ impl Updater {
         Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
 
         wtx.commit()?;
+
+        // Events are only published once the blocks they happened in are committed
+        for event in self.events.drain(..) {
+            index.events.send(event).ok();
+        }
+
         Ok(())
     }
 }
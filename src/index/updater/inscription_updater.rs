@@ -1,26 +1,77 @@
-use super::*;
+use {
+    super::*,
+    crate::compression::{
+        Codec,
+        CompressionError,
+    },
+};
 
 pub(super) struct Flotsam {
     inscription_id: InscriptionId,
     offset: u64,
     origin: Origin,
+    /// The transaction that spent or made the inscription
+    txid: Txid,
 }
 
 enum Origin {
-    New(u64),
+    New {
+        effect: Option<Effect>,
+        content_hash: Option<String>,
+        content_type: Option<String>,
+        fee: u64,
+        media: Media,
+        parent: Option<InscriptionId>,
+        protocol_id: String,
+        validation_error: Option<String>,
+    },
     Old(SatPoint),
 }
 
+/// What a new, valid inscription of a default protocol does to the index when
+/// it's inscribed.
+enum Effect {
+    Bfs(bfs::Operation),
+    Bid(Username),
+    Bnw(Vec<SatPointValue>, bnw::Wall),
+}
+
 pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
+    bfs_path_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+    bid_name_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+    bnw_wall_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+    chain: Chain,
+    child_to_parent:
+        &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static InscriptionIdValue>,
+    content_hash_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+    content_type_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+    pub(super) events: Vec<Event>,
+    fee_to_number: &'a mut Table<'db, 'tx, (u64, u64), ()>,
     flotsam: Vec<Flotsam>,
     height: u64,
+    id_to_bid_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, BidEntryValue>,
+    id_to_bnw_satpoints: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static [u8]>,
     id_to_satpoint: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static SatPointValue>,
+    id_to_transfers:
+        &'a mut Table<'db, 'tx, (&'static InscriptionIdValue, u64), &'static TransferValue>,
     value_receiver: &'a mut Receiver<u64>,
     id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
+    id_to_protocol_id: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static str>,
+    id_to_validation_error: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static str>,
     lost_sats: u64,
+    media_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
     next_number: u64,
     number_to_id: &'a mut Table<'db, 'tx, u64, &'static InscriptionIdValue>,
     outpoint_to_value: &'a mut Table<'db, 'tx, &'static OutPointValue, u64>,
+    parent_to_children:
+        &'a mut Table<'db, 'tx, (&'static InscriptionIdValue, &'static InscriptionIdValue), ()>,
+    protocol_id_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+    pub_content_type_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+    pub_fee_to_number: &'a mut Table<'db, 'tx, (u64, u64), ()>,
+    pub_id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
+    pub_media_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+    pub_number_to_id: &'a mut Table<'db, 'tx, u64, &'static InscriptionIdValue>,
+    pub_protocol_id_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
     reward: u64,
     sat_to_inscription_id: &'a mut Table<'db, 'tx, u64, &'static InscriptionIdValue>,
     satpoint_to_id: &'a mut Table<'db, 'tx, &'static SatPointValue, &'static InscriptionIdValue>,
@@ -31,17 +82,59 @@ pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
 impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
     pub(super) fn new(
         height: u64,
+        chain: Chain,
+        bfs_path_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+        bid_name_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+        bnw_wall_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+        child_to_parent: &'a mut Table<
+            'db,
+            'tx,
+            &'static InscriptionIdValue,
+            &'static InscriptionIdValue,
+        >,
+        content_hash_to_id: &'a mut Table<'db, 'tx, &'static str, &'static InscriptionIdValue>,
+        content_type_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+        fee_to_number: &'a mut Table<'db, 'tx, (u64, u64), ()>,
+        id_to_bid_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, BidEntryValue>,
+        id_to_bnw_satpoints: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static [u8]>,
         id_to_satpoint: &'a mut Table<
             'db,
             'tx,
             &'static InscriptionIdValue,
             &'static SatPointValue,
         >,
+        id_to_transfers: &'a mut Table<
+            'db,
+            'tx,
+            (&'static InscriptionIdValue, u64),
+            &'static TransferValue,
+        >,
         value_receiver: &'a mut Receiver<u64>,
         id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
+        id_to_protocol_id: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static str>,
+        id_to_validation_error: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static str>,
         lost_sats: u64,
+        media_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
         number_to_id: &'a mut Table<'db, 'tx, u64, &'static InscriptionIdValue>,
         outpoint_to_value: &'a mut Table<'db, 'tx, &'static OutPointValue, u64>,
+        parent_to_children: &'a mut Table<
+            'db,
+            'tx,
+            (&'static InscriptionIdValue, &'static InscriptionIdValue),
+            (),
+        >,
+        protocol_id_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+        pub_content_type_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+        pub_fee_to_number: &'a mut Table<'db, 'tx, (u64, u64), ()>,
+        pub_id_to_entry: &'a mut Table<
+            'db,
+            'tx,
+            &'static InscriptionIdValue,
+            InscriptionEntryValue,
+        >,
+        pub_number_to_id: &'a mut Table<'db, 'tx, u64, &'static InscriptionIdValue>,
+        pub_media_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
+        pub_protocol_id_to_number: &'a mut Table<'db, 'tx, (&'static str, u64), ()>,
         sat_to_inscription_id: &'a mut Table<'db, 'tx, u64, &'static InscriptionIdValue>,
         satpoint_to_id: &'a mut Table<
             'db,
@@ -60,15 +153,38 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
             .unwrap_or(0);
 
         Ok(Self {
+            bfs_path_to_id,
+            bid_name_to_id,
+            bnw_wall_to_id,
+            chain,
+            child_to_parent,
+            content_hash_to_id,
+            content_type_to_number,
+            events: Vec::new(),
+            fee_to_number,
             flotsam: Vec::new(),
             height,
+            id_to_bid_entry,
+            id_to_bnw_satpoints,
             id_to_satpoint,
+            id_to_transfers,
             value_receiver,
             id_to_entry,
+            id_to_protocol_id,
+            id_to_validation_error,
             lost_sats,
+            media_to_number,
             next_number,
             number_to_id,
             outpoint_to_value,
+            parent_to_children,
+            protocol_id_to_number,
+            pub_content_type_to_number,
+            pub_fee_to_number,
+            pub_id_to_entry,
+            pub_media_to_number,
+            pub_number_to_id,
+            pub_protocol_id_to_number,
             reward: Height(height).subsidy(),
             sat_to_inscription_id,
             satpoint_to_id,
@@ -97,6 +213,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
                         offset: input_value + old_satpoint.offset,
                         inscription_id,
                         origin: Origin::Old(old_satpoint),
+                        txid,
                     });
                 }
 
@@ -119,17 +236,103 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
             }
         }
 
-        if inscriptions.iter().all(|flotsam| flotsam.offset != 0)
-            && Inscription::from_transaction(tx).is_some()
+        // Envelopes on sats that are already inscribed, or that the transaction
+        // doesn't have, don't make inscriptions. Non-tracked inscriptions aren't
+        // on a sat, so they're always made.
+        let mut new_inscriptions = Vec::new();
+        for (index, (inscription, content_error)) in Inscription::from_transaction_checked(tx)
+            .into_iter()
+            .enumerate()
+        {
+            let offset = if inscription.tracking() {
+                let Some(offset) = Inscription::offset(tx, index, input_value) else {
+                    continue;
+                };
+
+                if inscriptions.iter().any(|flotsam| flotsam.offset == offset)
+                    || new_inscriptions
+                        .iter()
+                        .any(|(_, _, _, new)| *new == Some(offset))
+                {
+                    continue;
+                }
+
+                Some(offset)
+            } else {
+                None
+            };
+
+            new_inscriptions.push((index, inscription, content_error, offset));
+        }
+
+        // The fee is split between the inscriptions the transaction makes, with
+        // the first getting the remainder, so that their fees add up to the
+        // transaction's
+        let total_fee =
+            input_value.saturating_sub(tx.output.iter().map(|txout| txout.value).sum::<u64>());
+        let count = u64::try_from(new_inscriptions.len()).unwrap().max(1);
+
+        for (i, (index, inscription, content_error, offset)) in
+            new_inscriptions.into_iter().enumerate()
         {
+            let fee = if i == 0 {
+                total_fee / count + total_fee % count
+            } else {
+                total_fee / count
+            };
+
+            let mut validation_error = content_error
+                .map(|err| err.to_string())
+                .or_else(|| inscription.validation_error())
+                .or_else(|| inscription.constraint_error(self.height));
+
+            let inscription_id = InscriptionId {
+                txid,
+                index: index.try_into().unwrap(),
+            };
+
+            // Non-tracked inscriptions aren't on a sat, so they're recorded on their
+            // own rather than followed from output to output
+            let Some(offset) = offset else {
+                self.index_pub_inscription(inscription_id, &inscription, fee, validation_error)?;
+                continue;
+            };
+
+            let effect = if validation_error.is_none() {
+                match self.effect(&inscription, &inscriptions)? {
+                    Ok(effect) => effect,
+                    Err(err) => {
+                        validation_error = Some(err);
+                        None
+                    },
+                }
+            } else {
+                None
+            };
+
+            // A declared parent is only recognized if the transaction spends it
+            let parent = inscription.parent().filter(|parent| {
+                inscriptions.iter().any(|flotsam| {
+                    flotsam.inscription_id == *parent && matches!(flotsam.origin, Origin::Old(_))
+                })
+            });
+
             inscriptions.push(Flotsam {
-                inscription_id: txid.into(),
-                offset: 0,
-                origin: Origin::New(
-                    input_value - tx.output.iter().map(|txout| txout.value).sum::<u64>(),
-                ),
+                inscription_id,
+                offset,
+                origin: Origin::New {
+                    content_hash: inscription.content_hash(),
+                    content_type: inscription.unwrapped_content_type(),
+                    effect,
+                    fee,
+                    media: inscription.unwrapped_media(),
+                    parent,
+                    protocol_id: inscription.protocol_id().into(),
+                    validation_error,
+                },
+                txid,
             });
-        };
+        }
 
         let is_coinbase = tx
             .input
@@ -141,34 +344,16 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
             inscriptions.append(&mut self.flotsam);
         }
 
-        inscriptions.sort_by_key(|flotsam| flotsam.offset);
-        let mut inscriptions = inscriptions.into_iter().peekable();
+        let (located, inscriptions) =
+            Index::locate_flotsam(txid, &tx.output, inscriptions, |flotsam| flotsam.offset);
+
+        for (flotsam, new_satpoint) in located {
+            self.update_inscription_location(input_sat_ranges, flotsam, new_satpoint)?;
+        }
 
         let mut output_value = 0;
         for (vout, tx_out) in tx.output.iter().enumerate() {
-            let end = output_value + tx_out.value;
-
-            while let Some(flotsam) = inscriptions.peek() {
-                if flotsam.offset >= end {
-                    break;
-                }
-
-                let new_satpoint = SatPoint {
-                    outpoint: OutPoint {
-                        txid,
-                        vout: vout.try_into().unwrap(),
-                    },
-                    offset: flotsam.offset - output_value,
-                };
-
-                self.update_inscription_location(
-                    input_sat_ranges,
-                    inscriptions.next().unwrap(),
-                    new_satpoint,
-                )?;
-            }
-
-            output_value = end;
+            output_value += tx_out.value;
 
             self.value_cache.insert(
                 OutPoint {
@@ -190,10 +375,11 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
 
             Ok(self.reward - output_value)
         } else {
-            self.flotsam.extend(inscriptions.map(|flotsam| Flotsam {
-                offset: self.reward + flotsam.offset,
-                ..flotsam
-            }));
+            self.flotsam
+                .extend(inscriptions.into_iter().map(|flotsam| Flotsam {
+                    offset: self.reward + flotsam.offset,
+                    ..flotsam
+                }));
             self.reward += input_value - output_value;
             Ok(0)
         }
@@ -210,10 +396,95 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         match flotsam.origin {
             Origin::Old(old_satpoint) => {
                 self.satpoint_to_id.remove(&old_satpoint.store())?;
+                self.renew_username(&inscription_id)?;
+
+                let sequence = self
+                    .id_to_transfers
+                    .range((&inscription_id, 0)..=(&inscription_id, u64::MAX))?
+                    .next_back()
+                    .map(|(key, _transfer)| key.value().1 + 1)
+                    .unwrap_or(0);
+
+                self.id_to_transfers.insert(
+                    &(&inscription_id, sequence),
+                    &Transfer {
+                        height: self.height,
+                        txid: flotsam.txid,
+                        old_satpoint,
+                        new_satpoint,
+                        // Inscriptions that don't land in an output of the transaction that
+                        // spent them were spent as fee
+                        fee_spent: new_satpoint.outpoint.txid != flotsam.txid,
+                    }
+                    .store(),
+                )?;
+
+                self.events
+                    .push(if new_satpoint.outpoint == OutPoint::null() {
+                        Event::Burned {
+                            height: self.height,
+                            inscription_id: flotsam.inscription_id,
+                            old_satpoint,
+                            new_satpoint,
+                        }
+                    } else {
+                        Event::Transferred {
+                            height: self.height,
+                            inscription_id: flotsam.inscription_id,
+                            old_satpoint,
+                            new_satpoint,
+                        }
+                    });
             },
-            Origin::New(fee) => {
-                self.number_to_id
-                    .insert(&self.next_number, &inscription_id)?;
+            Origin::New {
+                content_hash,
+                content_type,
+                effect,
+                fee,
+                media,
+                parent,
+                protocol_id,
+                validation_error,
+            } => {
+                let number = self.next_number;
+
+                self.number_to_id.insert(&number, &inscription_id)?;
+
+                if let Some(content_type) = content_type {
+                    self.content_type_to_number
+                        .insert(&(content_type.as_str(), number), &())?;
+                }
+
+                self.fee_to_number.insert(&(fee, number), &())?;
+
+                self.media_to_number
+                    .insert(&(media.to_string().as_str(), number), &())?;
+
+                self.protocol_id_to_number
+                    .insert(&(protocol_id.as_str(), number), &())?;
+
+                if let Some(parent) = parent {
+                    let parent = parent.store();
+
+                    self.child_to_parent.insert(&inscription_id, &parent)?;
+
+                    self.parent_to_children
+                        .insert(&(&parent, &inscription_id), &())?;
+                }
+
+                if let Some(content_hash) = content_hash {
+                    if self
+                        .content_hash_to_id
+                        .get(content_hash.as_str())?
+                        .is_none()
+                    {
+                        self.content_hash_to_id
+                            .insert(content_hash.as_str(), &inscription_id)?;
+                    }
+                }
+
+                self.id_to_protocol_id
+                    .insert(&inscription_id, protocol_id.as_str())?;
 
                 let mut sat = None;
                 if let Some(input_sat_ranges) = input_sat_ranges {
@@ -221,21 +492,49 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
                     for (start, end) in input_sat_ranges {
                         let size = end - start;
                         if offset + size > flotsam.offset {
-                            let n = start + flotsam.offset - offset;
-                            self.sat_to_inscription_id.insert(&n, &inscription_id)?;
-                            sat = Some(Sat(n));
+                            sat = Some(Sat(start + flotsam.offset - offset));
                             break;
                         }
                         offset += size;
                     }
                 }
 
+                let validation_error = if validation_error.is_some() {
+                    validation_error
+                } else {
+                    match effect {
+                        Some(Effect::Bfs(operation)) => {
+                            self.apply_bfs_operation(&operation, &inscription_id)?
+                        },
+                        Some(Effect::Bid(username)) => {
+                            self.register_username(&username, &inscription_id)?
+                        },
+                        Some(Effect::Bnw(satpoints, wall)) => {
+                            self.bnw_wall_to_id
+                                .insert(wall.name.key(&wall.bid).as_str(), &inscription_id)?;
+                            self.id_to_bnw_satpoints
+                                .insert(&inscription_id, satpoints.concat().as_slice())?;
+                            None
+                        },
+                        None => None,
+                    }
+                };
+
+                if let Some(validation_error) = validation_error {
+                    self.id_to_validation_error
+                        .insert(&inscription_id, validation_error.as_str())?;
+                }
+
+                if let Some(Sat(n)) = sat {
+                    self.sat_to_inscription_id.insert(&n, &inscription_id)?;
+                }
+
                 self.id_to_entry.insert(
                     &inscription_id,
                     &InscriptionEntry {
                         fee,
                         height: self.height,
-                        number: self.next_number,
+                        number,
                         sat,
                         timestamp: self.timestamp,
                     }
@@ -243,6 +542,12 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
                 )?;
 
                 self.next_number += 1;
+
+                self.events.push(Event::Inscribed {
+                    height: self.height,
+                    inscription_id: flotsam.inscription_id,
+                    satpoint: Some(new_satpoint),
+                });
             },
         }
 
@@ -253,4 +558,376 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
 
         Ok(())
     }
+
+    /// Record the non-tracked `pub` inscription `inscription_id`, which is
+    /// numbered in its own sequence, and has no sat point.
+    fn index_pub_inscription(
+        &mut self,
+        inscription_id: InscriptionId,
+        inscription: &Inscription,
+        fee: u64,
+        validation_error: Option<String>,
+    ) -> Result {
+        self.events.push(Event::Inscribed {
+            height: self.height,
+            inscription_id,
+            satpoint: None,
+        });
+
+        let inscription_id = inscription_id.store();
+
+        let number = self
+            .pub_number_to_id
+            .iter()?
+            .rev()
+            .map(|(number, _id)| number.value() + 1)
+            .next()
+            .unwrap_or(0);
+
+        self.pub_number_to_id.insert(&number, &inscription_id)?;
+
+        if let Some(content_type) = inscription.unwrapped_content_type() {
+            self.pub_content_type_to_number
+                .insert(&(content_type.as_str(), number), &())?;
+        }
+
+        self.pub_fee_to_number.insert(&(fee, number), &())?;
+
+        self.pub_media_to_number.insert(
+            &(inscription.unwrapped_media().to_string().as_str(), number),
+            &(),
+        )?;
+
+        self.pub_protocol_id_to_number
+            .insert(&(inscription.protocol_id(), number), &())?;
+
+        self.pub_id_to_entry.insert(
+            &inscription_id,
+            &InscriptionEntry {
+                fee,
+                height: self.height,
+                number,
+                sat: None,
+                timestamp: self.timestamp,
+            }
+            .store(),
+        )?;
+
+        if let Some(content_hash) = inscription.content_hash() {
+            if self
+                .content_hash_to_id
+                .get(content_hash.as_str())?
+                .is_none()
+            {
+                self.content_hash_to_id
+                    .insert(content_hash.as_str(), &inscription_id)?;
+            }
+        }
+
+        self.id_to_protocol_id
+            .insert(&inscription_id, inscription.protocol_id())?;
+
+        if let Some(validation_error) = validation_error {
+            self.id_to_validation_error
+                .insert(&inscription_id, validation_error.as_str())?;
+        }
+
+        Ok(())
+    }
+
+    /// Register `username` to `inscription_id`, returning why the registration
+    /// failed, if it did.
+    fn register_username(
+        &mut self,
+        username: &Username,
+        inscription_id: &InscriptionIdValue,
+    ) -> Result<Option<String>> {
+        if username.is_reserved(self.chain, self.height) {
+            return Ok(Some(format!(
+                "bid `{username}` is reserved until height {}",
+                self.chain.bid_sunrise_end_height()
+            )));
+        }
+
+        let previous = self
+            .bid_name_to_id
+            .get(username.as_str())?
+            .map(|inscription_id| *inscription_id.value());
+
+        if let Some(previous) = previous {
+            let entry = self
+                .id_to_bid_entry
+                .get(&previous)?
+                .map(|entry| BidEntry::load(entry.value()));
+
+            if let Some(entry) = entry {
+                if entry.is_active(self.height) {
+                    return Ok(Some(format!("bid `{username}` is already registered")));
+                }
+            }
+
+            self.id_to_bid_entry.remove(&previous)?;
+        }
+
+        self.bid_name_to_id
+            .insert(username.as_str(), inscription_id)?;
+
+        self.id_to_bid_entry.insert(
+            inscription_id,
+            &BidEntry {
+                registered: self.height,
+                expires: self.height + self.chain.bid_renewal_period(),
+            }
+            .store(),
+        )?;
+
+        Ok(None)
+    }
+
+    /// Moving the inscription that registered a username renews the
+    /// registration, as long as it hasn't expired.
+    fn renew_username(
+        &mut self,
+        inscription_id: &InscriptionIdValue,
+    ) -> Result {
+        let entry = self
+            .id_to_bid_entry
+            .get(inscription_id)?
+            .map(|entry| BidEntry::load(entry.value()));
+
+        if let Some(entry) = entry {
+            if entry.is_active(self.height) {
+                self.id_to_bid_entry.insert(
+                    inscription_id,
+                    &BidEntry {
+                        expires: self.height + self.chain.bid_renewal_period(),
+                        ..entry
+                    }
+                    .store(),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The effect of `inscription`, or why it's invalid, given the inscriptions
+    /// `spent` by the transaction revealing it.
+    fn effect(
+        &self,
+        inscription: &Inscription,
+        spent: &[Flotsam],
+    ) -> Result<Result<Option<Effect>, String>> {
+        if let Some(manifest) = inscription.manifest() {
+            if manifest.parts.is_empty() {
+                return Ok(Err("multipart inscription has no parts".into()));
+            }
+
+            for part in &manifest.parts {
+                let part_id = part.inscription_id.store();
+
+                // Parts are usually non-tracked, but may be tracked
+                if self.id_to_entry.get(&part_id)?.is_none()
+                    && self.pub_id_to_entry.get(&part_id)?.is_none()
+                {
+                    return Ok(Err(format!(
+                        "multipart inscription lists unknown part {}",
+                        part.inscription_id
+                    )));
+                }
+            }
+        }
+
+        if let Some(err) = inscription.content_hash_error() {
+            return Ok(Err(err));
+        }
+
+        // Content compressed without a dictionary was decompressed when it was
+        // parsed
+        if let Some(compressed) = inscription.compressed_content() {
+            if let Some(dictionary) = compressed.dictionary {
+                let error = match compressed.codec {
+                    Ok(Codec::Zstd) => {
+                        self.id_to_entry
+                            .get(&dictionary.store())?
+                            .is_none()
+                            .then(|| {
+                                format!("compression dictionary {dictionary} is not an inscription")
+                            })
+                    },
+                    Ok(codec) => Some(CompressionError::DictionaryUnsupported(codec).to_string()),
+                    Err(err) => Some(err.to_string()),
+                };
+
+                if let Some(error) = error {
+                    return Ok(Err(error));
+                }
+            }
+        }
+
+        if let Some(content_hash) = inscription.content_reference() {
+            if self
+                .content_hash_to_id
+                .get(content_hash.as_str())?
+                .is_none()
+            {
+                return Ok(Err(format!(
+                    "content hash {content_hash} does not match any inscription"
+                )));
+            }
+        }
+
+        if let Some(username) = Username::from_inscription(inscription) {
+            return Ok(username
+                .map(|username| Some(Effect::Bid(username)))
+                .map_err(|err| format!("{err:#}")));
+        }
+
+        if let Some(operation) = bfs::Operation::from_inscription(inscription) {
+            let operation = match operation {
+                Ok(operation) => operation,
+                Err(err) => return Ok(Err(format!("{err:#}"))),
+            };
+
+            if let Some(err) = self.authorization_error("bfs", &operation.bid, spent)? {
+                return Ok(Err(err));
+            }
+
+            return Ok(Ok(Some(Effect::Bfs(operation))));
+        }
+
+        if let Some(wall) = bnw::Wall::from_inscription(inscription) {
+            let wall = match wall {
+                Ok(wall) => wall,
+                Err(err) => return Ok(Err(format!("{err:#}"))),
+            };
+
+            if let Some(err) = self.authorization_error("bnw", &wall.bid, spent)? {
+                return Ok(Err(err));
+            }
+
+            let mut satpoints = Vec::new();
+            for inscription_id in &wall.inscriptions {
+                match self.id_to_satpoint.get(&inscription_id.store())? {
+                    Some(satpoint) => satpoints.push(*satpoint.value()),
+                    None => {
+                        return Ok(Err(format!(
+                            "bnw wall lists unknown inscription {inscription_id}"
+                        )))
+                    },
+                }
+            }
+
+            return Ok(Ok(Some(Effect::Bnw(satpoints, wall))));
+        }
+
+        Ok(Ok(None))
+    }
+
+    /// Inscriptions of `protocol` may only act on behalf of an active `bid`
+    /// whose registration inscription is spent by the same transaction,
+    /// returning why they aren't authorized, if they aren't.
+    fn authorization_error(
+        &self,
+        protocol: &str,
+        bid: &Username,
+        spent: &[Flotsam],
+    ) -> Result<Option<String>> {
+        let Some(registration) = self
+            .bid_name_to_id
+            .get(bid.as_str())?
+            .map(|inscription_id| *inscription_id.value())
+        else {
+            return Ok(Some(format!("bid `{bid}` is not registered")));
+        };
+
+        let active = self
+            .id_to_bid_entry
+            .get(&registration)?
+            .map(|entry| BidEntry::load(entry.value()).is_active(self.height))
+            .unwrap_or_default();
+
+        if !active {
+            return Ok(Some(format!("bid `{bid}` has expired")));
+        }
+
+        if !spent
+            .iter()
+            .any(|flotsam| flotsam.inscription_id.store() == registration)
+        {
+            return Ok(Some(format!(
+                "{protocol} inscription does not spend the registration inscription of bid \
+                `{bid}`"
+            )));
+        }
+
+        Ok(None)
+    }
+
+    /// Apply `operation` to the file tree of its `bid`, returning why it
+    /// couldn't be applied, if it couldn't.
+    fn apply_bfs_operation(
+        &mut self,
+        operation: &bfs::Operation,
+        inscription_id: &InscriptionIdValue,
+    ) -> Result<Option<String>> {
+        let bid = &operation.bid;
+        let path = &operation.path;
+
+        let directory_key = path.directory_key(bid);
+
+        let mut descendants = Vec::new();
+        for (key, _) in self
+            .bfs_path_to_id
+            .range::<&str>(directory_key.as_str()..)?
+        {
+            if !key.value().starts_with(&directory_key) {
+                break;
+            }
+            descendants.push(key.value().to_string());
+        }
+
+        match operation.action {
+            bfs::Action::Put => {
+                for ancestor in path.ancestors() {
+                    if self
+                        .bfs_path_to_id
+                        .get(ancestor.key(bid).as_str())?
+                        .is_some()
+                    {
+                        return Ok(Some(format!(
+                            "bfs path `{ancestor}` of bid `{bid}` is a file"
+                        )));
+                    }
+                }
+
+                if !descendants.is_empty() {
+                    return Ok(Some(format!(
+                        "bfs path `{path}` of bid `{bid}` is a directory"
+                    )));
+                }
+
+                self.bfs_path_to_id
+                    .insert(path.key(bid).as_str(), inscription_id)?;
+            },
+            bfs::Action::Rm => {
+                let removed = self
+                    .bfs_path_to_id
+                    .remove(path.key(bid).as_str())?
+                    .is_some();
+
+                if !removed && descendants.is_empty() {
+                    return Ok(Some(format!(
+                        "bfs path `{path}` of bid `{bid}` does not exist"
+                    )));
+                }
+
+                for key in descendants {
+                    self.bfs_path_to_id.remove(key.as_str())?;
+                }
+            },
+        }
+
+        Ok(None)
+    }
 }
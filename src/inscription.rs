@@ -1,6 +1,19 @@
 use {
     super::*,
-    crate::off_chain,
+    crate::{
+        compression::{
+            self,
+            Codec,
+            CompressedContent,
+            Compression,
+            CompressionError,
+        },
+        multipart::{
+            self,
+            Part,
+        },
+        off_chain,
+    },
     base64::Engine,
     bitcoin::{
         blockdata::{
@@ -20,62 +33,13 @@ use {
         Script,
         Witness,
     },
-    brotli::{
-        CompressorWriter,
-        Decompressor,
-    },
-    include_dir::{
-        include_dir,
-        Dir,
-    },
     std::{
         ffi::OsStr,
-        io::{
-            Cursor,
-            Read,
-            Write,
-        },
         iter::Peekable,
         str,
     },
-    version_compare::Version,
 };
 
-struct LimitedReader<R> {
-    inner: io::BufReader<R>,
-    limit: usize,
-    total_read: usize,
-}
-
-impl<R: Read> LimitedReader<R> {
-    fn new(
-        inner: R,
-        limit: usize,
-    ) -> Self {
-        Self {
-            inner: io::BufReader::new(inner),
-            limit,
-            total_read: 0,
-        }
-    }
-}
-
-impl<R: Read> Read for LimitedReader<R> {
-    fn read(
-        &mut self,
-        buf: &mut [u8],
-    ) -> io::Result<usize> {
-        if self.total_read >= self.limit {
-            return Ok(0);
-        }
-        let remaining = self.limit - self.total_read;
-        let to_read = buf.len().min(remaining);
-        let n = self.inner.read(&mut buf[..to_read])?;
-        self.total_read += n;
-        Ok(n)
-    }
-}
-
 const ORDV1_GENERAL_MESSAGE: &str = "This inscription is using the ordv1 protocol. If \
 you see this message, you're likely using an outdated ordv0-only client or explorer. Consider \
 upgrading to the software referenced in this message, asking your current software provider to add \
@@ -93,6 +57,19 @@ const ORDV1_SOFTWARE_MESSAGE: &str = "https://github.com/tyjvazum/arb";
 
 const BODY_TAG: &[u8] = &[];
 const CONTENT_TYPE_TAG: &[u8] = &[1];
+const PARENT_TAG: &[u8] = &[3];
+const PROTOCOL_TAG: &[u8] = &[7];
+const CONTENT_ENCODING_TAG: &[u8] = &[9];
+const PROTOCOL_PROPERTIES_TAG: &[u8] = &[13];
+const CONTENT_HASH_TAG: &[u8] = &[15];
+const ENVELOPE_VERSION_TAG: &[u8] = &[17];
+const CONTENT_METADATA_TAG: &[u8] = &[19];
+
+/// Tags whose values may not fit in a single push, and so may be repeated,
+/// with their values concatenated.
+const CHUNKED_TAGS: [&[u8]; 2] = [PROTOCOL_PROPERTIES_TAG, CONTENT_METADATA_TAG];
+
+const BINARY_ENVELOPE_VERSION: u8 = 1;
 
 #[derive(Deserialize, Serialize)]
 pub struct Expansion {
@@ -106,6 +83,22 @@ pub struct Expansion {
     content_type: Option<String>,
     content_metadata: Option<String>,
     wrapped: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parts: Option<Vec<Part>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<TorrentFile>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compression_dictionary: Option<InscriptionId>,
+}
+
+/// The fields of a binary envelope not kept elsewhere in an inscription. A
+/// binary envelope carries what a JSON envelope expansion does in separate
+/// envelope tags, with its content as raw, unencoded bytes.
+#[derive(Debug, PartialEq, Clone, Default)]
+struct BinaryEnvelope {
+    /// The codec the body is compressed with, if it's still compressed.
+    content_encoding: Option<String>,
+    content_hash: Option<sha256::Hash>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -115,6 +108,9 @@ pub(crate) struct Inscription {
     tracking: bool,
     content_metadata: Option<Vec<u8>>,
     protocol_properties: Option<String>,
+    protocol_id: String,
+    binary: Option<BinaryEnvelope>,
+    parent: Option<InscriptionId>,
 }
 
 impl Inscription {
@@ -129,11 +125,57 @@ impl Inscription {
             tracking: true,
             content_metadata: None,
             protocol_properties: None,
+            protocol_id: "ord-v0".into(),
+            parent: None,
+            binary: None,
         }
     }
 
-    pub(crate) fn from_transaction(tx: &Transaction) -> Option<Inscription> {
-        InscriptionParser::parse(&tx.input.get(0)?.witness).ok()
+    /// The inscriptions in `tx`, in the order of the inputs and envelopes they
+    /// are in, which is the order of their inscription id indices.
+    pub(crate) fn from_transaction(tx: &Transaction) -> Vec<Inscription> {
+        Self::from_transaction_checked(tx)
+            .into_iter()
+            .map(|(inscription, _content_error)| inscription)
+            .collect()
+    }
+
+    /// The inscriptions in `tx`, each along with why what it carries is
+    /// malformed, if it is. Such inscriptions are returned as far as they could
+    /// be parsed.
+    pub(crate) fn from_transaction_checked(
+        tx: &Transaction
+    ) -> Vec<(Inscription, Option<InscriptionError>)> {
+        tx.input
+            .iter()
+            .flat_map(|tx_in| InscriptionParser::parse_all(&tx_in.witness))
+            .collect()
+    }
+
+    /// The offset of the sat that the inscription with `index` in `tx` is
+    /// made on: the first sat of the output with the same index, or for
+    /// inscriptions beyond the last output, a sat paid as fee.
+    ///
+    /// Returns `None` if `tx`, whose inputs are worth `input_value`, has no
+    /// such sat, as when there are more inscriptions beyond the last output
+    /// than sats paid as fee, in which case no inscription is made.
+    pub(crate) fn offset(
+        tx: &Transaction,
+        index: usize,
+        input_value: u64,
+    ) -> Option<u64> {
+        let outputs = tx.output.len();
+
+        let preceding = tx
+            .output
+            .iter()
+            .take(index)
+            .map(|txout| txout.value)
+            .sum::<u64>();
+
+        let offset = preceding + index.saturating_sub(outputs) as u64;
+
+        (offset < input_value).then_some(offset)
     }
 
     pub(crate) fn from_file(
@@ -141,43 +183,87 @@ impl Inscription {
         path: impl AsRef<Path>,
         title: Option<String>,
         subtitle: Option<String>,
-        compression: bool,
+        compression: Option<Compression>,
+        compression_dictionary: Option<(InscriptionId, Vec<u8>)>,
+        binary_envelope: bool,
         offchain: bool,
-        torrent_path: Option<impl AsRef<Path>>,
-        tracker_url: &str,
-        peer_addr: &str,
+        multipart: bool,
+        torrent: &TorrentOptions,
         metadata_path: Option<impl AsRef<Path>>,
         properties_path: Option<impl AsRef<Path>>,
         license: Option<String>,
         protocol_id: String,
         description: Option<String>,
     ) -> Result<Self, Error> {
-        if compression && offchain {
+        if compression.is_some() && offchain {
             bail!("Compression and offchain must not be enabled at the same time!");
         }
 
-        let path = path.as_ref();
+        if compression_dictionary.is_some() && multipart {
+            bail!("Multipart inscriptions cannot be compressed with a dictionary!");
+        }
 
-        let body =
-            fs::read(path).with_context(|| format!("io error reading {}", path.display()))?;
+        if binary_envelope {
+            if protocol_id == *"ord-v0" {
+                bail!("Binary envelopes must use a protocol other than `ord-v0`, like `ord-v1`!");
+            }
 
-        let mut compressed = Vec::new();
+            if offchain || multipart {
+                bail!("Binary envelopes cannot be used for off-chain or multipart inscriptions!");
+            }
 
-        if compression || metadata_path.is_some() {
-            let mut compressor = CompressorWriter::new(&mut compressed, 4096, 11, 22);
-            compressor.write_all(&body)?;
-            drop(compressor);
+            if compression_dictionary.is_some() {
+                bail!("Binary envelopes cannot be compressed with a dictionary!");
+            }
         }
 
-        let (result, content_encoding) = if (compression || metadata_path.is_some())
-            && (1.0 - (compressed.len() as f64 / body.len() as f64)) > 0.0
-        {
-            (compressed, Some(true))
+        if multipart && offchain {
+            bail!("Multipart and offchain must not be enabled at the same time!");
+        }
+
+        if multipart && protocol_id == *"ord-v0" {
+            bail!("Multipart inscriptions must use a protocol other than `ord-v0`, like `ord-v1`!");
+        }
+
+        let path = path.as_ref();
+
+        // Off-chain content is streamed from disk while its torrent is created
+        // instead, since it may be too large to fit in memory.
+        let body = if offchain {
+            Vec::new()
+        } else {
+            fs::read(path).with_context(|| format!("io error reading {}", path.display()))?
+        };
+
+        let compression = if offchain {
+            None
+        } else if compression_dictionary.is_some() {
+            Some(compression.unwrap_or(Compression::Zstd))
         } else {
-            (body, None)
+            compression.or_else(|| metadata_path.is_some().then_some(Compression::Brotli))
         };
 
-        if let Some(limit) = chain.inscription_content_size_limit() {
+        let dictionary = compression_dictionary
+            .as_ref()
+            .map(|(_inscription_id, dictionary)| dictionary.as_slice());
+
+        let content_hash = binary_envelope.then(|| sha256::Hash::hash(&body));
+
+        // Content is only compressed if that makes it smaller
+        let (result, codec) = match compression {
+            Some(compression) => match compression.compress(&body, dictionary)? {
+                (codec, compressed) if compressed.len() < body.len() => (compressed, Some(codec)),
+                _ => (body, None),
+            },
+            None => (body, None),
+        };
+
+        // The limit applies to each part of multipart inscriptions instead, and
+        // not at all to off-chain inscriptions.
+        if let Some(limit) = chain
+            .inscription_content_size_limit()
+            .filter(|_| !multipart && !offchain)
+        {
             let len = result.len();
             if len > limit {
                 bail!(
@@ -207,51 +293,26 @@ impl Inscription {
         };
 
         // Begin protocol-level properties file handling.
-        static PROTOCOLS_DIR: Dir<'_> = include_dir!("protocols/");
-
-        // Default protocols are version locked.
-        let spec_name = if protocol_id == *"ord-v1" {
-            "ord-v1.0.0.json".to_string()
-        } else if protocol_id == *"pub-v1" || protocol_id == *"pub" {
-            "pub-v1.0.0.json".to_string()
+        let spec = if protocol_id == *"ord-v0" {
+            None
         } else {
-            // Get list of spec filenames from protocols folder.
-            let files = PROTOCOLS_DIR.files();
-            let mut active_spec = "".to_string();
-            for file in files {
-                // Check if any begin with protocol_id.
-                let path_string = file.path().display().to_string();
-                if file.path().starts_with(&protocol_id) {
-                    if active_spec != *"" {
-                        // If multiple, choose the one with the highest SemVer.
-                        if Version::from(path_string.as_str()).unwrap()
-                            > Version::from(active_spec.as_str()).unwrap()
-                        {
-                            active_spec = path_string.clone();
-                        }
-                    } else {
-                        active_spec = path_string.clone();
-                    }
-                }
-            }
-            // Return spec or ""
-            active_spec
-        };
-
-        let protocol_spec = if spec_name != *"" {
-            let r = PROTOCOLS_DIR
-                .get_file(spec_name)
-                .unwrap()
-                .contents_utf8()
-                .unwrap()
-                .replace('\n', "");
-            r
-        } else {
-            "{}".to_string()
+            Some(
+                ProtocolRegistry::current()
+                    .get(&protocol_id)
+                    .cloned()
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "unknown protocol `{protocol_id}`, see `arb protocols list` for \
+                            available protocols"
+                        )
+                    })?,
+            )
         };
 
-        let mut protocol_json: serde_json::Value =
-            serde_json::from_str(&protocol_spec).expect("Unable to parse properties JSON!");
+        let mut protocol_json = spec
+            .as_ref()
+            .map(ProtocolSpec::default_properties)
+            .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
         // End protocol-level properties file handling.
 
         // Special handling for 'ord' in CLI API w/o directly using a prop file.
@@ -281,16 +342,21 @@ impl Inscription {
                 "".into()
             };
 
-            if content_encoding.is_some() {
-                protocol_json["comment"] = ORDV1_COMPRESSED_MESSAGE.to_owned().into();
-            } else if offchain {
-                protocol_json["comment"] = ORDV1_OFF_CHAIN_MESSAGE.to_owned().into();
-            } else {
-                protocol_json["comment"] = ORDV1_GENERAL_MESSAGE.to_owned().into();
+            // Outdated clients never see the properties of binary envelopes,
+            // so the comment addressed to them is left out.
+            if !binary_envelope {
+                protocol_json["comment"] = if codec.is_some() {
+                    ORDV1_COMPRESSED_MESSAGE
+                } else if offchain {
+                    ORDV1_OFF_CHAIN_MESSAGE
+                } else {
+                    ORDV1_GENERAL_MESSAGE
+                }
+                .into();
             }
 
             protocol_json["description"] = ORDV1_SOFTWARE_MESSAGE.to_owned().into();
-        } else {
+        } else if spec.as_ref().map_or(true, |spec| spec.fields.is_none()) {
             protocol_json["title"] = "".into();
             protocol_json["subtitle"] = "".into();
             protocol_json["license"] = "".into();
@@ -299,38 +365,72 @@ impl Inscription {
         let tracking = protocol_json["tracking"] == true || protocol_id == *"ord-v0";
 
         // Begin inscription-level properties file handling.
-        let properties_json = match &properties_path {
-            Some(p) if p.as_ref().extension().and_then(OsStr::to_str) == Some("json") => {
-                let contents = fs::read_to_string(p).expect("Unable to read props from file!");
-                let result: Option<serde_json::Value> =
-                    Some(serde_json::from_str(&contents).unwrap());
-                result
-            },
-            _ => None,
-        };
+        if let Some(properties_path) = &properties_path {
+            let properties_path = properties_path.as_ref();
 
-        if let Some(properties) = properties_json {
-            for pair in properties.as_object().unwrap() {
-                let (key, value) = pair;
-                if let Some(_field) = protocol_json.get("key") {
-                    protocol_json[key] = value.clone();
-                }
+            let Some(spec) = &spec else {
+                bail!("protocol `{protocol_id}` does not support properties");
+            };
+
+            if properties_path.extension().and_then(OsStr::to_str) != Some("json") {
+                bail!(
+                    "properties file {} must be a `.json` file",
+                    properties_path.display()
+                );
             }
+
+            let contents = fs::read(properties_path)
+                .with_context(|| format!("io error reading {}", properties_path.display()))?;
+
+            let properties: serde_json::Value = serde_json::from_slice(&contents)
+                .with_context(|| format!("invalid JSON in {}", properties_path.display()))?;
+
+            let Some(properties) = properties.as_object() else {
+                bail!(
+                    "properties file {} must contain a JSON object",
+                    properties_path.display()
+                );
+            };
+
+            spec.merge_properties(&mut protocol_json, properties)?;
+        }
+
+        if let Some(spec) = &spec {
+            spec.validate_properties(&protocol_json)
+                .with_context(|| format!("invalid properties for protocol `{}`", spec.id()))?;
         }
         // End inscription-level properties file handling.
 
-        if content_encoding.is_some() {
+        if binary_envelope {
+            Ok(Self {
+                content_type: Some(Media::content_type_for_path(path)?.into()),
+                body: Some(result),
+                tracking,
+                content_metadata: metadata_bytes,
+                protocol_properties: Some(protocol_json.to_string()),
+                protocol_id,
+                parent: None,
+                binary: Some(BinaryEnvelope {
+                    content_encoding: codec.map(|codec| codec.to_string()),
+                    content_hash,
+                }),
+            })
+        } else if let Some(codec) = codec {
             let compressed = Expansion {
-                protocol: protocol_id,
+                protocol: protocol_id.clone(),
                 protocol_version: protocol_json["version"].to_string(),
                 protocol_properties: protocol_json.to_string(),
-                compression: Some("br base64".to_owned()),
+                compression: Some(codec.encoding()),
                 offchain: None,
                 content: Some(base64::engine::general_purpose::STANDARD.encode(&result)),
                 content_hash: Some(sha256::Hash::hash(&result).into_inner().to_vec().to_hex()),
                 content_type: Some(Media::content_type_for_path(path)?.to_owned()),
                 content_metadata: encoded_metadata,
                 wrapped: true,
+                parts: None,
+                files: None,
+                compression_dictionary: compression_dictionary
+                    .map(|(inscription_id, _dictionary)| inscription_id),
             };
 
             let json = serde_json::to_string(&compressed)?;
@@ -341,22 +441,33 @@ impl Inscription {
                 tracking,
                 content_metadata: metadata_bytes,
                 protocol_properties: Some(protocol_json.to_string()),
+                protocol_id,
+                parent: None,
+                binary: None,
             })
         } else if offchain {
-            let magnet_and_sha256hash =
-                off_chain::make_offchain_inscription(path, torrent_path, tracker_url, peer_addr)?;
+            let torrent = off_chain::make_offchain_inscription(path, torrent)?;
+
+            let content_type = if torrent.files.is_some() {
+                None
+            } else {
+                Some(Media::content_type_for_path(path)?.to_owned())
+            };
 
             let offchain = Expansion {
-                protocol: protocol_id,
+                protocol: protocol_id.clone(),
                 protocol_version: protocol_json["version"].to_string(),
                 protocol_properties: protocol_json.to_string(),
                 compression: None,
-                offchain: Some(magnet_and_sha256hash[0].clone()),
+                offchain: Some(torrent.magnet),
                 content: None,
-                content_hash: Some(magnet_and_sha256hash[1].clone()),
-                content_type: Some(Media::content_type_for_path(path)?.to_owned()),
+                content_hash: torrent.content_hash,
+                content_type,
                 content_metadata: encoded_metadata,
                 wrapped: true,
+                parts: None,
+                files: torrent.files,
+                compression_dictionary: None,
             };
 
             let json = serde_json::to_string(&offchain)?;
@@ -367,10 +478,13 @@ impl Inscription {
                 tracking,
                 content_metadata: metadata_bytes,
                 protocol_properties: Some(protocol_json.to_string()),
+                protocol_id,
+                parent: None,
+                binary: None,
             })
         } else if protocol_id != *"ord-v0" {
             let v1wrapper = Expansion {
-                protocol: protocol_id,
+                protocol: protocol_id.clone(),
                 protocol_version: protocol_json["version"].to_string(),
                 protocol_properties: protocol_json.to_string(),
                 compression: None,
@@ -380,6 +494,9 @@ impl Inscription {
                 content_type: Some(Media::content_type_for_path(path)?.to_owned()),
                 content_metadata: encoded_metadata,
                 wrapped: true,
+                parts: None,
+                files: None,
+                compression_dictionary: None,
             };
 
             let json = serde_json::to_string(&v1wrapper)?;
@@ -390,6 +507,9 @@ impl Inscription {
                 tracking,
                 content_metadata: metadata_bytes,
                 protocol_properties: Some(protocol_json.to_string()),
+                protocol_id,
+                parent: None,
+                binary: None,
             })
         } else {
             let content_type = Media::content_type_for_path(path)?;
@@ -400,11 +520,64 @@ impl Inscription {
                 tracking: true,
                 content_metadata: None,
                 protocol_properties: None,
+                protocol_id,
+                parent: None,
+                binary: None,
             })
         }
     }
 
-    fn append_reveal_script_to_builder(
+    /// A wrapped inscription of `body` for a protocol without file-based
+    /// options, with `properties` set on top of the spec's defaults.
+    pub(crate) fn wrapped(
+        protocol_id: &str,
+        properties: serde_json::Value,
+        content_type: &str,
+        body: &[u8],
+    ) -> Result<Self, Error> {
+        let spec = ProtocolRegistry::current()
+            .get(protocol_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown protocol `{protocol_id}`"))?;
+
+        let mut protocol_json = spec.default_properties();
+
+        if let Some(properties) = properties.as_object() {
+            spec.merge_properties(&mut protocol_json, properties)?;
+        }
+
+        spec.validate_properties(&protocol_json)
+            .with_context(|| format!("invalid properties for protocol `{}`", spec.id()))?;
+
+        let expansion = Expansion {
+            protocol: protocol_id.into(),
+            protocol_version: protocol_json["version"].to_string(),
+            protocol_properties: protocol_json.to_string(),
+            compression: None,
+            offchain: None,
+            content: Some(base64::engine::general_purpose::STANDARD.encode(body)),
+            content_hash: Some(sha256::Hash::hash(body).into_inner().to_vec().to_hex()),
+            content_type: Some(content_type.into()),
+            content_metadata: None,
+            wrapped: true,
+            parts: None,
+            files: None,
+            compression_dictionary: None,
+        };
+
+        Ok(Self {
+            content_type: Some("application/json".as_bytes().to_vec()),
+            body: Some(serde_json::to_string(&expansion)?.into()),
+            tracking: spec.tracking,
+            content_metadata: None,
+            protocol_properties: Some(protocol_json.to_string()),
+            protocol_id: protocol_id.into(),
+            parent: None,
+            binary: None,
+        })
+    }
+
+    pub(crate) fn append_reveal_script_to_builder(
         &self,
         mut builder: script::Builder,
     ) -> script::Builder {
@@ -421,6 +594,46 @@ impl Inscription {
                 .push_slice(content_type);
         }
 
+        if let Some(parent) = self.parent {
+            builder = builder
+                .push_slice(PARENT_TAG)
+                .push_slice(&parent.tag_value());
+        }
+
+        if let Some(binary) = &self.binary {
+            builder = builder
+                .push_slice(ENVELOPE_VERSION_TAG)
+                .push_slice(&[BINARY_ENVELOPE_VERSION])
+                .push_slice(PROTOCOL_TAG)
+                .push_slice(self.protocol_id.as_bytes());
+
+            if let Some(protocol_properties) = &self.protocol_properties {
+                for chunk in protocol_properties.as_bytes().chunks(520) {
+                    builder = builder
+                        .push_slice(PROTOCOL_PROPERTIES_TAG)
+                        .push_slice(chunk);
+                }
+            }
+
+            if let Some(content_metadata) = &self.content_metadata {
+                for chunk in content_metadata.chunks(520) {
+                    builder = builder.push_slice(CONTENT_METADATA_TAG).push_slice(chunk);
+                }
+            }
+
+            if let Some(content_encoding) = &binary.content_encoding {
+                builder = builder
+                    .push_slice(CONTENT_ENCODING_TAG)
+                    .push_slice(content_encoding.as_bytes());
+            }
+
+            if let Some(content_hash) = &binary.content_hash {
+                builder = builder
+                    .push_slice(CONTENT_HASH_TAG)
+                    .push_slice(&content_hash.into_inner());
+            }
+        }
+
         if let Some(body) = &self.body {
             builder = builder.push_slice(BODY_TAG);
             for chunk in body.chunks(520) {
@@ -431,6 +644,7 @@ impl Inscription {
         builder.push_opcode(opcodes::all::OP_ENDIF)
     }
 
+    #[cfg(test)]
     pub(crate) fn append_reveal_script(
         &self,
         builder: script::Builder,
@@ -450,6 +664,32 @@ impl Inscription {
         content_type.parse().unwrap_or(Media::Unknown)
     }
 
+    /// The content type of this inscription's content, unwrapped if it's
+    /// wrapped or compressed.
+    pub(crate) fn unwrapped_content_type(&self) -> Option<String> {
+        if let Some((content_type, _)) = self.wrapped_content() {
+            return Some(content_type);
+        }
+
+        if let Some(compressed) = self.compressed_content() {
+            return compressed.content_type;
+        }
+
+        self.content_type().map(str::to_owned)
+    }
+
+    /// The kind of this inscription's content, unwrapped if it's wrapped or
+    /// compressed.
+    pub(crate) fn unwrapped_media(&self) -> Media {
+        if self.body.is_none() {
+            return Media::Unknown;
+        }
+
+        self.unwrapped_content_type()
+            .and_then(|content_type| content_type.parse().ok())
+            .unwrap_or(Media::Unknown)
+    }
+
     pub(crate) fn body(&self) -> Option<&[u8]> {
         Some(self.body.as_ref()?)
     }
@@ -468,6 +708,331 @@ impl Inscription {
         str::from_utf8(self.content_type.as_ref()?).ok()
     }
 
+    /// Why this inscription doesn't conform to the field schema of its
+    /// protocol's spec, if it doesn't.
+    pub(crate) fn validation_error(&self) -> Option<String> {
+        let registry = ProtocolRegistry::current();
+        let spec = registry.get(&self.protocol_id)?;
+        let properties = self.protocol_properties.as_ref()?;
+
+        let result = serde_json::from_str(properties)
+            .context("protocol properties are not valid JSON")
+            .and_then(|properties| spec.validate_properties(&properties));
+
+        result.err().map(|err| format!("{err:#}"))
+    }
+
+    /// Why this inscription, inscribed at `height`, doesn't conform to the
+    /// constraints of its protocol's spec, if it doesn't.
+    pub(crate) fn constraint_error(
+        &self,
+        height: u64,
+    ) -> Option<String> {
+        let registry = ProtocolRegistry::current();
+        let constraints = registry.get(&self.protocol_id)?.constraints.as_ref()?;
+
+        constraints
+            .check(self, height)
+            .err()
+            .map(|err| format!("{err:#}"))
+    }
+
+    /// The inscription's metadata, parsed as JSON. Wrapped inscriptions carry
+    /// it base64 encoded.
+    pub(crate) fn metadata(&self) -> Option<serde_json::Value> {
+        let metadata = self.content_metadata.as_ref()?;
+
+        let decoded = base64::engine::general_purpose::STANDARD.decode(metadata);
+
+        serde_json::from_slice(decoded.as_deref().unwrap_or(metadata)).ok()
+    }
+
+    /// The content type and content wrapped in the envelope expansion or
+    /// binary envelope of an uncompressed, on-chain wrapped inscription.
+    pub(crate) fn wrapped_content(&self) -> Option<(String, Vec<u8>)> {
+        if let Some(binary) = &self.binary {
+            if binary.content_encoding.is_some() {
+                return None;
+            }
+
+            return Some((self.content_type()?.into(), self.body()?.to_vec()));
+        }
+
+        if self.content_type()? != "application/json" {
+            return None;
+        }
+
+        let expansion = serde_json::from_slice::<Expansion>(self.body()?).ok()?;
+
+        if !expansion.wrapped || expansion.compression.is_some() || expansion.offchain.is_some() {
+            return None;
+        }
+
+        let content = base64::engine::general_purpose::STANDARD
+            .decode(expansion.content?)
+            .ok()?;
+
+        Some((expansion.content_type?, content))
+    }
+
+    /// The compressed content of a wrapped inscription that was left in its
+    /// envelope expansion when it was parsed.
+    pub(crate) fn compressed_content(&self) -> Option<CompressedContent> {
+        if let Some(binary) = &self.binary {
+            return Some(CompressedContent {
+                codec: Codec::from_name(binary.content_encoding.as_ref()?),
+                content: self.body.clone()?,
+                content_type: self.content_type().map(str::to_owned),
+                dictionary: None,
+            });
+        }
+
+        let expansion = self.expansion().ok()?;
+
+        Some(CompressedContent {
+            codec: Codec::from_encoding(&expansion.compression?),
+            content: base64::engine::general_purpose::STANDARD
+                .decode(expansion.content?)
+                .ok()?,
+            content_type: expansion.content_type,
+            dictionary: expansion.compression_dictionary,
+        })
+    }
+
+    /// The content type and content of this inscription, unwrapped if it's
+    /// wrapped, or `None` if its content is elsewhere, like for off-chain,
+    /// multipart and reference inscriptions.
+    pub(crate) fn content(&self) -> Option<(Option<String>, Vec<u8>)> {
+        if let Some(binary) = &self.binary {
+            if binary.content_encoding.is_some() {
+                return None;
+            }
+
+            return Some((
+                self.content_type().map(str::to_owned),
+                self.body()?.to_vec(),
+            ));
+        }
+
+        if let Some((content_type, content)) = self.wrapped_content() {
+            return Some((Some(content_type), content));
+        }
+
+        if self.expansion().is_ok() {
+            return None;
+        }
+
+        Some((
+            self.content_type().map(str::to_owned),
+            self.body()?.to_vec(),
+        ))
+    }
+
+    /// The hex encoded sha256 hash of this inscription's content, if it's in
+    /// the inscription.
+    pub(crate) fn content_hash(&self) -> Option<String> {
+        self.content()
+            .map(|(_content_type, content)| multipart::content_hash(&content))
+    }
+
+    /// The content hash of a reference inscription, whose envelope expansion
+    /// carries the hash of content already on chain in place of the content.
+    pub(crate) fn content_reference(&self) -> Option<String> {
+        if let Some(binary) = &self.binary {
+            if self.body.is_some() {
+                return None;
+            }
+
+            return Some(binary.content_hash?.into_inner().to_hex());
+        }
+
+        let expansion = self.expansion().ok()?;
+
+        if expansion.content.is_some() || expansion.offchain.is_some() || expansion.parts.is_some()
+        {
+            return None;
+        }
+
+        expansion.content_hash
+    }
+
+    /// Why the content of a binary envelope doesn't match the content hash it
+    /// carries, if it doesn't.
+    pub(crate) fn content_hash_error(&self) -> Option<String> {
+        let binary = self.binary.as_ref()?;
+
+        if binary.content_encoding.is_some() {
+            return None;
+        }
+
+        let content_hash = binary.content_hash?.into_inner().to_hex();
+
+        (multipart::content_hash(self.body()?) != content_hash)
+            .then(|| format!("content does not match its content hash {content_hash}"))
+    }
+
+    /// The content type and content hash of an off-chain inscription, whose
+    /// content is distributed over BitTorrent.
+    pub(crate) fn offchain_content(&self) -> Option<(Option<String>, String)> {
+        let expansion = self.expansion().ok()?;
+
+        expansion.offchain?;
+
+        Some((expansion.content_type, expansion.content_hash?))
+    }
+
+    /// The reference inscription of a wrapped inscription, referring to its
+    /// content by `content_hash` instead of carrying it.
+    pub(crate) fn with_reference(
+        &self,
+        content_hash: String,
+    ) -> Result<Inscription, Error> {
+        if self.binary.is_some() {
+            return Ok(Inscription {
+                body: None,
+                parent: None,
+                binary: Some(BinaryEnvelope {
+                    content_encoding: None,
+                    content_hash: Some(content_hash.parse()?),
+                }),
+                ..self.clone()
+            });
+        }
+
+        let expansion = Expansion {
+            compression: None,
+            content: None,
+            content_hash: Some(content_hash),
+            offchain: None,
+            parts: None,
+            files: None,
+            compression_dictionary: None,
+            ..self.expansion()?
+        };
+
+        Ok(Inscription {
+            body: Some(serde_json::to_string(&expansion)?.into()),
+            ..self.clone()
+        })
+    }
+
+    /// The manifest of a multipart inscription, which lists the part
+    /// inscriptions its content was split into.
+    pub(crate) fn manifest(&self) -> Option<Manifest> {
+        let expansion = self.expansion().ok()?;
+
+        Some(Manifest {
+            compression: expansion.compression,
+            content_hash: expansion.content_hash?,
+            content_type: expansion.content_type?,
+            parts: expansion.parts?,
+        })
+    }
+
+    /// Split the content of a wrapped, on-chain inscription into untracked
+    /// part inscriptions of at most `part_size` bytes each. If the content is
+    /// compressed, the compressed content is split.
+    pub(crate) fn parts(
+        &self,
+        part_size: usize,
+    ) -> Result<Vec<Inscription>, Error> {
+        let expansion = self.expansion()?;
+
+        if expansion.offchain.is_some() {
+            bail!("off-chain inscriptions cannot be split into parts");
+        }
+
+        let content = base64::engine::general_purpose::STANDARD.decode(
+            expansion
+                .content
+                .ok_or_else(|| anyhow!("inscription has no content to split into parts"))?,
+        )?;
+
+        Ok(content
+            .chunks(part_size)
+            .map(|chunk| Inscription {
+                content_type: Some("application/octet-stream".as_bytes().to_vec()),
+                body: Some(chunk.to_vec()),
+                tracking: false,
+                content_metadata: None,
+                protocol_properties: None,
+                protocol_id: "pub-v0".into(),
+                parent: None,
+                binary: None,
+            })
+            .collect())
+    }
+
+    /// The manifest inscription of a wrapped, on-chain inscription whose
+    /// content was inscribed as `parts`.
+    pub(crate) fn with_parts(
+        &self,
+        parts: Vec<Part>,
+    ) -> Result<Inscription, Error> {
+        let expansion = Expansion {
+            content: None,
+            parts: Some(parts),
+            ..self.expansion()?
+        };
+
+        Ok(Inscription {
+            body: Some(serde_json::to_string(&expansion)?.into()),
+            ..self.clone()
+        })
+    }
+
+    /// This inscription, with `content` found elsewhere as its body, like the
+    /// reassembled content of a multipart inscription.
+    pub(crate) fn with_content(
+        self,
+        content_type: Option<String>,
+        content: Vec<u8>,
+    ) -> Self {
+        Self {
+            content_type: content_type.map(String::into_bytes),
+            body: Some(content),
+            ..self
+        }
+    }
+
+    fn expansion(&self) -> Result<Expansion, Error> {
+        self.body()
+            .filter(|_| self.content_type() == Some("application/json"))
+            .and_then(|body| serde_json::from_slice::<Expansion>(body).ok())
+            .filter(|expansion| expansion.wrapped)
+            .ok_or_else(|| anyhow!("inscription is not wrapped in an envelope expansion"))
+    }
+
+    pub(crate) fn protocol_properties(&self) -> Option<&str> {
+        self.protocol_properties.as_deref()
+    }
+
+    pub(crate) fn protocol_id(&self) -> &str {
+        &self.protocol_id
+    }
+
+    /// Whether the inscription is tracked, rather than a standalone `pub`
+    /// inscription, such as a part of a multipart inscription.
+    pub(crate) fn tracking(&self) -> bool {
+        self.tracking
+    }
+
+    /// The inscription this inscription declares as its parent. A parent is
+    /// only recognized by the index if the reveal transaction spends it.
+    pub(crate) fn parent(&self) -> Option<InscriptionId> {
+        self.parent
+    }
+
+    pub(crate) fn with_parent(
+        self,
+        parent: InscriptionId,
+    ) -> Self {
+        Self {
+            parent: Some(parent),
+            ..self
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn to_witness(&self) -> Witness {
         let builder = script::Builder::new();
@@ -483,9 +1048,35 @@ impl Inscription {
     }
 }
 
+/// Parse the inscription in `witness`, and everything derived from it,
+/// returning why it isn't a valid inscription if it isn't. Exposed for fuzzing
+/// the parser, which must not panic on any witness.
+pub fn parse_witness(witness: &Witness) -> Result<(), String> {
+    let (inscription, content_error) =
+        InscriptionParser::parse_checked(witness).map_err(|err| err.to_string())?;
+
+    inscription.content();
+    inscription.compressed_content();
+    inscription.content_hash_error();
+    inscription.content_reference();
+    inscription.manifest();
+    inscription.metadata();
+    inscription.offchain_content();
+    inscription.validation_error();
+
+    match content_error {
+        Some(err) => Err(err.to_string()),
+        None => Ok(()),
+    }
+}
+
 #[derive(Debug, PartialEq)]
-enum InscriptionError {
+pub(crate) enum InscriptionError {
+    DecompressionBomb(Codec),
+    DecompressionFailed(CompressionError),
     EmptyWitness,
+    InvalidBase64(String),
+    InvalidExpansion(String),
     InvalidInscription,
     KeyPathSpend,
     NoInscription,
@@ -493,14 +1084,104 @@ enum InscriptionError {
     UnrecognizedEvenField,
 }
 
+impl From<CompressionError> for InscriptionError {
+    fn from(err: CompressionError) -> Self {
+        match err {
+            CompressionError::TooLarge(codec) => Self::DecompressionBomb(codec),
+            err => Self::DecompressionFailed(err),
+        }
+    }
+}
+
+impl Display for InscriptionError {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        match self {
+            Self::DecompressionBomb(codec) => write!(
+                f,
+                "`{codec}` compressed content decompresses to more than {} bytes",
+                compression::MAX_DECOMPRESSED_SIZE
+            ),
+            Self::DecompressionFailed(err) => write!(f, "decompression failed: {err}"),
+            Self::EmptyWitness => write!(f, "witness is empty"),
+            Self::InvalidBase64(err) => write!(f, "content is not valid base64: {err}"),
+            Self::InvalidExpansion(err) => write!(f, "invalid envelope expansion: {err}"),
+            Self::InvalidInscription => write!(f, "invalid inscription envelope"),
+            Self::KeyPathSpend => write!(f, "witness is a key path spend"),
+            Self::NoInscription => write!(f, "witness contains no inscription"),
+            Self::Script(err) => write!(f, "invalid script: {err}"),
+            Self::UnrecognizedEvenField => write!(f, "inscription has an unrecognized even field"),
+        }
+    }
+}
+
+impl std::error::Error for InscriptionError {}
+
 type Result<T, E = InscriptionError> = std::result::Result<T, E>;
 
 struct InscriptionParser<'a> {
     instructions: Peekable<Instructions<'a>>,
+    content_error: Option<InscriptionError>,
 }
 
 impl<'a> InscriptionParser<'a> {
+    #[cfg(test)]
     fn parse(witness: &Witness) -> Result<Inscription> {
+        match Self::parse_checked(witness)? {
+            (inscription, None) => Ok(inscription),
+            (_inscription, Some(err)) => Err(err),
+        }
+    }
+
+    /// Parse the inscription in `witness`, along with the first content error
+    /// found in it, if any. Inscriptions with content errors are returned as
+    /// far as they could be parsed, with content that couldn't be unwrapped or
+    /// decompressed left as it is.
+    fn parse_checked(witness: &Witness) -> Result<(Inscription, Option<InscriptionError>)> {
+        let script = Self::tapscript(witness)?;
+
+        InscriptionParser {
+            instructions: script.instructions().peekable(),
+            content_error: None,
+        }
+        .parse_script()
+    }
+
+    /// Every inscription in `witness`, in order, each along with its content
+    /// error, if any. Envelopes that aren't well formed are skipped.
+    fn parse_all(witness: &Witness) -> Vec<(Inscription, Option<InscriptionError>)> {
+        let Ok(script) = Self::tapscript(witness) else {
+            return Vec::new();
+        };
+
+        let mut parser = InscriptionParser {
+            instructions: script.instructions().peekable(),
+            content_error: None,
+        };
+
+        let mut inscriptions = Vec::new();
+
+        while let Ok(next) = parser.advance() {
+            if next != Instruction::PushBytes(&[]) {
+                continue;
+            }
+
+            match parser.parse_inscription() {
+                Ok(Some(inscription)) => {
+                    inscriptions.push((inscription, parser.content_error.take()));
+                },
+                Ok(None) => {},
+                Err(InscriptionError::Script(_)) => break,
+                Err(_) => parser.content_error = None,
+            }
+        }
+
+        inscriptions
+    }
+
+    fn tapscript(witness: &Witness) -> Result<Script> {
         if witness.is_empty() {
             return Err(InscriptionError::EmptyWitness);
         }
@@ -527,24 +1208,28 @@ impl<'a> InscriptionParser<'a> {
             })
             .unwrap();
 
-        InscriptionParser {
-            instructions: Script::from(Vec::from(script)).instructions().peekable(),
-        }
-        .parse_script()
+        Ok(Script::from(Vec::from(script)))
     }
 
-    fn parse_script(mut self) -> Result<Inscription> {
+    fn parse_script(mut self) -> Result<(Inscription, Option<InscriptionError>)> {
         loop {
             let next = self.advance()?;
 
             if next == Instruction::PushBytes(&[]) {
                 if let Some(inscription) = self.parse_inscription()? {
-                    return Ok(inscription);
+                    return Ok((inscription, self.content_error));
                 }
             }
         }
     }
 
+    fn content_error(
+        &mut self,
+        err: impl Into<InscriptionError>,
+    ) {
+        self.content_error.get_or_insert(err.into());
+    }
+
     fn advance(&mut self) -> Result<Instruction<'a>> {
         self.instructions
             .next()
@@ -554,9 +1239,17 @@ impl<'a> InscriptionParser<'a> {
 
     fn parse_inscription(&mut self) -> Result<Option<Inscription>> {
         if self.advance()? == Instruction::Op(opcodes::all::OP_IF) {
-            if !self.accept(Instruction::PushBytes(b"ord"))? {
-                return Err(InscriptionError::NoInscription);
-            }
+            let (protocol_id, tracking) = match self.advance()? {
+                Instruction::PushBytes(tag) => {
+                    match ProtocolRegistry::current().tag_tracking(tag) {
+                        Some(tracking) => {
+                            (format!("{}-v0", String::from_utf8_lossy(tag)), tracking)
+                        },
+                        None => return Err(InscriptionError::NoInscription),
+                    }
+                },
+                _ => return Err(InscriptionError::NoInscription),
+            };
 
             let mut fields = BTreeMap::new();
 
@@ -570,6 +1263,12 @@ impl<'a> InscriptionParser<'a> {
                         fields.insert(BODY_TAG, body);
                         break;
                     },
+                    Instruction::PushBytes(tag) if CHUNKED_TAGS.contains(&tag) => {
+                        fields
+                            .entry(tag)
+                            .or_insert_with(Vec::new)
+                            .extend_from_slice(self.expect_push()?);
+                    },
                     Instruction::PushBytes(tag) => {
                         if fields.contains_key(tag) {
                             return Err(InscriptionError::InvalidInscription);
@@ -582,7 +1281,7 @@ impl<'a> InscriptionParser<'a> {
             }
 
             let content_type = fields.remove(CONTENT_TYPE_TAG);
-            let mut body = fields.remove(BODY_TAG);
+            let body = fields.remove(BODY_TAG);
 
             for tag in fields.keys() {
                 if let Some(lsb) = tag.first() {
@@ -592,107 +1291,168 @@ impl<'a> InscriptionParser<'a> {
                 }
             }
 
-            if content_type.is_some()
-                && content_type.clone().unwrap() == "application/json".as_bytes().to_vec()
-                && body.is_some()
-            {
-                if let Ok(_utf8) = str::from_utf8(body.clone().unwrap().as_slice()) {
-                    let expansion: Expansion = serde_json::from_str(
-                        str::from_utf8(body.clone().unwrap().as_slice()).unwrap(),
-                    )
-                    .unwrap_or_else(|_| Expansion {
-                        protocol: "error".to_owned(),
-                        protocol_version: "error".to_owned(),
-                        protocol_properties: "".to_owned(),
-                        compression: None,
-                        offchain: None,
-                        content: None,
-                        content_hash: None,
-                        content_type: None,
-                        content_metadata: None,
-                        wrapped: false,
-                    });
-
-                    if expansion.wrapped {
-                        let content_metadata = if expansion.content_metadata.is_some() {
-                            Some(expansion.content_metadata.unwrap().into_bytes())
-                        } else {
-                            None
-                        };
-
-                        let (protocol_properties, tracked) =
-                            if expansion.protocol_properties != *"{}" {
-                                let protocol_protocol_json: serde_json::Value =
-                                    serde_json::from_str(&expansion.protocol_properties)
-                                        .expect("Unable to parse protocol properties!");
-
-                                (
-                                    Some(expansion.protocol_properties),
-                                    protocol_protocol_json["tracking"] == true,
-                                )
-                            } else {
-                                (None, false)
-                            };
-
-                        if expansion.compression.is_some() {
-                            let content = base64::engine::general_purpose::STANDARD
-                                .decode(expansion.content.unwrap())
-                                .unwrap();
-
-                            // Limit input data size to 10MB max to prevent DoS vector.
-                            let max_input_size = 10000000;
-                            let input_cursor = Cursor::new(&content);
-                            let input_limited = input_cursor.take(max_input_size);
-                            #[allow(clippy::cast_possible_truncation)]
-                            let mut limited_reader =
-                                LimitedReader::new(input_limited, max_input_size as usize);
-                            let mut decompressor = Decompressor::new(&mut limited_reader, 4096);
-                            let mut decompressed = Vec::new();
-
-                            match decompressor.read_to_end(&mut decompressed) {
-                                Ok(_) => (),
-                                Err(e) => {
-                                    if e.kind() == std::io::ErrorKind::InvalidData {
-                                        println!("Decompression failed due to invalid data!");
-                                    } else {
-                                        println!("Decompression failed due to an error: {}", e);
-                                    }
-                                },
-                            }
-                            body = Some(decompressed);
-                        }
+            let parent = fields
+                .remove(PARENT_TAG)
+                .and_then(|parent| InscriptionId::from_tag_value(&parent));
 
-                        return Ok(Some(Inscription {
-                            content_type,
-                            body,
-                            tracking: tracked,
-                            content_metadata,
-                            protocol_properties,
-                        }));
-                    }
-                } else {
-                    return Ok(Some(Inscription {
-                        content_type,
-                        body,
-                        tracking: true,
-                        content_metadata: None,
-                        protocol_properties: None,
-                    }));
-                }
+            // Envelopes of unknown versions are parsed as if they were unwrapped
+            let inscription = if fields.get(ENVELOPE_VERSION_TAG).map(Vec::as_slice)
+                == Some(&[BINARY_ENVELOPE_VERSION])
+            {
+                self.binary_inscription(content_type, body, fields, protocol_id)
+            } else if let Some(expansion) = body
+                .as_deref()
+                .filter(|_| content_type.as_deref() == Some(b"application/json".as_slice()))
+                .and_then(|body| serde_json::from_slice::<Expansion>(body).ok())
+                .filter(|expansion| expansion.wrapped)
+            {
+                self.expanded_inscription(content_type, body, expansion)
             } else {
-                return Ok(Some(Inscription {
+                Inscription {
                     content_type,
                     body,
-                    tracking: true,
+                    tracking,
                     content_metadata: None,
                     protocol_properties: None,
-                }));
-            }
+                    protocol_id,
+                    binary: None,
+                    parent: None,
+                }
+            };
+
+            return Ok(Some(Inscription {
+                parent,
+                ..inscription
+            }));
         }
 
         Ok(None)
     }
 
+    /// The inscription wrapped in an envelope expansion, with its content
+    /// decompressed if it's compressed without a dictionary.
+    fn expanded_inscription(
+        &mut self,
+        mut content_type: Option<Vec<u8>>,
+        mut body: Option<Vec<u8>>,
+        expansion: Expansion,
+    ) -> Inscription {
+        let (protocol_properties, tracking) = if expansion.protocol_properties == "{}" {
+            (None, false)
+        } else {
+            let tracking =
+                match serde_json::from_str::<serde_json::Value>(&expansion.protocol_properties) {
+                    Ok(properties) => properties["tracking"] == true,
+                    Err(err) => {
+                        self.content_error(InscriptionError::InvalidExpansion(format!(
+                            "protocol properties are not valid JSON: {err}"
+                        )));
+                        false
+                    },
+                };
+
+            (Some(expansion.protocol_properties), tracking)
+        };
+
+        // Content compressed with a dictionary is decompressed once the
+        // dictionary inscription is at hand.
+        if let Some(content) = &expansion.content {
+            match base64::engine::general_purpose::STANDARD.decode(content) {
+                Ok(content) => {
+                    if let (Some(encoding), None) =
+                        (&expansion.compression, &expansion.compression_dictionary)
+                    {
+                        match Codec::from_encoding(encoding)
+                            .and_then(|codec| codec.decompress(&content, None))
+                        {
+                            Ok(decompressed) => {
+                                body = Some(decompressed);
+                                content_type =
+                                    expansion.content_type.clone().map(String::into_bytes);
+                            },
+                            Err(err) => self.content_error(err),
+                        }
+                    }
+                },
+                Err(err) => self.content_error(InscriptionError::InvalidBase64(err.to_string())),
+            }
+        }
+
+        Inscription {
+            content_type,
+            body,
+            tracking,
+            content_metadata: expansion.content_metadata.map(String::into_bytes),
+            protocol_properties,
+            protocol_id: expansion.protocol,
+            parent: None,
+            binary: None,
+        }
+    }
+
+    /// The inscription in a binary envelope, with its content decompressed if
+    /// it's compressed.
+    fn binary_inscription(
+        &mut self,
+        content_type: Option<Vec<u8>>,
+        mut body: Option<Vec<u8>>,
+        mut fields: BTreeMap<&[u8], Vec<u8>>,
+        protocol_id: String,
+    ) -> Inscription {
+        let protocol_id = fields
+            .remove(PROTOCOL_TAG)
+            .map(|protocol_id| String::from_utf8_lossy(&protocol_id).into_owned())
+            .unwrap_or(protocol_id);
+
+        let protocol_properties = fields
+            .remove(PROTOCOL_PROPERTIES_TAG)
+            .map(|properties| String::from_utf8_lossy(&properties).into_owned());
+
+        let tracking = match protocol_properties
+            .as_deref()
+            .map(serde_json::from_str::<serde_json::Value>)
+        {
+            Some(Ok(properties)) => properties["tracking"] == true,
+            Some(Err(err)) => {
+                self.content_error(InscriptionError::InvalidExpansion(format!(
+                    "protocol properties are not valid JSON: {err}"
+                )));
+                false
+            },
+            None => false,
+        };
+
+        let mut content_encoding = fields
+            .remove(CONTENT_ENCODING_TAG)
+            .map(|encoding| String::from_utf8_lossy(&encoding).into_owned());
+
+        if let (Some(encoding), Some(content)) = (&content_encoding, &body) {
+            match Codec::from_name(encoding).and_then(|codec| codec.decompress(content, None)) {
+                Ok(decompressed) => {
+                    body = Some(decompressed);
+                    content_encoding = None;
+                },
+                Err(err) => self.content_error(err),
+            }
+        }
+
+        Inscription {
+            content_type,
+            body,
+            tracking,
+            content_metadata: fields.remove(CONTENT_METADATA_TAG),
+            protocol_properties,
+            protocol_id,
+            parent: None,
+            binary: Some(BinaryEnvelope {
+                content_encoding,
+                content_hash: fields
+                    .remove(CONTENT_HASH_TAG)
+                    .and_then(|content_hash| sha256::Hash::from_slice(&content_hash).ok()),
+            }),
+        }
+    }
+
     fn expect_push(&mut self) -> Result<&'a [u8]> {
         match self.advance()? {
             Instruction::PushBytes(bytes) => Ok(bytes),
@@ -836,6 +1596,9 @@ mod tests {
                 tracking: true,
                 content_metadata: None,
                 protocol_properties: None,
+                protocol_id: "ord-v0".into(),
+                parent: None,
+                binary: None,
             }),
         );
     }
@@ -850,6 +1613,9 @@ mod tests {
                 tracking: true,
                 content_metadata: None,
                 protocol_properties: None,
+                protocol_id: "ord-v0".into(),
+                parent: None,
+                binary: None,
             }),
         );
     }
@@ -1020,6 +1786,250 @@ mod tests {
         );
     }
 
+    #[test]
+    fn version_qualified_protocol_ids_are_not_magic_numbers() {
+        for tag in [b"ord-v1".as_slice(), b"ord-v1.0.0", b"ord-v0"] {
+            assert_eq!(
+                InscriptionParser::parse(&envelope(&[tag, &[1], b"text/plain", &[], b"foo"])),
+                Err(InscriptionError::NoInscription),
+            );
+        }
+    }
+
+    #[test]
+    fn pub_magic_number() {
+        assert_eq!(
+            InscriptionParser::parse(&envelope(&[
+                b"pub",
+                &[1],
+                b"text/plain;charset=utf-8",
+                &[],
+                b"pub"
+            ])),
+            Ok(Inscription {
+                content_type: Some(b"text/plain;charset=utf-8".to_vec()),
+                body: Some(b"pub".to_vec()),
+                tracking: false,
+                content_metadata: None,
+                protocol_properties: None,
+                protocol_id: "pub-v0".into(),
+                parent: None,
+                binary: None,
+            }),
+        );
+    }
+
+    #[test]
+    fn non_tracking_inscriptions_round_trip() {
+        let inscription = Inscription {
+            tracking: false,
+            protocol_id: "pub-v0".into(),
+            parent: None,
+            binary: None,
+            ..inscription("text/plain;charset=utf-8", "pub")
+        };
+
+        assert_eq!(
+            InscriptionParser::parse(&inscription.to_witness()),
+            Ok(inscription)
+        );
+    }
+
+    #[test]
+    fn wrapped_inscriptions_record_protocol_id() {
+        let expansion = Expansion {
+            protocol: "pub-v1".into(),
+            protocol_version: "\"1.0.0\"".into(),
+            protocol_properties: r#"{"protocol":"pub","tracking":false}"#.into(),
+            compression: None,
+            offchain: None,
+            content: Some(base64::engine::general_purpose::STANDARD.encode("hello")),
+            content_hash: None,
+            content_type: Some("text/plain;charset=utf-8".into()),
+            content_metadata: None,
+            wrapped: true,
+            parts: None,
+            files: None,
+            compression_dictionary: None,
+        };
+
+        let inscription = InscriptionParser::parse(&envelope(&[
+            b"pub",
+            &[1],
+            b"application/json",
+            &[],
+            serde_json::to_string(&expansion).unwrap().as_bytes(),
+        ]))
+        .unwrap();
+
+        assert_eq!(inscription.protocol_id(), "pub-v1");
+        assert!(!inscription.tracking);
+    }
+
+    #[test]
+    fn wrapped_content_is_unwrapped() {
+        let wrapped = Inscription::wrapped(
+            "bid-v1",
+            serde_json::json!({ "name": "alice_1" }),
+            "text/plain;charset=utf-8",
+            b"alice_1",
+        )
+        .unwrap();
+
+        let parsed = InscriptionParser::parse(&wrapped.to_witness()).unwrap();
+
+        assert_eq!(
+            parsed.wrapped_content(),
+            Some(("text/plain;charset=utf-8".into(), b"alice_1".to_vec()))
+        );
+
+        assert_eq!(inscription("text/plain", "foo").wrapped_content(), None);
+    }
+
+    #[test]
+    fn wrapped_content_is_split_into_parts() {
+        let wrapped = Inscription::wrapped(
+            "ord-v1",
+            serde_json::json!({}),
+            "text/plain;charset=utf-8",
+            b"foobar",
+        )
+        .unwrap();
+
+        let parts = wrapped
+            .parts(4)
+            .unwrap()
+            .iter()
+            .map(|part| InscriptionParser::parse(&part.to_witness()).unwrap())
+            .collect::<Vec<Inscription>>();
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].body(), Some(b"foob".as_slice()));
+        assert_eq!(parts[1].body(), Some(b"ar".as_slice()));
+        assert_eq!(parts[0].protocol_id(), "pub-v0");
+        assert!(!parts[0].tracking);
+        assert_eq!(parts[0].manifest(), None);
+
+        let part = Part {
+            inscription_id: txid(1).into(),
+            content_hash: multipart::content_hash(b"foobar"),
+        };
+
+        let manifest =
+            InscriptionParser::parse(&wrapped.with_parts(vec![part.clone()]).unwrap().to_witness())
+                .unwrap();
+
+        assert_eq!(manifest.protocol_id(), "ord-v1");
+        assert_eq!(manifest.wrapped_content(), None);
+        assert_eq!(
+            manifest.manifest(),
+            Some(Manifest {
+                compression: None,
+                content_hash: multipart::content_hash(b"foobar"),
+                content_type: "text/plain;charset=utf-8".into(),
+                parts: vec![part],
+            })
+        );
+
+        assert_eq!(wrapped.manifest(), None);
+        assert_eq!(
+            inscription("text/plain", "foo")
+                .parts(4)
+                .unwrap_err()
+                .to_string(),
+            "inscription is not wrapped in an envelope expansion"
+        );
+    }
+
+    #[test]
+    fn content_is_unwrapped_before_hashing() {
+        let wrapped =
+            Inscription::wrapped("ord-v1", serde_json::json!({}), "text/plain", b"hello").unwrap();
+
+        assert_eq!(
+            wrapped.content(),
+            Some((Some("text/plain".into()), b"hello".to_vec()))
+        );
+        assert_eq!(
+            wrapped.content_hash(),
+            inscription("text/plain", "hello").content_hash()
+        );
+        assert_eq!(wrapped.content_reference(), None);
+
+        let reference = InscriptionParser::parse(
+            &wrapped
+                .with_reference(multipart::content_hash(b"hello"))
+                .unwrap()
+                .to_witness(),
+        )
+        .unwrap();
+
+        assert_eq!(reference.content(), None);
+        assert_eq!(reference.manifest(), None);
+        assert_eq!(
+            reference.content_reference(),
+            Some(multipart::content_hash(b"hello"))
+        );
+    }
+
+    #[test]
+    fn wrapped_inscriptions_are_validated_against_protocol_fields() {
+        let parse = |properties: &str| {
+            let expansion = Expansion {
+                protocol: "ord-v1".into(),
+                protocol_version: "\"1.0.0\"".into(),
+                protocol_properties: properties.into(),
+                compression: None,
+                offchain: None,
+                content: Some(base64::engine::general_purpose::STANDARD.encode("hello")),
+                content_hash: None,
+                content_type: Some("text/plain;charset=utf-8".into()),
+                content_metadata: None,
+                wrapped: true,
+                parts: None,
+                files: None,
+                compression_dictionary: None,
+            };
+
+            InscriptionParser::parse(&envelope(&[
+                b"ord",
+                &[1],
+                b"application/json",
+                &[],
+                serde_json::to_string(&expansion).unwrap().as_bytes(),
+            ]))
+            .unwrap()
+        };
+
+        assert_eq!(
+            parse(r#"{"protocol":"ord","tracking":true,"title":"foo"}"#).validation_error(),
+            None
+        );
+        assert_eq!(
+            parse(r#"{"protocol":"ord","tracking":true,"title":5}"#).validation_error(),
+            Some("property `title` must be a string".into())
+        );
+        assert_eq!(
+            parse(r#"{"protocol":"ord","tracking":true,"color":"red"}"#).validation_error(),
+            Some("unknown property `color` for protocol `ord-v1.0.0`".into())
+        );
+        assert_eq!(inscription("text/plain", "foo").validation_error(), None);
+    }
+
+    #[test]
+    fn unwrapped_json_is_not_skipped() {
+        assert_eq!(
+            InscriptionParser::parse(&envelope(&[
+                b"ord",
+                &[1],
+                b"application/json",
+                &[],
+                br#"{"foo":"bar"}"#,
+            ])),
+            Ok(inscription("application/json", r#"{"foo":"bar"}"#)),
+        );
+    }
+
     #[test]
     fn extract_from_transaction() {
         let tx = Transaction {
@@ -1036,12 +2046,12 @@ mod tests {
 
         assert_eq!(
             Inscription::from_transaction(&tx),
-            Some(inscription("text/plain;charset=utf-8", "ord")),
+            vec![inscription("text/plain;charset=utf-8", "ord")],
         );
     }
 
     #[test]
-    fn do_not_extract_from_second_input() {
+    fn extract_from_second_input() {
         let tx = Transaction {
             version: 0,
             lock_time: bitcoin::PackedLockTime(0),
@@ -1062,11 +2072,14 @@ mod tests {
             output: Vec::new(),
         };
 
-        assert_eq!(Inscription::from_transaction(&tx), None);
+        assert_eq!(
+            Inscription::from_transaction(&tx),
+            vec![inscription("foo", [1; 1040])]
+        );
     }
 
     #[test]
-    fn do_not_extract_from_second_envelope() {
+    fn extract_from_second_envelope() {
         let mut builder = script::Builder::new();
         builder = inscription("foo", [1; 100]).append_reveal_script_to_builder(builder);
         builder = inscription("bar", [1; 100]).append_reveal_script_to_builder(builder);
@@ -1087,7 +2100,7 @@ mod tests {
 
         assert_eq!(
             Inscription::from_transaction(&tx),
-            Some(inscription("foo", [1; 100]))
+            vec![inscription("foo", [1; 100]), inscription("bar", [1; 100])]
         );
     }
 
@@ -1175,6 +2188,9 @@ mod tests {
                 tracking: true,
                 content_metadata: None,
                 protocol_properties: None,
+                protocol_id: "ord-v0".into(),
+                parent: None,
+                binary: None,
             }
             .append_reveal_script(script::Builder::new()),
         );
@@ -1189,6 +2205,9 @@ mod tests {
                 tracking: true,
                 content_metadata: None,
                 protocol_properties: None,
+                protocol_id: "ord-v0".into(),
+                parent: None,
+                binary: None,
             }
         );
     }
@@ -1203,6 +2222,9 @@ mod tests {
                 tracking: true,
                 content_metadata: None,
                 protocol_properties: None,
+                protocol_id: "ord-v0".into(),
+                parent: None,
+                binary: None,
             }),
         );
     }
@@ -1214,4 +2236,323 @@ mod tests {
             Err(InscriptionError::UnrecognizedEvenField),
         );
     }
+
+    fn file_inscription(
+        content: &[u8],
+        compression: Option<Compression>,
+        metadata: Option<&str>,
+        binary_envelope: bool,
+    ) -> Inscription {
+        let tempdir = TempDir::new().unwrap();
+        let path = tempdir.path().join("foo.txt");
+        fs::write(&path, content).unwrap();
+
+        let metadata_path = metadata.map(|metadata| {
+            let metadata_path = tempdir.path().join("metadata.json");
+            fs::write(&metadata_path, metadata).unwrap();
+            metadata_path
+        });
+
+        Inscription::from_file(
+            Chain::Mainnet,
+            path,
+            None,
+            None,
+            compression,
+            None,
+            binary_envelope,
+            false,
+            false,
+            &TorrentOptions::default(),
+            metadata_path,
+            None::<PathBuf>,
+            None,
+            "ord-v1".into(),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parents_round_trip() {
+        for inscription in [
+            inscription("text/plain", "foo"),
+            file_inscription(b"foo", None, None, false),
+            file_inscription(&[0xFF; 100], None, None, true),
+        ] {
+            let inscription = inscription.with_parent(inscription_id(1));
+
+            let parsed = InscriptionParser::parse(&inscription.to_witness()).unwrap();
+
+            assert_eq!(parsed.parent(), Some(inscription_id(1)));
+            assert_eq!(parsed, inscription);
+        }
+    }
+
+    #[test]
+    fn malformed_parents_are_ignored() {
+        assert_eq!(
+            InscriptionParser::parse(&envelope(&[
+                b"ord",
+                &[1],
+                b"text/plain",
+                &[3],
+                &[1; 31],
+                &[],
+                b"foo"
+            ]))
+            .unwrap()
+            .parent(),
+            None
+        );
+    }
+
+    #[test]
+    fn binary_envelopes_round_trip() {
+        let content = [0xFF; 100];
+
+        let inscription = file_inscription(&content, None, None, true);
+
+        let parsed = InscriptionParser::parse(&inscription.to_witness()).unwrap();
+
+        assert_eq!(parsed, inscription);
+        assert_eq!(parsed.protocol_id(), "ord-v1");
+        assert_eq!(parsed.content_type(), Some("text/plain;charset=utf-8"));
+        assert_eq!(parsed.body(), Some(content.as_slice()));
+        assert_eq!(
+            parsed.wrapped_content(),
+            Some(("text/plain;charset=utf-8".into(), content.to_vec()))
+        );
+        assert_eq!(parsed.content_hash_error(), None);
+    }
+
+    #[test]
+    fn binary_envelopes_are_smaller_than_envelope_expansions() {
+        let content = [0xFF; 1000];
+
+        let binary = file_inscription(&content, None, None, true)
+            .append_reveal_script(script::Builder::new())
+            .len();
+        let json = file_inscription(&content, None, None, false)
+            .append_reveal_script(script::Builder::new())
+            .len();
+
+        assert!(binary + 300 < json, "{binary} {json}");
+    }
+
+    #[test]
+    fn binary_envelope_content_is_decompressed() {
+        let content = "foo".repeat(1000);
+
+        let inscription = file_inscription(content.as_bytes(), Some(Compression::Zstd), None, true);
+
+        assert!(inscription.body().unwrap().len() < 100);
+        assert_eq!(inscription.content(), None);
+
+        let parsed = InscriptionParser::parse(&inscription.to_witness()).unwrap();
+
+        assert_eq!(parsed.body(), Some(content.as_bytes()));
+        assert_eq!(
+            parsed.content(),
+            Some((
+                Some("text/plain;charset=utf-8".into()),
+                content.as_bytes().to_vec()
+            ))
+        );
+        assert_eq!(parsed.compressed_content(), None);
+        assert_eq!(parsed.content_hash_error(), None);
+    }
+
+    #[test]
+    fn long_binary_envelope_fields_are_chunked() {
+        let metadata = format!("{{\"foo\":\"{}\"}}", "a".repeat(1000));
+
+        let inscription = file_inscription(b"foo", None, Some(&metadata), true);
+
+        let parsed = InscriptionParser::parse(&inscription.to_witness()).unwrap();
+
+        assert_eq!(
+            parsed.metadata(),
+            Some(serde_json::from_str(&metadata).unwrap())
+        );
+        assert_eq!(parsed, inscription);
+    }
+
+    #[test]
+    fn binary_envelope_content_must_match_its_hash() {
+        let mut inscription = file_inscription(b"foo", None, None, true);
+
+        inscription.body = Some(b"bar".to_vec());
+
+        let parsed = InscriptionParser::parse(&inscription.to_witness()).unwrap();
+
+        assert_eq!(
+            parsed.content_hash_error(),
+            Some(format!(
+                "content does not match its content hash {}",
+                multipart::content_hash(b"foo")
+            ))
+        );
+    }
+
+    #[test]
+    fn binary_envelopes_may_refer_to_content_by_hash() {
+        let inscription = file_inscription(b"foo", None, None, true)
+            .with_reference(multipart::content_hash(b"foo"))
+            .unwrap();
+
+        let parsed = InscriptionParser::parse(&inscription.to_witness()).unwrap();
+
+        assert_eq!(parsed.body(), None);
+        assert_eq!(parsed.content(), None);
+        assert_eq!(
+            parsed.content_reference(),
+            Some(multipart::content_hash(b"foo"))
+        );
+    }
+
+    #[test]
+    fn binary_envelopes_of_unknown_versions_are_not_unwrapped() {
+        assert_eq!(
+            InscriptionParser::parse(&envelope(&[b"ord", &[17], &[2], &[7], b"foo-v1"]))
+                .unwrap()
+                .protocol_id(),
+            "ord-v0"
+        );
+    }
+
+    fn expansion_witness(
+        protocol_properties: &str,
+        compression: Option<&str>,
+        content: &str,
+    ) -> Witness {
+        let expansion = serde_json::json!({
+            "protocol": "ord-v1",
+            "protocol_version": "\"1.0.0\"",
+            "protocol_properties": protocol_properties,
+            "compression": compression,
+            "offchain": null,
+            "content": content,
+            "content_hash": null,
+            "content_type": "text/plain;charset=utf-8",
+            "content_metadata": null,
+            "wrapped": true,
+        });
+
+        envelope(&[
+            b"ord",
+            &[1],
+            b"application/json",
+            &[],
+            expansion.to_string().as_bytes(),
+        ])
+    }
+
+    #[test]
+    fn malformed_protocol_properties_are_an_error() {
+        let witness = expansion_witness("{", None, "Zm9v");
+
+        assert!(matches!(
+            InscriptionParser::parse(&witness),
+            Err(InscriptionError::InvalidExpansion(_))
+        ));
+
+        let (inscription, content_error) = InscriptionParser::parse_checked(&witness).unwrap();
+
+        assert_eq!(inscription.protocol_id(), "ord-v1");
+        assert_eq!(inscription.protocol_properties(), Some("{"));
+        assert_eq!(
+            content_error.unwrap().to_string(),
+            "invalid envelope expansion: protocol properties are not valid JSON: EOF while \
+            parsing an object at line 1 column 1"
+        );
+    }
+
+    #[test]
+    fn invalid_base64_content_is_an_error() {
+        assert_eq!(
+            InscriptionParser::parse(&expansion_witness("{}", Some("br base64"), "!!!!")),
+            Err(InscriptionError::InvalidBase64(
+                "Invalid byte 33, offset 0.".into()
+            ))
+        );
+        assert!(matches!(
+            InscriptionParser::parse(&expansion_witness("{}", None, "!!!!")),
+            Err(InscriptionError::InvalidBase64(_))
+        ));
+    }
+
+    #[test]
+    fn decompressed_content_has_the_wrapped_content_type() {
+        let inscription = InscriptionParser::parse(&expansion_witness(
+            "{}",
+            Some("br base64"),
+            &base64::engine::general_purpose::STANDARD
+                .encode(Codec::Brotli.compress(b"foo", None).unwrap()),
+        ))
+        .unwrap();
+
+        assert_eq!(inscription.content_type(), Some("text/plain;charset=utf-8"));
+        assert_eq!(inscription.media(), Media::Text);
+        assert_eq!(
+            inscription.content(),
+            Some((Some("text/plain;charset=utf-8".into()), b"foo".to_vec()))
+        );
+    }
+
+    #[test]
+    fn content_that_cannot_be_decompressed_is_an_error() {
+        assert!(matches!(
+            InscriptionParser::parse(&expansion_witness("{}", Some("gzip base64"), "Zm9v")),
+            Err(InscriptionError::DecompressionFailed(
+                CompressionError::InvalidContent {
+                    codec: Codec::Gzip,
+                    ..
+                }
+            ))
+        ));
+        assert_eq!(
+            InscriptionParser::parse(&expansion_witness("{}", Some("lzma base64"), "Zm9v")),
+            Err(InscriptionError::DecompressionFailed(
+                CompressionError::UnknownCodec("lzma base64".into())
+            ))
+        );
+    }
+
+    #[test]
+    fn decompression_bombs_are_an_error() {
+        let bomb = Codec::Zstd
+            .compress(&vec![0; compression::MAX_DECOMPRESSED_SIZE + 1], None)
+            .unwrap();
+
+        assert_eq!(
+            InscriptionParser::parse(&expansion_witness(
+                "{}",
+                Some("zstd base64"),
+                &base64::engine::general_purpose::STANDARD.encode(bomb)
+            )),
+            Err(InscriptionError::DecompressionBomb(Codec::Zstd))
+        );
+    }
+
+    #[test]
+    fn malformed_binary_envelopes_are_an_error() {
+        let (inscription, content_error) = InscriptionParser::parse_checked(&envelope(&[
+            b"ord",
+            &[17],
+            &[1],
+            &[9],
+            b"gzip",
+            &[],
+            b"foo",
+        ]))
+        .unwrap();
+
+        assert!(matches!(
+            content_error,
+            Some(InscriptionError::DecompressionFailed(_))
+        ));
+        assert_eq!(inscription.body(), Some(b"foo".as_slice()));
+        assert_eq!(inscription.content(), None);
+    }
 }
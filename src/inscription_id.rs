@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, PartialEq, Copy, Clone, Hash, Eq)]
+#[derive(Debug, PartialEq, Copy, Clone, Hash, Eq, PartialOrd, Ord)]
 pub struct InscriptionId {
     pub(crate) txid: Txid,
     pub(crate) index: u32,
@@ -94,6 +94,45 @@ impl FromStr for InscriptionId {
     }
 }
 
+impl InscriptionId {
+    /// The value of an envelope tag referring to this inscription: its txid,
+    /// followed by its index as little-endian bytes, without trailing zeros.
+    pub(crate) fn tag_value(self) -> Vec<u8> {
+        let mut value = self.txid.to_vec();
+
+        let index = self.index.to_le_bytes();
+
+        value.extend_from_slice(
+            &index[..index
+                .iter()
+                .rposition(|byte| *byte != 0)
+                .map_or(0, |i| i + 1)],
+        );
+
+        value
+    }
+
+    pub(crate) fn from_tag_value(value: &[u8]) -> Option<Self> {
+        if value.len() < 32 || value.len() > 36 {
+            return None;
+        }
+
+        let (txid, index) = value.split_at(32);
+
+        if index.last() == Some(&0) {
+            return None;
+        }
+
+        let mut index_bytes = [0; 4];
+        index_bytes[..index.len()].copy_from_slice(index);
+
+        Some(Self {
+            txid: Txid::from_slice(txid).ok()?,
+            index: u32::from_le_bytes(index_bytes),
+        })
+    }
+}
+
 impl From<Txid> for InscriptionId {
     fn from(txid: Txid) -> Self {
         Self { txid, index: 0 }
@@ -195,4 +234,32 @@ mod tests {
             Err(ParseError::Txid(_)),
         );
     }
+
+    #[test]
+    fn tag_values_round_trip() {
+        for index in [0, 1, 255, 256, u32::MAX] {
+            let inscription_id = InscriptionId {
+                txid: txid(1),
+                index,
+            };
+
+            assert_eq!(
+                InscriptionId::from_tag_value(&inscription_id.tag_value()),
+                Some(inscription_id)
+            );
+        }
+
+        assert_eq!(inscription_id(1).tag_value().len(), 33);
+        assert_eq!(InscriptionId::from(txid(1)).tag_value().len(), 32);
+    }
+
+    #[test]
+    fn invalid_tag_values() {
+        assert_eq!(InscriptionId::from_tag_value(&[1; 31]), None);
+        assert_eq!(InscriptionId::from_tag_value(&[1; 37]), None);
+        assert_eq!(
+            InscriptionId::from_tag_value(&[[1; 32], [0; 32]].concat()[..33]),
+            None
+        );
+    }
 }
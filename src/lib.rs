@@ -13,7 +13,12 @@
 use {
     self::{
         arguments::Arguments,
+        bfs::{
+            BfsPath,
+            DirectoryEntry,
+        },
         blocktime::Blocktime,
+        bnw::WallName,
         config::Config,
         decimal::Decimal,
         degree::Degree,
@@ -21,17 +26,34 @@ use {
         epoch::Epoch,
         height::Height,
         index::{
+            Cursor,
+            Event,
             Index,
+            InscriptionQuery,
             List,
+            Pending,
+            PendingKind,
+            Transfer,
         },
         inscription::Inscription,
         inscription_id::InscriptionId,
         media::Media,
+        multipart::Manifest,
+        off_chain::{
+            TorrentFile,
+            TorrentOptions,
+            TorrentVersion,
+        },
         options::Options,
         outgoing::Outgoing,
+        protocol_registry::{
+            ProtocolRegistry,
+            ProtocolSpec,
+        },
         representation::Representation,
         subcommand::Subcommand,
         tally::Tally,
+        username::Username,
     },
     anyhow::{
         anyhow,
@@ -152,6 +174,7 @@ use {
 
 pub use crate::{
     fee_rate::FeeRate,
+    inscription::parse_witness,
     object::Object,
     rarity::Rarity,
     sat::Sat,
@@ -177,8 +200,11 @@ macro_rules! tprintln {
 }
 
 mod arguments;
+mod bfs;
 mod blocktime;
+mod bnw;
 mod chain;
+mod compression;
 mod config;
 mod decimal;
 mod degree;
@@ -190,11 +216,13 @@ mod index;
 mod inscription;
 mod inscription_id;
 mod media;
+mod multipart;
 mod object;
 mod off_chain;
 mod options;
 mod outgoing;
 mod page_config;
+mod protocol_registry;
 mod rarity;
 mod representation;
 mod sat;
@@ -202,6 +230,7 @@ mod sat_point;
 pub mod subcommand;
 mod tally;
 mod templates;
+mod username;
 mod wallet;
 
 type Result<T = (), E = Error> = std::result::Result<T, E>;
@@ -1,5 +1,6 @@
 use {
     super::*,
+    clap::ValueEnum,
     mp4::{
         MediaType,
         Mp4Reader,
@@ -11,7 +12,8 @@ use {
     },
 };
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Copy, Clone, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum Media {
     Audio,
     Iframe,
@@ -100,6 +102,27 @@ impl Media {
     }
 }
 
+impl Display for Media {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Audio => "audio",
+                Self::Iframe => "iframe",
+                Self::Image => "image",
+                Self::Pdf => "pdf",
+                Self::Text => "text",
+                Self::Unknown => "unknown",
+                Self::Video => "video",
+            }
+        )
+    }
+}
+
 impl FromStr for Media {
     type Err = Error;
 
@@ -0,0 +1,147 @@
+use {
+    super::*,
+    crate::compression::Codec,
+    bitcoin::hashes::{
+        hex::ToHex,
+        sha256,
+        Hash,
+    },
+};
+
+/// The largest part size that keeps part reveal transactions below the
+/// standard transaction weight limit.
+pub(crate) const DEFAULT_PART_SIZE: usize = 380_000;
+
+/// The hex encoded sha256 hash of `content`, as used in envelope expansions.
+pub(crate) fn content_hash(content: &[u8]) -> String {
+    sha256::Hash::hash(content).into_inner().to_vec().to_hex()
+}
+
+/// A part of the content of a multipart inscription, inscribed on its own.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub(crate) struct Part {
+    pub(crate) inscription_id: InscriptionId,
+    pub(crate) content_hash: String,
+}
+
+/// The manifest of a multipart inscription, which lists the inscriptions
+/// holding its content in order, and the hash of their concatenation.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Manifest {
+    pub(crate) compression: Option<String>,
+    pub(crate) content_hash: String,
+    pub(crate) content_type: String,
+    pub(crate) parts: Vec<Part>,
+}
+
+impl Manifest {
+    /// Reassemble the content of the multipart inscription from the `bodies` of
+    /// its parts, verifying them against the manifest.
+    pub(crate) fn assemble(
+        &self,
+        bodies: &[Vec<u8>],
+    ) -> Result<Vec<u8>> {
+        if bodies.len() != self.parts.len() {
+            bail!(
+                "multipart inscription has {} parts but {} were given",
+                self.parts.len(),
+                bodies.len()
+            );
+        }
+
+        let mut content = Vec::new();
+
+        for (i, (part, body)) in self.parts.iter().zip(bodies).enumerate() {
+            if content_hash(body) != part.content_hash {
+                bail!(
+                    "part {i} of multipart inscription, {}, does not match its content hash",
+                    part.inscription_id
+                );
+            }
+
+            content.extend_from_slice(body);
+        }
+
+        if content_hash(&content) != self.content_hash {
+            bail!("multipart inscription content does not match its content hash");
+        }
+
+        if let Some(encoding) = &self.compression {
+            content = Codec::from_encoding(encoding)
+                .and_then(|codec| codec.decompress(&content, None))
+                .context("multipart inscription content could not be decompressed")?;
+        }
+
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(parts: &[&[u8]]) -> Manifest {
+        Manifest {
+            compression: None,
+            content_hash: content_hash(&parts.concat()),
+            content_type: "text/plain".into(),
+            parts: parts
+                .iter()
+                .enumerate()
+                .map(|(i, part)| Part {
+                    inscription_id: txid(i as u64 + 1).into(),
+                    content_hash: content_hash(part),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parts_are_reassembled_in_order() {
+        assert_eq!(
+            manifest(&[b"foo", b"bar"])
+                .assemble(&[b"foo".to_vec(), b"bar".to_vec()])
+                .unwrap(),
+            b"foobar"
+        );
+    }
+
+    #[test]
+    fn parts_must_match_their_hashes() {
+        assert_eq!(
+            manifest(&[b"foo", b"bar"])
+                .assemble(&[b"bar".to_vec(), b"foo".to_vec()])
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "part 0 of multipart inscription, {}, does not match its content hash",
+                InscriptionId::from(txid(1))
+            )
+        );
+    }
+
+    #[test]
+    fn content_must_match_its_hash() {
+        let mut manifest = manifest(&[b"foo", b"bar"]);
+        manifest.content_hash = content_hash(b"baz");
+
+        assert_eq!(
+            manifest
+                .assemble(&[b"foo".to_vec(), b"bar".to_vec()])
+                .unwrap_err()
+                .to_string(),
+            "multipart inscription content does not match its content hash"
+        );
+    }
+
+    #[test]
+    fn missing_parts_are_an_error() {
+        assert_eq!(
+            manifest(&[b"foo", b"bar"])
+                .assemble(&[b"foo".to_vec()])
+                .unwrap_err()
+                .to_string(),
+            "multipart inscription has 2 parts but 1 were given"
+        );
+    }
+}
@@ -1,19 +1,40 @@
 // Based on the work by shesek in: https://github.com/casey/ord/pull/1805
 use {
-    super::Error,
-    anyhow::Context,
+    super::{
+        integration_test,
+        Error,
+    },
+    anyhow::{
+        bail,
+        Context,
+    },
     bitcoin::hashes::{
         hex::ToHex,
+        sha1,
         sha256,
         Hash,
+        HashEngine,
+    },
+    clap::ValueEnum,
+    indicatif::{
+        ProgressBar,
+        ProgressStyle,
     },
-    lava_torrent::{
-        bencode::BencodeElem,
-        torrent::v1::TorrentBuilder,
+    lava_torrent::bencode::BencodeElem,
+    serde::{
+        Deserialize,
+        Serialize,
     },
     std::{
+        collections::HashMap,
         ffi::OsString,
         fs,
+        io::{
+            self,
+            BufReader,
+            Read,
+        },
+        mem,
         path::{
             Path,
             PathBuf,
@@ -23,58 +44,548 @@ use {
 };
 
 // Bittorrent piece length (1MB)
-const PIECE_LENGTH: i64 = 1048576;
+const PIECE_LENGTH: usize = 1048576;
+
+// Bittorrent v2 merkle tree leaf block size (16KiB)
+const BLOCK_SIZE: usize = 16384;
+
+const BLOCKS_PER_PIECE: usize = PIECE_LENGTH / BLOCK_SIZE;
 
 // Default tracker URIs (included in .torrent & magnet links)
-// Note: support multiple, add wss tracker
 pub const DEFAULT_TRACKER: &str = "udp://tracker.openbittorrent.com:6969";
+pub const DEFAULT_WSS_TRACKER: &str = "wss://tracker.openwebtorrent.com";
 
 // Bootstrap peers for DHT discovery (included in .torrent & magnet links)
 pub const DEFAULT_PEER: &str = "dht.aelitis.com:6881";
 
+/// The BitTorrent metainfo version of a torrent. v2 torrents are identified by
+/// a SHA-256 infohash, and hybrid torrents by both a v1 and a v2 infohash.
+#[derive(Default, ValueEnum, Copy, Clone, Debug, PartialEq)]
+pub(crate) enum TorrentVersion {
+    #[default]
+    V1,
+    V2,
+    Hybrid,
+}
+
+#[derive(Default, Clone, Debug)]
+pub(crate) struct TorrentOptions {
+    pub(crate) path: Option<PathBuf>,
+    pub(crate) trackers: Vec<String>,
+    pub(crate) peers: Vec<String>,
+    pub(crate) web_seeds: Vec<String>,
+    pub(crate) version: TorrentVersion,
+}
+
+impl TorrentOptions {
+    fn validate(&self) -> Result<(), Error> {
+        for tracker in &self.trackers {
+            if !["udp://", "http://", "https://", "ws://", "wss://"]
+                .iter()
+                .any(|scheme| tracker.starts_with(scheme))
+            {
+                bail!("Torrent tracker `{tracker}` must be a udp, http(s) or ws(s) URL!");
+            }
+        }
+
+        for web_seed in &self.web_seeds {
+            if !web_seed.starts_with("http://") && !web_seed.starts_with("https://") {
+                bail!("Torrent web seed `{web_seed}` must be an http(s) URL!");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn peers(&self) -> impl Iterator<Item = &str> {
+        self.peers.iter().flat_map(|peers| peers.split_whitespace())
+    }
+}
+
+/// A file in an off-chain torrent, with the hex encoded SHA-256 hash of its
+/// content.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub(crate) struct TorrentFile {
+    pub(crate) path: String,
+    pub(crate) length: u64,
+    pub(crate) content_hash: String,
+}
+
+/// The torrent of an off-chain inscription. Torrents of a single file have its
+/// content hash, and torrents of a directory a manifest of its files.
+#[derive(Debug, PartialEq)]
+pub(crate) struct OffchainTorrent {
+    pub(crate) magnet: String,
+    pub(crate) content_hash: Option<String>,
+    pub(crate) files: Option<Vec<TorrentFile>>,
+}
+
+/// Create a torrent for the file or directory at `path`, hashing its content
+/// in a single streaming pass.
 pub(crate) fn make_offchain_inscription(
-    file_path: impl AsRef<Path>,
-    torrent_path: Option<impl AsRef<Path>>,
-    tracker_url: &str,
-    peer_addr: &str,
-) -> Result<[String; 2], Error> {
-    // TorrentBuilder requires absolute paths
-    let file_path = fs::canonicalize(file_path)?;
-
-    // Create the torrent and gets its infohash
-    let torrent = TorrentBuilder::new(&file_path, PIECE_LENGTH)
-        .set_announce(Some(tracker_url.to_string()))
-        .add_extra_field("nodes".to_string(), bencode_nodes(peer_addr))
-        .build()
-        .with_context(|| "TorrentBuilder failed")?;
-    let infohash = torrent.info_hash_bytes();
+    path: impl AsRef<Path>,
+    options: &TorrentOptions,
+) -> Result<OffchainTorrent, Error> {
+    options.validate()?;
+
+    let path = fs::canonicalize(path)?;
+
+    let name = path
+        .file_name()
+        .context("file has no name")?
+        .to_string_lossy()
+        .into_owned();
+
+    let directory = path.is_dir();
+
+    let files = if directory {
+        let mut files = Vec::new();
+        walk(&path, &mut Vec::new(), &mut files)?;
+        files.sort();
+        if files.is_empty() {
+            bail!("directory {} contains no files", path.display());
+        }
+        files
+    } else {
+        vec![(vec![name.clone()], path.clone())]
+    };
+
+    let total = files
+        .iter()
+        .map(|(_, path)| fs::metadata(path).map(|metadata| metadata.len()))
+        .sum::<io::Result<u64>>()?;
+
+    let progress_bar = if cfg!(test) || integration_test() {
+        ProgressBar::hidden()
+    } else {
+        let progress_bar = ProgressBar::new(total);
+        progress_bar.set_style(
+            ProgressStyle::with_template("[hashing {msg}] {wide_bar} {bytes}/{total_bytes}")
+                .unwrap(),
+        );
+        progress_bar.set_message(name.clone());
+        progress_bar
+    };
+
+    let mut hasher = TorrentHasher::new(options.version);
+    let mut v1_files = Vec::new();
+    let mut file_tree = HashMap::new();
+    let mut piece_layers = HashMap::new();
+    let mut manifest = Vec::new();
+
+    for (i, (components, file_path)) in files.iter().enumerate() {
+        let file = fs::File::open(file_path)
+            .with_context(|| format!("io error reading {}", file_path.display()))?;
+
+        let hashes = hasher
+            .hash_file(BufReader::new(file), &progress_bar)
+            .with_context(|| format!("io error reading {}", file_path.display()))?;
+
+        let length = i64::try_from(hashes.length)?;
+
+        v1_files.push(BencodeElem::Dictionary(HashMap::from([
+            ("length".to_string(), length.into()),
+            (
+                "path".to_string(),
+                BencodeElem::List(components.iter().map(|c| c.as_str().into()).collect()),
+            ),
+        ])));
+
+        // Hybrid torrents align files to piece boundaries with padding files
+        if directory && i + 1 < files.len() {
+            if let Some(padding) = hasher.pad() {
+                let padding = i64::try_from(padding)?;
+                v1_files.push(BencodeElem::Dictionary(HashMap::from([
+                    ("attr".to_string(), "p".into()),
+                    ("length".to_string(), padding.into()),
+                    (
+                        "path".to_string(),
+                        BencodeElem::List(vec![".pad".into(), padding.to_string().into()]),
+                    ),
+                ])));
+            }
+        }
+
+        let mut file_info = HashMap::from([("length".to_string(), length.into())]);
+        if let Some(pieces_root) = hashes.pieces_root {
+            file_info.insert("pieces root".to_string(), pieces_root.to_vec().into());
+            if let Some(piece_layer) = hashes.piece_layer {
+                piece_layers.insert(pieces_root.to_vec(), piece_layer.into());
+            }
+        }
+        insert_file_tree_entry(
+            &mut file_tree,
+            components,
+            BencodeElem::Dictionary(file_info),
+        );
+
+        manifest.push(TorrentFile {
+            path: components.join("/"),
+            length: hashes.length,
+            content_hash: hashes.content_hash.to_hex(),
+        });
+    }
+
+    progress_bar.finish_and_clear();
+
+    let version = options.version;
+
+    let mut info = HashMap::from([
+        ("name".to_string(), name.into()),
+        (
+            "piece length".to_string(),
+            i64::try_from(PIECE_LENGTH)?.into(),
+        ),
+    ]);
+
+    if version != TorrentVersion::V2 {
+        info.insert("pieces".to_string(), hasher.finish().into());
+        if directory {
+            info.insert("files".to_string(), BencodeElem::List(v1_files));
+        } else {
+            info.insert(
+                "length".to_string(),
+                i64::try_from(manifest[0].length)?.into(),
+            );
+        }
+    }
+
+    if version != TorrentVersion::V1 {
+        info.insert("meta version".to_string(), BencodeElem::Integer(2));
+        info.insert("file tree".to_string(), BencodeElem::Dictionary(file_tree));
+    }
+
+    let info = BencodeElem::Dictionary(info);
+
+    let v1_infohash = (version != TorrentVersion::V2)
+        .then(|| sha1::Hash::hash(&info.encode()).into_inner().to_vec());
+    let v2_infohash = (version != TorrentVersion::V1)
+        .then(|| sha256::Hash::hash(&info.encode()).into_inner().to_vec());
+
+    let mut torrent = HashMap::from([
+        ("info".to_string(), info),
+        ("nodes".to_string(), bencode_nodes(options.peers())),
+    ]);
+    if let Some(tracker) = options.trackers.first() {
+        torrent.insert("announce".to_string(), tracker.as_str().into());
+        torrent.insert(
+            "announce-list".to_string(),
+            BencodeElem::List(
+                options
+                    .trackers
+                    .iter()
+                    .map(|tracker| BencodeElem::List(vec![tracker.as_str().into()]))
+                    .collect(),
+            ),
+        );
+    }
+    if !options.web_seeds.is_empty() {
+        torrent.insert(
+            "url-list".to_string(),
+            BencodeElem::List(
+                options
+                    .web_seeds
+                    .iter()
+                    .map(|web_seed| web_seed.as_str().into())
+                    .collect(),
+            ),
+        );
+    }
+    if version != TorrentVersion::V1 && !piece_layers.is_empty() {
+        torrent.insert(
+            "piece layers".to_string(),
+            BencodeElem::RawDictionary(piece_layers),
+        );
+    }
 
     // Write the .torrent file (by default, to <path>.torrent)
-    let torrent_path = get_torrent_path(&file_path, torrent_path);
+    let torrent_path = get_torrent_path(&path, options.path.as_ref());
     log::info!(
         "Writing torrent with infohash {} to {}",
-        infohash.to_hex(),
+        v1_infohash
+            .as_ref()
+            .or(v2_infohash.as_ref())
+            .unwrap()
+            .to_hex(),
         torrent_path.display()
     );
-    torrent
-        .write_into_file(torrent_path)
+    BencodeElem::Dictionary(torrent)
+        .write_into_file(&torrent_path)
         .with_context(|| "failed writing .torrent file")?;
 
-    // Calculate the file's SHA256
-    // Note: streaming hash to avoid loading the entire file in memory
-    let contents = fs::read(&file_path)
-        .with_context(|| format!("io error reading {}", file_path.display()))?;
-    let sha256hash = sha256::Hash::hash(&contents).into_inner().to_vec();
-
-    Ok([
-        format!(
-            "magnet:?xt=urn:btih:{}&tr={}&x.pe={}",
-            infohash.to_hex(),
-            encode(DEFAULT_TRACKER),
-            encode(DEFAULT_PEER),
-        ),
-        sha256hash.to_hex(),
-    ])
+    let magnet = magnet_link(v1_infohash, v2_infohash, options);
+
+    Ok(if directory {
+        OffchainTorrent {
+            magnet,
+            content_hash: None,
+            files: Some(manifest),
+        }
+    } else {
+        OffchainTorrent {
+            magnet,
+            content_hash: Some(manifest.remove(0).content_hash),
+            files: None,
+        }
+    })
+}
+
+/// Collect the files in `dir`, recursively, along with their path components
+/// relative to the torrent root.
+fn walk(
+    dir: &Path,
+    components: &mut Vec<String>,
+    files: &mut Vec<(Vec<String>, PathBuf)>,
+) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        components.push(entry.file_name().to_string_lossy().into_owned());
+
+        if path.is_dir() {
+            walk(&path, components, files)?;
+        } else {
+            files.push((components.clone(), path));
+        }
+
+        components.pop();
+    }
+
+    Ok(())
+}
+
+fn insert_file_tree_entry(
+    tree: &mut HashMap<String, BencodeElem>,
+    components: &[String],
+    file_info: BencodeElem,
+) {
+    let Some((first, rest)) = components.split_first() else {
+        return;
+    };
+
+    if rest.is_empty() {
+        tree.insert(
+            first.clone(),
+            BencodeElem::Dictionary(HashMap::from([(String::new(), file_info)])),
+        );
+    } else if let BencodeElem::Dictionary(subtree) = tree
+        .entry(first.clone())
+        .or_insert_with(|| BencodeElem::Dictionary(HashMap::new()))
+    {
+        insert_file_tree_entry(subtree, rest, file_info);
+    }
+}
+
+/// The hashes of a file in a torrent.
+struct FileHashes {
+    length: u64,
+    content_hash: sha256::Hash,
+    pieces_root: Option<[u8; 32]>,
+    piece_layer: Option<Vec<u8>>,
+}
+
+/// Hashes the files of a torrent as they're streamed, one block at a time,
+/// computing their content hashes, v1 pieces and v2 merkle trees in a single
+/// pass with memory bounded by the number of pieces.
+struct TorrentHasher {
+    v1: bool,
+    v2: bool,
+    piece: sha1::HashEngine,
+    piece_fill: usize,
+    pieces: Vec<u8>,
+}
+
+impl TorrentHasher {
+    fn new(version: TorrentVersion) -> Self {
+        Self {
+            v1: version != TorrentVersion::V2,
+            v2: version != TorrentVersion::V1,
+            piece: sha1::Hash::engine(),
+            piece_fill: 0,
+            pieces: Vec::new(),
+        }
+    }
+
+    fn hash_file(
+        &mut self,
+        mut reader: impl Read,
+        progress_bar: &ProgressBar,
+    ) -> io::Result<FileHashes> {
+        let mut content_hash = sha256::Hash::engine();
+        let mut block = vec![0; BLOCK_SIZE];
+        let mut length = 0;
+        let mut leaves = Vec::new();
+        let mut pieces = Vec::new();
+
+        loop {
+            let n = read_block(&mut reader, &mut block)?;
+
+            if n == 0 {
+                break;
+            }
+
+            let block = &block[..n];
+
+            content_hash.input(block);
+
+            if self.v1 {
+                self.hash_v1(block);
+            }
+
+            if self.v2 {
+                leaves.push(sha256::Hash::hash(block).into_inner());
+                if leaves.len() == BLOCKS_PER_PIECE {
+                    pieces.push(merkle_root(mem::take(&mut leaves), [0; 32]));
+                }
+            }
+
+            length += n as u64;
+            progress_bar.inc(n as u64);
+
+            if n < BLOCK_SIZE {
+                break;
+            }
+        }
+
+        let (pieces_root, piece_layer) = if pieces.is_empty() {
+            (
+                (!leaves.is_empty()).then(|| merkle_root(leaves, [0; 32])),
+                None,
+            )
+        } else {
+            if !leaves.is_empty() {
+                leaves.resize(BLOCKS_PER_PIECE, [0; 32]);
+                pieces.push(merkle_root(leaves, [0; 32]));
+            }
+
+            if pieces.len() == 1 {
+                (Some(pieces[0]), None)
+            } else {
+                let padding = merkle_root(vec![[0; 32]; BLOCKS_PER_PIECE], [0; 32]);
+                (
+                    Some(merkle_root(pieces.clone(), padding)),
+                    Some(pieces.concat()),
+                )
+            }
+        };
+
+        Ok(FileHashes {
+            length,
+            content_hash: sha256::Hash::from_engine(content_hash),
+            pieces_root,
+            piece_layer,
+        })
+    }
+
+    fn hash_v1(
+        &mut self,
+        mut data: &[u8],
+    ) {
+        while !data.is_empty() {
+            let n = data.len().min(PIECE_LENGTH - self.piece_fill);
+            self.piece.input(&data[..n]);
+            self.piece_fill += n;
+            data = &data[n..];
+
+            if self.piece_fill == PIECE_LENGTH {
+                self.finish_piece();
+            }
+        }
+    }
+
+    fn finish_piece(&mut self) {
+        let piece = mem::replace(&mut self.piece, sha1::Hash::engine());
+        self.pieces
+            .extend_from_slice(&sha1::Hash::from_engine(piece).into_inner());
+        self.piece_fill = 0;
+    }
+
+    /// Pad the current v1 piece of a hybrid torrent with zeros, so the next
+    /// file starts at a piece boundary, returning the length of the padding.
+    fn pad(&mut self) -> Option<usize> {
+        if !(self.v1 && self.v2) || self.piece_fill == 0 {
+            return None;
+        }
+
+        let padding = PIECE_LENGTH - self.piece_fill;
+        self.hash_v1(&vec![0; padding]);
+        Some(padding)
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.piece_fill > 0 {
+            self.finish_piece();
+        }
+        self.pieces
+    }
+}
+
+fn read_block(
+    reader: &mut impl Read,
+    block: &mut [u8],
+) -> io::Result<usize> {
+    let mut filled = 0;
+
+    while filled < block.len() {
+        match reader.read(&mut block[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+
+    Ok(filled)
+}
+
+fn magnet_link(
+    v1_infohash: Option<Vec<u8>>,
+    v2_infohash: Option<Vec<u8>>,
+    options: &TorrentOptions,
+) -> String {
+    let mut params = Vec::new();
+
+    if let Some(infohash) = v1_infohash {
+        params.push(format!("xt=urn:btih:{}", infohash.to_hex()));
+    }
+
+    // A multihash of the SHA-256 infohash, 0x12 being SHA-256 and 0x20 its length
+    if let Some(infohash) = v2_infohash {
+        params.push(format!("xt=urn:btmh:1220{}", infohash.to_hex()));
+    }
+
+    params.extend(
+        options
+            .trackers
+            .iter()
+            .map(|tracker| format!("tr={}", encode(tracker))),
+    );
+    params.extend(
+        options
+            .web_seeds
+            .iter()
+            .map(|web_seed| format!("ws={}", encode(web_seed))),
+    );
+    params.extend(options.peers().map(|peer| format!("x.pe={}", encode(peer))));
+
+    format!("magnet:?{}", params.join("&"))
+}
+
+/// The root of a merkle tree with `leaves`, padded to a power of two with
+/// `padding`.
+fn merkle_root(
+    mut leaves: Vec<[u8; 32]>,
+    padding: [u8; 32],
+) -> [u8; 32] {
+    leaves.resize(leaves.len().next_power_of_two(), padding);
+
+    while leaves.len() > 1 {
+        leaves = leaves
+            .chunks(2)
+            .map(|pair| sha256::Hash::hash(&pair.concat()).into_inner())
+            .collect();
+    }
+
+    leaves[0]
 }
 
 fn get_torrent_path(
@@ -90,11 +601,9 @@ fn get_torrent_path(
     }
 }
 
-fn bencode_nodes(nodes: &str) -> BencodeElem {
+fn bencode_nodes<'a>(nodes: impl Iterator<Item = &'a str>) -> BencodeElem {
     BencodeElem::List(
         nodes
-            .trim()
-            .split(' ')
             .filter_map(|node| {
                 let mut parts = node.split(':'); // host:port
                 Some(BencodeElem::List(vec![
@@ -105,3 +614,355 @@ fn bencode_nodes(nodes: &str) -> BencodeElem {
             .collect(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        lava_torrent::torrent::v1::{
+            Torrent,
+            TorrentBuilder,
+        },
+        tempfile::TempDir,
+    };
+
+    fn options(version: TorrentVersion) -> TorrentOptions {
+        TorrentOptions {
+            path: None,
+            trackers: vec![DEFAULT_TRACKER.into(), DEFAULT_WSS_TRACKER.into()],
+            peers: vec![DEFAULT_PEER.into()],
+            web_seeds: vec!["https://example.com/foo.txt".into()],
+            version,
+        }
+    }
+
+    fn inscribe(options: &TorrentOptions) -> (TempDir, String) {
+        let tempdir = TempDir::new().unwrap();
+        let path = tempdir.path().join("foo.txt");
+        fs::write(&path, "FOO").unwrap();
+        let torrent = make_offchain_inscription(&path, options).unwrap();
+        (tempdir, torrent.magnet)
+    }
+
+    fn v2_hashes(contents: &[u8]) -> (Option<[u8; 32]>, Option<Vec<u8>>) {
+        let hashes = TorrentHasher::new(TorrentVersion::V2)
+            .hash_file(contents, &ProgressBar::hidden())
+            .unwrap();
+        (hashes.pieces_root, hashes.piece_layer)
+    }
+
+    fn read_torrent(path: impl AsRef<Path>) -> HashMap<String, BencodeElem> {
+        match BencodeElem::from_bytes(fs::read(path).unwrap())
+            .unwrap()
+            .remove(0)
+        {
+            BencodeElem::Dictionary(torrent) => torrent,
+            _ => panic!("torrent is not a dictionary"),
+        }
+    }
+
+    fn directory() -> TempDir {
+        let tempdir = TempDir::new().unwrap();
+        fs::create_dir_all(tempdir.path().join("foo/bar")).unwrap();
+        fs::write(tempdir.path().join("foo/b.txt"), "BBB").unwrap();
+        fs::write(tempdir.path().join("foo/bar/a.txt"), "AAAA").unwrap();
+        tempdir
+    }
+
+    #[test]
+    fn magnet_link_includes_trackers_web_seeds_and_peers() {
+        let (tempdir, magnet) = inscribe(&options(TorrentVersion::V1));
+
+        let torrent = Torrent::read_from_file(tempdir.path().join("foo.txt.torrent")).unwrap();
+
+        assert_eq!(
+            magnet,
+            format!(
+                "magnet:?xt=urn:btih:{}\
+                &tr=udp%3A%2F%2Ftracker.openbittorrent.com%3A6969\
+                &tr=wss%3A%2F%2Ftracker.openwebtorrent.com\
+                &ws=https%3A%2F%2Fexample.com%2Ffoo.txt\
+                &x.pe=dht.aelitis.com%3A6881",
+                torrent.info_hash()
+            )
+        );
+        assert_eq!(
+            torrent.announce_list,
+            Some(vec![
+                vec![DEFAULT_TRACKER.to_string()],
+                vec![DEFAULT_WSS_TRACKER.to_string()],
+            ])
+        );
+        assert_eq!(
+            torrent.extra_fields.unwrap()["url-list"],
+            BencodeElem::List(vec!["https://example.com/foo.txt".into()])
+        );
+    }
+
+    #[test]
+    fn v2_torrents_have_sha256_infohashes() {
+        let (tempdir, magnet) = inscribe(&options(TorrentVersion::V2));
+
+        let torrent = read_torrent(tempdir.path().join("foo.txt.torrent"));
+
+        let BencodeElem::Dictionary(info) = &torrent["info"] else {
+            panic!("info is not a dictionary");
+        };
+
+        assert!(!info.contains_key("pieces"));
+        assert_eq!(info["meta version"], BencodeElem::Integer(2));
+
+        assert!(magnet.starts_with(&format!(
+            "magnet:?xt=urn:btmh:1220{}&tr=",
+            sha256::Hash::hash(&torrent["info"].encode()).to_hex()
+        )));
+    }
+
+    #[test]
+    fn hybrid_torrents_have_both_infohashes() {
+        let (tempdir, magnet) = inscribe(&options(TorrentVersion::Hybrid));
+
+        let torrent = Torrent::read_from_file(tempdir.path().join("foo.txt.torrent")).unwrap();
+
+        assert!(magnet.starts_with(&format!(
+            "magnet:?xt=urn:btih:{}&xt=urn:btmh:1220{}&tr=",
+            torrent.info_hash(),
+            sha256::Hash::hash(&torrent.construct_info().encode()).to_hex()
+        )));
+    }
+
+    #[test]
+    fn merkle_root_of_small_file_is_padded_to_a_power_of_two() {
+        let contents = vec![1; BLOCK_SIZE * 2 + 1];
+
+        let blocks = [
+            sha256::Hash::hash(&contents[..BLOCK_SIZE]).into_inner(),
+            sha256::Hash::hash(&contents[BLOCK_SIZE..BLOCK_SIZE * 2]).into_inner(),
+            sha256::Hash::hash(&contents[BLOCK_SIZE * 2..]).into_inner(),
+            [0; 32],
+        ];
+
+        let hash = |a: [u8; 32], b: [u8; 32]| sha256::Hash::hash(&[a, b].concat()).into_inner();
+
+        assert_eq!(
+            v2_hashes(&contents),
+            (
+                Some(hash(hash(blocks[0], blocks[1]), hash(blocks[2], blocks[3]))),
+                None
+            )
+        );
+        assert_eq!(v2_hashes(&[]), (None, None));
+    }
+
+    #[test]
+    fn files_larger_than_a_piece_have_a_piece_layer() {
+        let contents = vec![1; BLOCKS_PER_PIECE * BLOCK_SIZE + 1];
+
+        let (root, layer) = v2_hashes(&contents);
+
+        let first = v2_hashes(&contents[..BLOCKS_PER_PIECE * BLOCK_SIZE])
+            .0
+            .unwrap();
+
+        let mut last = vec![sha256::Hash::hash(&[1]).into_inner()];
+        last.resize(BLOCKS_PER_PIECE, [0; 32]);
+        let last = merkle_root(last, [0; 32]);
+
+        assert_eq!(layer.unwrap(), [first, last].concat());
+        assert_eq!(
+            root.unwrap(),
+            sha256::Hash::hash(&[first, last].concat()).into_inner()
+        );
+    }
+
+    #[test]
+    fn v1_torrents_match_lava_torrent() {
+        let tempdir = TempDir::new().unwrap();
+        let path = tempdir.path().join("foo.bin");
+        fs::write(
+            &path,
+            (0..PIECE_LENGTH * 2 + 5)
+                .map(|i| u8::try_from(i % 251).unwrap())
+                .collect::<Vec<u8>>(),
+        )
+        .unwrap();
+
+        let options = TorrentOptions {
+            trackers: vec![DEFAULT_TRACKER.into()],
+            ..Default::default()
+        };
+
+        let magnet = make_offchain_inscription(&path, &options).unwrap().magnet;
+
+        let torrent = TorrentBuilder::new(fs::canonicalize(&path).unwrap(), 1048576)
+            .build()
+            .unwrap();
+
+        assert!(magnet.starts_with(&format!("magnet:?xt=urn:btih:{}&", torrent.info_hash())));
+        assert_eq!(
+            Torrent::read_from_file(tempdir.path().join("foo.bin.torrent"))
+                .unwrap()
+                .pieces,
+            torrent.pieces
+        );
+    }
+
+    #[test]
+    fn content_hash_is_computed_while_streaming() {
+        let tempdir = TempDir::new().unwrap();
+        let path = tempdir.path().join("foo.txt");
+        fs::write(&path, "FOO").unwrap();
+
+        let torrent = make_offchain_inscription(&path, &options(TorrentVersion::V1)).unwrap();
+
+        assert_eq!(
+            torrent.content_hash.unwrap(),
+            "9520437ce8902eb379a7d8aaa98fc4c94eeb07b6684854868fa6f72bf34b0fd3"
+        );
+        assert_eq!(torrent.files, None);
+    }
+
+    #[test]
+    fn directories_produce_multi_file_torrents_with_a_manifest() {
+        let tempdir = directory();
+
+        let torrent =
+            make_offchain_inscription(tempdir.path().join("foo"), &options(TorrentVersion::V1))
+                .unwrap();
+
+        assert_eq!(torrent.content_hash, None);
+        assert_eq!(
+            torrent.files.unwrap(),
+            vec![
+                TorrentFile {
+                    path: "b.txt".into(),
+                    length: 3,
+                    content_hash: sha256::Hash::hash(b"BBB").to_hex(),
+                },
+                TorrentFile {
+                    path: "bar/a.txt".into(),
+                    length: 4,
+                    content_hash: sha256::Hash::hash(b"AAAA").to_hex(),
+                },
+            ]
+        );
+
+        let torrent = Torrent::read_from_file(tempdir.path().join("foo.torrent")).unwrap();
+
+        assert_eq!(torrent.name, "foo");
+        assert_eq!(torrent.length, 7);
+        assert_eq!(
+            torrent
+                .files
+                .unwrap()
+                .into_iter()
+                .map(|file| file.path)
+                .collect::<Vec<PathBuf>>(),
+            vec![PathBuf::from("b.txt"), PathBuf::from("bar/a.txt")]
+        );
+        assert_eq!(
+            torrent.pieces,
+            vec![sha1::Hash::hash(b"BBBAAAA").into_inner().to_vec()]
+        );
+    }
+
+    #[test]
+    fn hybrid_directory_torrents_pad_files_to_piece_boundaries() {
+        let tempdir = directory();
+
+        make_offchain_inscription(tempdir.path().join("foo"), &options(TorrentVersion::Hybrid))
+            .unwrap();
+
+        let torrent = read_torrent(tempdir.path().join("foo.torrent"));
+
+        let BencodeElem::Dictionary(info) = &torrent["info"] else {
+            panic!("info is not a dictionary");
+        };
+
+        let BencodeElem::List(files) = &info["files"] else {
+            panic!("files is not a list");
+        };
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(
+            files[1],
+            BencodeElem::Dictionary(HashMap::from([
+                ("attr".to_string(), "p".into()),
+                ("length".to_string(), 1048573.into()),
+                (
+                    "path".to_string(),
+                    BencodeElem::List(vec![".pad".into(), "1048573".into()])
+                ),
+            ]))
+        );
+
+        let mut first = b"BBB".to_vec();
+        first.resize(PIECE_LENGTH, 0);
+
+        assert_eq!(
+            info["pieces"],
+            BencodeElem::Bytes(
+                [
+                    sha1::Hash::hash(&first).into_inner(),
+                    sha1::Hash::hash(b"AAAA").into_inner()
+                ]
+                .concat()
+            )
+        );
+
+        let BencodeElem::Dictionary(file_tree) = &info["file tree"] else {
+            panic!("file tree is not a dictionary");
+        };
+
+        assert_eq!(
+            file_tree["bar"],
+            BencodeElem::Dictionary(HashMap::from([(
+                "a.txt".to_string(),
+                BencodeElem::Dictionary(HashMap::from([(
+                    String::new(),
+                    BencodeElem::Dictionary(HashMap::from([
+                        ("length".to_string(), 4.into()),
+                        (
+                            "pieces root".to_string(),
+                            sha256::Hash::hash(b"AAAA").into_inner().to_vec().into()
+                        ),
+                    ]))
+                )]))
+            )]))
+        );
+    }
+
+    #[test]
+    fn empty_directories_are_an_error() {
+        let tempdir = TempDir::new().unwrap();
+
+        assert_eq!(
+            make_offchain_inscription(tempdir.path(), &options(TorrentVersion::V1))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "directory {} contains no files",
+                fs::canonicalize(tempdir.path()).unwrap().display()
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_urls_are_rejected() {
+        let mut options = options(TorrentVersion::V1);
+        options.trackers.push("ftp://example.com".into());
+
+        assert_eq!(
+            options.validate().unwrap_err().to_string(),
+            "Torrent tracker `ftp://example.com` must be a udp, http(s) or ws(s) URL!"
+        );
+
+        let mut options = self::options(TorrentVersion::V1);
+        options.web_seeds.push("udp://example.com".into());
+
+        assert_eq!(
+            options.validate().unwrap_err().to_string(),
+            "Torrent web seed `udp://example.com` must be an http(s) URL!"
+        );
+    }
+}
@@ -36,8 +36,19 @@ pub(crate) struct Options {
     pub(crate) height_limit: Option<u64>,
     #[clap(long, help = "Use index at <INDEX>.")]
     pub(crate) index: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Track outputs holding inscriptions, or rare sats with `--index-sats`, by address."
+    )]
+    pub(crate) index_addresses: bool,
     #[clap(long, help = "Track location of all satoshis.")]
     pub(crate) index_sats: bool,
+    #[clap(
+        long,
+        help = "Load protocol specs from <PROTOCOLS_DIR> in addition to the bundled specs and \
+    those in <DATA_DIR>/protocols."
+    )]
+    pub(crate) protocols_dir: Option<PathBuf>,
     #[clap(long, short, help = "Use regtest. Equivalent to `--chain regtest`.")]
     pub(crate) regtest: bool,
     #[clap(long, help = "Connect to Bitcoin Core RPC at <RPC_URL>.")]
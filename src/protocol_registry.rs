@@ -0,0 +1,888 @@
+use {
+    super::*,
+    bitcoin::hashes::{
+        sha256,
+        Hash,
+    },
+    include_dir::{
+        include_dir,
+        Dir,
+    },
+    std::sync::RwLock,
+    version_compare::Version,
+};
+
+static BUNDLED_PROTOCOLS_DIR: Dir<'_> = include_dir!("protocols/");
+
+lazy_static! {
+    static ref CURRENT: RwLock<Arc<ProtocolRegistry>> =
+        RwLock::new(Arc::new(ProtocolRegistry::bundled()));
+}
+
+/// Spec keys that describe the protocol itself and can't be set as
+/// inscription properties.
+const RESERVED_KEYS: &[&str] = &["protocol", "version", "tracking", "fields", "constraints"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum FieldType {
+    Array,
+    Boolean,
+    Integer,
+    Number,
+    Object,
+    String,
+}
+
+impl FieldType {
+    fn matches(
+        self,
+        value: &serde_json::Value,
+    ) -> bool {
+        match self {
+            Self::Array => value.is_array(),
+            Self::Boolean => value.is_boolean(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Object => value.is_object(),
+            Self::String => value.is_string(),
+        }
+    }
+}
+
+impl Display for FieldType {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        let name = match self {
+            Self::Array => "an array",
+            Self::Boolean => "a boolean",
+            Self::Integer => "an integer",
+            Self::Number => "a number",
+            Self::Object => "an object",
+            Self::String => "a string",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FieldSpec {
+    #[serde(rename = "type")]
+    pub(crate) field_type: FieldType,
+    #[serde(default)]
+    pub(crate) required: bool,
+    pub(crate) max_length: Option<usize>,
+    #[serde(rename = "enum")]
+    pub(crate) allowed: Option<Vec<serde_json::Value>>,
+}
+
+impl FieldSpec {
+    fn validate(
+        &self,
+        name: &str,
+        value: &serde_json::Value,
+    ) -> Result {
+        if !self.field_type.matches(value) {
+            bail!("property `{name}` must be {}", self.field_type);
+        }
+
+        if let Some(max_length) = self.max_length {
+            let length = match value {
+                serde_json::Value::String(string) => string.chars().count(),
+                serde_json::Value::Array(array) => array.len(),
+                _ => 0,
+            };
+
+            if length > max_length {
+                bail!(
+                    "property `{name}` is {length} long, exceeding maximum length of {max_length}"
+                );
+            }
+        }
+
+        if let Some(allowed) = &self.allowed {
+            if !allowed.contains(value) {
+                bail!(
+                    "property `{name}` must be one of {}: {value}",
+                    allowed
+                        .iter()
+                        .map(|value| format!("`{value}`"))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Rules beyond the field schema that inscriptions of a protocol must follow,
+/// which the indexer checks to decide whether they're valid.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Constraints {
+    pub(crate) max_content_size: Option<usize>,
+    /// Allowed content types, which match with or without parameters like
+    /// `charset`.
+    pub(crate) content_types: Option<Vec<String>>,
+    /// Keys that the inscription's JSON metadata must have.
+    #[serde(default)]
+    pub(crate) required_metadata: Vec<String>,
+    pub(crate) min_height: Option<u64>,
+    pub(crate) max_height: Option<u64>,
+}
+
+impl Constraints {
+    /// Check `inscription`, inscribed at `height`, against the constraints.
+    pub(crate) fn check(
+        &self,
+        inscription: &Inscription,
+        height: u64,
+    ) -> Result {
+        if let Some(min_height) = self.min_height {
+            if height < min_height {
+                bail!("inscription at height {height} is below minimum height {min_height}");
+            }
+        }
+
+        if let Some(max_height) = self.max_height {
+            if height > max_height {
+                bail!("inscription at height {height} is above maximum height {max_height}");
+            }
+        }
+
+        if self.max_content_size.is_some() || self.content_types.is_some() {
+            let (content_type, content) = inscription
+                .content()
+                .ok_or_else(|| anyhow!("inscription content must be carried by the inscription"))?;
+
+            if let Some(max_content_size) = self.max_content_size {
+                if content.len() > max_content_size {
+                    bail!(
+                        "content size of {} bytes exceeds maximum of {max_content_size} bytes",
+                        content.len()
+                    );
+                }
+            }
+
+            if let Some(content_types) = &self.content_types {
+                let content_type =
+                    content_type.ok_or_else(|| anyhow!("inscription has no content type"))?;
+
+                let essence = content_type.split(';').next().unwrap_or_default().trim();
+
+                if !content_types
+                    .iter()
+                    .any(|allowed| *allowed == content_type || allowed == essence)
+                {
+                    bail!(
+                        "content type `{content_type}` is not one of {}",
+                        content_types
+                            .iter()
+                            .map(|content_type| format!("`{content_type}`"))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    );
+                }
+            }
+        }
+
+        if !self.required_metadata.is_empty() {
+            let metadata = inscription
+                .metadata()
+                .ok_or_else(|| anyhow!("inscription has no JSON metadata"))?;
+
+            for key in &self.required_metadata {
+                if metadata.get(key).map_or(true, |value| value.is_null()) {
+                    bail!("metadata is missing required field `{key}`");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProtocolSpec {
+    pub(crate) protocol: String,
+    pub(crate) version: String,
+    pub(crate) tracking: bool,
+    pub(crate) source: String,
+    pub(crate) fields: Option<BTreeMap<String, FieldSpec>>,
+    pub(crate) constraints: Option<Constraints>,
+    pub(crate) json: serde_json::Value,
+}
+
+impl ProtocolSpec {
+    pub(crate) fn parse(
+        contents: &[u8],
+        source: impl Into<String>,
+    ) -> Result<Self> {
+        lazy_static! {
+            static ref PROTOCOL: Regex = Regex::new(r"^[a-z0-9_]{1,16}$").unwrap();
+            static ref VERSION: Regex = Regex::new(r"^\d+\.\d+\.\d+$").unwrap();
+        }
+
+        let json: serde_json::Value =
+            serde_json::from_slice(contents).context("protocol spec is not valid JSON")?;
+
+        if !json.is_object() {
+            bail!("protocol spec must be a JSON object");
+        }
+
+        let protocol = json["protocol"]
+            .as_str()
+            .ok_or_else(|| anyhow!("protocol spec field `protocol` must be a string"))?;
+
+        if !PROTOCOL.is_match(protocol) {
+            bail!(
+                "protocol spec field `protocol` must be 1 to 16 characters of `a-z`, `0-9` and \
+                `_`: `{protocol}`"
+            );
+        }
+
+        let version = json["version"]
+            .as_str()
+            .ok_or_else(|| anyhow!("protocol spec field `version` must be a string"))?;
+
+        if !VERSION.is_match(version) {
+            bail!("protocol spec field `version` must be a semantic version: `{version}`");
+        }
+
+        let tracking = json["tracking"]
+            .as_bool()
+            .ok_or_else(|| anyhow!("protocol spec field `tracking` must be a boolean"))?;
+
+        let fields = match json.get("fields") {
+            Some(fields) => {
+                let fields = BTreeMap::<String, FieldSpec>::deserialize(fields)
+                    .context("protocol spec field `fields` is not a valid schema")?;
+
+                if let Some(name) = fields
+                    .keys()
+                    .find(|name| RESERVED_KEYS.contains(&name.as_str()))
+                {
+                    bail!("protocol spec field `fields` declares reserved key `{name}`");
+                }
+
+                Some(fields)
+            },
+            None => None,
+        };
+
+        let constraints = match json.get("constraints") {
+            Some(constraints) => {
+                let constraints = Constraints::deserialize(constraints)
+                    .context("protocol spec field `constraints` is not valid")?;
+
+                if let (Some(min_height), Some(max_height)) =
+                    (constraints.min_height, constraints.max_height)
+                {
+                    if min_height > max_height {
+                        bail!(
+                            "protocol spec field `constraints` has `min_height` {min_height} \
+                            above `max_height` {max_height}"
+                        );
+                    }
+                }
+
+                Some(constraints)
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            protocol: protocol.into(),
+            version: version.into(),
+            tracking,
+            source: source.into(),
+            fields,
+            constraints,
+            json,
+        })
+    }
+
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let contents =
+            fs::read(path).with_context(|| format!("io error reading {}", path.display()))?;
+
+        Self::parse(&contents, path.display().to_string())
+            .with_context(|| format!("invalid protocol spec {}", path.display()))
+    }
+
+    /// The spec's own keys, minus the field schema and constraints, which are
+    /// used as the defaults for an inscription's properties.
+    pub(crate) fn default_properties(&self) -> serde_json::Value {
+        let mut properties = self.json.clone();
+
+        if let Some(object) = properties.as_object_mut() {
+            object.remove("fields");
+            object.remove("constraints");
+        }
+
+        properties
+    }
+
+    /// Set `overrides` on `properties`, rejecting keys the spec doesn't allow
+    /// inscriptions to set.
+    pub(crate) fn merge_properties(
+        &self,
+        properties: &mut serde_json::Value,
+        overrides: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result {
+        for (key, value) in overrides {
+            self.check_settable(key)?;
+            properties[key] = value.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Whether an inscription may set property `key`.
+    fn check_settable(
+        &self,
+        key: &str,
+    ) -> Result {
+        if RESERVED_KEYS.contains(&key) {
+            bail!("property `{key}` is reserved");
+        }
+
+        if let Some(fields) = &self.fields {
+            if !fields.contains_key(key) {
+                bail!("unknown property `{key}` for protocol `{}`", self.id());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check `properties` against the spec's field schema, if it declares one.
+    /// Fields set to `null` are treated as absent.
+    pub(crate) fn validate_properties(
+        &self,
+        properties: &serde_json::Value,
+    ) -> Result {
+        let Some(fields) = &self.fields else {
+            return Ok(());
+        };
+
+        let properties = properties
+            .as_object()
+            .ok_or_else(|| anyhow!("properties must be a JSON object"))?;
+
+        for (key, value) in properties {
+            match fields.get(key) {
+                Some(field) if !value.is_null() => field.validate(key, value)?,
+                Some(_) => {},
+                None => {
+                    if !RESERVED_KEYS.contains(&key.as_str()) && self.json.get(key).is_none() {
+                        bail!("unknown property `{key}` for protocol `{}`", self.id());
+                    }
+                },
+            }
+        }
+
+        for (name, field) in fields {
+            if field.required && properties.get(name).map_or(true, |value| value.is_null()) {
+                bail!("missing required property `{name}`");
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn id(&self) -> String {
+        format!("{}-v{}", self.protocol, self.version)
+    }
+
+    fn newer_than(
+        &self,
+        other: &ProtocolSpec,
+    ) -> bool {
+        Version::from(&self.version) > Version::from(&other.version)
+    }
+
+    fn matches(
+        &self,
+        protocol_id: &str,
+    ) -> bool {
+        match protocol_id.split_once("-v") {
+            Some((protocol, version)) => {
+                protocol == self.protocol
+                    && (self.version == version || self.version.starts_with(&format!("{version}.")))
+            },
+            None => protocol_id == self.protocol,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ProtocolRegistry {
+    specs: BTreeMap<(String, String), ProtocolSpec>,
+}
+
+impl ProtocolRegistry {
+    pub(crate) fn bundled() -> Self {
+        let mut registry = Self::default();
+
+        for file in BUNDLED_PROTOCOLS_DIR.files() {
+            registry.insert(
+                ProtocolSpec::parse(file.contents(), "bundled")
+                    .expect("bundled protocol specs should be valid"),
+            );
+        }
+
+        registry
+    }
+
+    pub(crate) fn load(options: &Options) -> Result<Self> {
+        let mut registry = Self::bundled();
+
+        registry.load_dir(&options.data_dir()?.join("protocols"))?;
+
+        if let Some(protocols_dir) = &options.protocols_dir {
+            if !protocols_dir.is_dir() {
+                bail!("protocols dir `{}` does not exist", protocols_dir.display());
+            }
+
+            registry.load_dir(protocols_dir)?;
+        }
+
+        Ok(registry)
+    }
+
+    fn load_dir(
+        &mut self,
+        dir: &Path,
+    ) -> Result {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+
+        for path in Self::spec_paths(dir)? {
+            match ProtocolSpec::load(&path) {
+                Ok(spec) => self.insert(spec),
+                Err(err) => log::warn!("Skipping protocol spec: {err:#}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn spec_paths(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut paths = fs::read_dir(dir)
+            .with_context(|| format!("io error reading {}", dir.display()))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<io::Result<Vec<PathBuf>>>()?
+            .into_iter()
+            .filter(|path| {
+                path.extension()
+                    .map(|ext| ext == "json")
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<PathBuf>>();
+
+        paths.sort();
+
+        Ok(paths)
+    }
+
+    fn insert(
+        &mut self,
+        spec: ProtocolSpec,
+    ) {
+        self.specs
+            .insert((spec.protocol.clone(), spec.version.clone()), spec);
+    }
+
+    pub(crate) fn install(self) {
+        *CURRENT.write().unwrap() = Arc::new(self);
+    }
+
+    pub(crate) fn current() -> Arc<Self> {
+        CURRENT.read().unwrap().clone()
+    }
+
+    /// Resolve a protocol id such as `ord`, `ord-v1` or `ord-v1.0.0` to the
+    /// highest matching spec version.
+    pub(crate) fn get(
+        &self,
+        protocol_id: &str,
+    ) -> Option<&ProtocolSpec> {
+        self.specs
+            .values()
+            .filter(|spec| spec.matches(protocol_id))
+            .fold(None, |latest, spec| match latest {
+                Some(latest) if !spec.newer_than(latest) => Some(latest),
+                _ => Some(spec),
+            })
+    }
+
+    /// The highest version of every registered protocol.
+    pub(crate) fn latest(&self) -> Vec<&ProtocolSpec> {
+        let mut latest: BTreeMap<&str, &ProtocolSpec> = BTreeMap::new();
+
+        for spec in self.specs.values() {
+            match latest.get(spec.protocol.as_str()) {
+                Some(current) if !spec.newer_than(current) => {},
+                _ => {
+                    latest.insert(&spec.protocol, spec);
+                },
+            }
+        }
+
+        latest.into_values().collect()
+    }
+
+    /// A digest of the registered specs, which determine how inscriptions are
+    /// indexed, so an index can only be updated with the specs it was built
+    /// with.
+    pub(crate) fn digest(&self) -> u64 {
+        let mut specs = Vec::new();
+
+        for spec in self.specs.values() {
+            specs.extend(serde_json::to_vec(&spec.json).expect("specs should serialize"));
+        }
+
+        let hash = sha256::Hash::hash(&specs).into_inner();
+
+        u64::from_be_bytes(hash[..8].try_into().unwrap())
+    }
+
+    /// Whether `tag` is a known envelope tag, and if so, whether inscriptions
+    /// using it are tracked by default. Tags are bare protocol names, so
+    /// version-qualified ids like `ord-v1` aren't tags.
+    pub(crate) fn tag_tracking(
+        &self,
+        tag: &[u8],
+    ) -> Option<bool> {
+        let protocol = std::str::from_utf8(tag).ok()?;
+
+        self.get(protocol)
+            .filter(|spec| spec.protocol == protocol)
+            .map(|spec| spec.tracking)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(
+        protocol: &str,
+        version: &str,
+    ) -> ProtocolSpec {
+        ProtocolSpec::parse(
+            format!(r#"{{"protocol":"{protocol}","version":"{version}","tracking":true}}"#)
+                .as_bytes(),
+            "test",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn bundled_specs_are_loaded() {
+        let registry = ProtocolRegistry::bundled();
+        assert_eq!(registry.get("ord").unwrap().id(), "ord-v1.0.0");
+        assert_eq!(registry.get("pub-v1").unwrap().id(), "pub-v1.0.0");
+        assert!(registry.get("foo").is_none());
+    }
+
+    #[test]
+    fn highest_version_is_selected() {
+        let mut registry = ProtocolRegistry::default();
+        registry.insert(spec("foo", "1.2.0"));
+        registry.insert(spec("foo", "1.10.0"));
+        registry.insert(spec("foo", "2.0.0"));
+
+        assert_eq!(registry.get("foo").unwrap().version, "2.0.0");
+        assert_eq!(registry.get("foo-v1").unwrap().version, "1.10.0");
+        assert_eq!(registry.get("foo-v1.2").unwrap().version, "1.2.0");
+        assert_eq!(registry.get("foo-v1.2.0").unwrap().version, "1.2.0");
+        assert!(registry.get("foo-v3").is_none());
+        assert_eq!(registry.latest().len(), 1);
+    }
+
+    #[test]
+    fn later_sources_override_earlier_ones() {
+        let mut registry = ProtocolRegistry::bundled();
+        let mut ord = spec("ord", "1.0.0");
+        ord.source = "override".into();
+        registry.insert(ord);
+        assert_eq!(registry.get("ord").unwrap().source, "override");
+    }
+
+    #[test]
+    fn invalid_specs_are_rejected() {
+        assert_eq!(
+            ProtocolSpec::parse(b"[]", "test").unwrap_err().to_string(),
+            "protocol spec must be a JSON object"
+        );
+        assert_eq!(
+            ProtocolSpec::parse(
+                br#"{"protocol":"Foo","version":"1.0.0","tracking":true}"#,
+                "test"
+            )
+            .unwrap_err()
+            .to_string(),
+            "protocol spec field `protocol` must be 1 to 16 characters of `a-z`, `0-9` and `_`: \
+            `Foo`"
+        );
+        assert_eq!(
+            ProtocolSpec::parse(
+                br#"{"protocol":"foo","version":"1.0","tracking":true}"#,
+                "test"
+            )
+            .unwrap_err()
+            .to_string(),
+            "protocol spec field `version` must be a semantic version: `1.0`"
+        );
+        assert_eq!(
+            ProtocolSpec::parse(br#"{"protocol":"foo","version":"1.0.0"}"#, "test")
+                .unwrap_err()
+                .to_string(),
+            "protocol spec field `tracking` must be a boolean"
+        );
+    }
+
+    #[test]
+    fn invalid_field_schemas_are_rejected() {
+        assert_eq!(
+            ProtocolSpec::parse(
+                br#"{"protocol":"foo","version":"1.0.0","tracking":true,"fields":{"a":{"type":"date"}}}"#,
+                "test"
+            )
+            .unwrap_err()
+            .to_string(),
+            "protocol spec field `fields` is not a valid schema"
+        );
+        assert_eq!(
+            ProtocolSpec::parse(
+                br#"{"protocol":"foo","version":"1.0.0","tracking":true,"fields":{"version":{"type":"string"}}}"#,
+                "test"
+            )
+            .unwrap_err()
+            .to_string(),
+            "protocol spec field `fields` declares reserved key `version`"
+        );
+    }
+
+    #[test]
+    fn invalid_constraints_are_rejected() {
+        assert_eq!(
+            ProtocolSpec::parse(
+                br#"{"protocol":"foo","version":"1.0.0","tracking":true,"constraints":{"max_size":1}}"#,
+                "test"
+            )
+            .unwrap_err()
+            .to_string(),
+            "protocol spec field `constraints` is not valid"
+        );
+        assert_eq!(
+            ProtocolSpec::parse(
+                br#"{"protocol":"foo","version":"1.0.0","tracking":true,"constraints":{"min_height":2,"max_height":1}}"#,
+                "test"
+            )
+            .unwrap_err()
+            .to_string(),
+            "protocol spec field `constraints` has `min_height` 2 above `max_height` 1"
+        );
+    }
+
+    #[test]
+    fn inscriptions_are_checked_against_constraints() {
+        let spec = ProtocolSpec::parse(
+            br#"{
+                "protocol": "foo",
+                "version": "1.0.0",
+                "tracking": true,
+                "constraints": {
+                    "max_content_size": 5,
+                    "content_types": ["text/plain", "image/png"],
+                    "min_height": 10,
+                    "max_height": 20
+                }
+            }"#,
+            "test",
+        )
+        .unwrap();
+
+        assert_eq!(spec.default_properties().get("constraints"), None);
+
+        let constraints = spec.constraints.unwrap();
+
+        let check = |content_type: &str, content: &str, height: u64| {
+            constraints
+                .check(&inscription(content_type, content), height)
+                .map_err(|err| err.to_string())
+        };
+
+        assert_eq!(check("text/plain;charset=utf-8", "hello", 10), Ok(()));
+        assert_eq!(check("image/png", "", 20), Ok(()));
+        assert_eq!(
+            check("text/plain", "hello", 9),
+            Err("inscription at height 9 is below minimum height 10".into())
+        );
+        assert_eq!(
+            check("text/plain", "hello", 21),
+            Err("inscription at height 21 is above maximum height 20".into())
+        );
+        assert_eq!(
+            check("text/plain", "hello!", 10),
+            Err("content size of 6 bytes exceeds maximum of 5 bytes".into())
+        );
+        assert_eq!(
+            check("text/html", "hello", 10),
+            Err("content type `text/html` is not one of `text/plain`, `image/png`".into())
+        );
+
+        let constraints = Constraints {
+            required_metadata: vec!["author".into()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            constraints
+                .check(&inscription("text/plain", "hello"), 0)
+                .unwrap_err()
+                .to_string(),
+            "inscription has no JSON metadata"
+        );
+    }
+
+    #[test]
+    fn properties_are_validated_against_fields() {
+        let spec = ProtocolSpec::parse(
+            br#"{
+                "protocol": "foo",
+                "version": "1.0.0",
+                "tracking": true,
+                "note": null,
+                "fields": {
+                    "name": { "type": "string", "required": true, "max_length": 4 },
+                    "size": { "type": "integer" },
+                    "color": { "type": "string", "enum": ["red", "blue"] },
+                    "note": { "type": "string" }
+                }
+            }"#,
+            "test",
+        )
+        .unwrap();
+
+        let validate = |properties: serde_json::Value| {
+            spec.validate_properties(&properties)
+                .map_err(|err| err.to_string())
+        };
+
+        assert_eq!(
+            validate(
+                serde_json::json!({"protocol": "foo", "name": "abcd", "size": 2, "note": null})
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate(serde_json::json!({})),
+            Err("missing required property `name`".into())
+        );
+        assert_eq!(
+            validate(serde_json::json!({"name": 1})),
+            Err("property `name` must be a string".into())
+        );
+        assert_eq!(
+            validate(serde_json::json!({"name": "abcde"})),
+            Err("property `name` is 5 long, exceeding maximum length of 4".into())
+        );
+        assert_eq!(
+            validate(serde_json::json!({"name": "a", "size": 1.5})),
+            Err("property `size` must be an integer".into())
+        );
+        assert_eq!(
+            validate(serde_json::json!({"name": "a", "color": "green"})),
+            Err(r#"property `color` must be one of `"red"`, `"blue"`: "green""#.into())
+        );
+        assert_eq!(
+            validate(serde_json::json!({"name": "a", "shape": "round"})),
+            Err("unknown property `shape` for protocol `foo-v1.0.0`".into())
+        );
+
+        assert!(spec.check_settable("name").is_ok());
+        assert_eq!(
+            spec.check_settable("tracking").unwrap_err().to_string(),
+            "property `tracking` is reserved"
+        );
+        assert_eq!(
+            spec.check_settable("shape").unwrap_err().to_string(),
+            "unknown property `shape` for protocol `foo-v1.0.0`"
+        );
+        assert!(spec.default_properties().get("fields").is_none());
+    }
+
+    #[test]
+    fn specs_without_fields_accept_any_properties() {
+        let spec = spec("foo", "1.0.0");
+        assert!(spec
+            .validate_properties(&serde_json::json!({"anything": [1, 2, 3]}))
+            .is_ok());
+        assert!(spec.check_settable("anything").is_ok());
+    }
+
+    #[test]
+    fn specs_are_loaded_from_data_dir_and_protocols_dir() {
+        let tempdir = TempDir::new().unwrap();
+        let data_protocols = tempdir.path().join("protocols");
+        let extra_protocols = tempdir.path().join("extra");
+        fs::create_dir_all(&data_protocols).unwrap();
+        fs::create_dir_all(&extra_protocols).unwrap();
+
+        fs::write(
+            data_protocols.join("foo-v1.0.0.json"),
+            r#"{"protocol":"foo","version":"1.0.0","tracking":true}"#,
+        )
+        .unwrap();
+        fs::write(
+            extra_protocols.join("bar-v0.1.0.json"),
+            r#"{"protocol":"bar","version":"0.1.0","tracking":false}"#,
+        )
+        .unwrap();
+        fs::write(extra_protocols.join("broken.json"), "{").unwrap();
+        fs::write(extra_protocols.join("README.md"), "ignored").unwrap();
+
+        let options = Options::try_parse_from([
+            "arb",
+            "--data-dir",
+            tempdir.path().to_str().unwrap(),
+            "--protocols-dir",
+            extra_protocols.to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let registry = ProtocolRegistry::load(&options).unwrap();
+
+        assert!(registry.get("foo").unwrap().tracking);
+        assert!(!registry.get("bar").unwrap().tracking);
+        assert!(registry.get("ord").is_some());
+        assert_eq!(registry.tag_tracking(b"bar"), Some(false));
+        assert_eq!(registry.tag_tracking(b"baz"), None);
+        assert_eq!(registry.tag_tracking(b"bar-v0"), None);
+
+        assert_ne!(registry.digest(), ProtocolRegistry::bundled().digest());
+    }
+
+    #[test]
+    fn digest_is_deterministic() {
+        assert_eq!(
+            ProtocolRegistry::bundled().digest(),
+            ProtocolRegistry::bundled().digest()
+        );
+
+        let mut registry = ProtocolRegistry::bundled();
+        registry.insert(spec("foo", "1.0.0"));
+        assert_ne!(registry.digest(), ProtocolRegistry::bundled().digest());
+    }
+}
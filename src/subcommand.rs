@@ -1,12 +1,15 @@
 use super::*;
 
+pub mod address;
 pub mod epochs;
 pub mod find;
 mod index;
 pub mod info;
+pub mod inscriptions;
 pub mod list;
 pub mod parse;
 mod preview;
+pub mod protocols;
 mod server;
 pub mod subsidy;
 pub mod supply;
@@ -21,6 +24,8 @@ fn print_json(output: impl Serialize) -> Result {
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
+    #[clap(about = "List an address's outputs with inscriptions or rare sats")]
+    Address(address::Address),
     #[clap(about = "List the first satoshis of each reward epoch")]
     Epochs,
     #[clap(about = "Run an explorer server populated with inscriptions")]
@@ -31,10 +36,14 @@ pub(crate) enum Subcommand {
     Index,
     #[clap(about = "Display index statistics")]
     Info(info::Info),
+    #[clap(subcommand, about = "Inscription commands")]
+    Inscriptions(inscriptions::Inscriptions),
     #[clap(about = "List the satoshis in an output")]
     List(list::List),
     #[clap(about = "Parse a satoshi from ordinal notation")]
     Parse(parse::Parse),
+    #[clap(subcommand, about = "Protocol spec commands")]
+    Protocols(protocols::Protocols),
     #[clap(about = "Display information about a block's subsidy")]
     Subsidy(subsidy::Subsidy),
     #[clap(about = "Run the explorer server")]
@@ -53,13 +62,16 @@ impl Subcommand {
         options: Options,
     ) -> Result {
         match self {
+            Self::Address(address) => address.run(options),
             Self::Epochs => epochs::run(),
             Self::Preview(preview) => preview.run(),
             Self::Find(find) => find.run(options),
             Self::Index => index::run(options),
             Self::Info(info) => info.run(options),
+            Self::Inscriptions(inscriptions) => inscriptions.run(options),
             Self::List(list) => list.run(options),
             Self::Parse(parse) => parse.run(),
+            Self::Protocols(protocols) => protocols.run(),
             Self::Subsidy(subsidy) => subsidy.run(),
             Self::Server(server) => {
                 let index = Arc::new(Index::open(&options)?);
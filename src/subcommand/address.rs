@@ -0,0 +1,50 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Address {
+    #[clap(help = "List outputs with inscriptions or rare sats held by <ADDRESS>.")]
+    address: bitcoin::Address,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    pub outpoint: OutPoint,
+    pub inscriptions: Vec<InscriptionId>,
+    pub rare_sats: Vec<Sat>,
+}
+
+impl Address {
+    pub(crate) fn run(
+        self,
+        options: Options,
+    ) -> Result {
+        if !self.address.is_valid_for_network(options.chain().network()) {
+            bail!(
+                "Address `{}` is not valid for {}",
+                self.address,
+                options.chain()
+            );
+        }
+
+        let index = Index::open(&options)?;
+
+        index.update()?;
+
+        let outputs = index
+            .get_address_outputs(&self.address.script_pubkey())?
+            .ok_or_else(|| {
+                anyhow!("tracking addresses requires index created with `--index-addresses` flag")
+            })?
+            .into_iter()
+            .map(|outpoint| {
+                Ok(Output {
+                    outpoint,
+                    inscriptions: index.get_inscriptions_on_output(outpoint)?,
+                    rare_sats: index.get_rare_sats_on_output(outpoint)?,
+                })
+            })
+            .collect::<Result<Vec<Output>>>()?;
+
+        print_json(outputs)
+    }
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Inscriptions {
+    #[clap(
+        about = "Query inscriptions by content type, media, protocol, height, fee, rarity or tracking"
+    )]
+    Query(InscriptionQuery),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueryOutput {
+    pub inscriptions: Vec<InscriptionId>,
+    pub cursor: Option<String>,
+}
+
+impl Inscriptions {
+    pub(crate) fn run(
+        self,
+        options: Options,
+    ) -> Result {
+        match self {
+            Self::Query(query) => {
+                let index = Index::open(&options)?;
+
+                index.update()?;
+
+                let (inscriptions, cursor) = index.query_inscriptions(&query)?;
+
+                print_json(QueryOutput {
+                    inscriptions,
+                    cursor: cursor.map(|cursor| cursor.to_string()),
+                })
+            },
+        }
+    }
+}
@@ -83,7 +83,8 @@ impl Preview {
                     super::wallet::inscribe::Inscribe {
                         fee_rate: FeeRate::try_from(1.0).unwrap(),
                         commit_fee_rate: None,
-                        file,
+                        file: Some(file),
+                        batch: None,
                         title: None,
                         subtitle: None,
                         no_backup: true,
@@ -91,16 +92,24 @@ impl Preview {
                         dry_run: false,
                         no_limit: false,
                         destination: None,
-                        compression: false,
+                        parent: None,
+                        compression: None,
+                        compression_dictionary: None,
+                        binary_envelope: false,
                         off_chain: false,
                         torrent_path: None,
-                        torrent_tracker: "".to_owned(),
-                        torrent_peers: "".to_owned(),
+                        torrent_tracker: Vec::new(),
+                        torrent_peer: Vec::new(),
+                        torrent_web_seed: Vec::new(),
+                        torrent_version: TorrentVersion::V1,
                         metadata_file: None,
                         properties_file: None,
                         license: None,
                         protocol_id: "ord-v0".to_owned(),
                         description: None,
+                        multipart: false,
+                        part_size: None,
+                        dedup: false,
                     },
                 )),
             }
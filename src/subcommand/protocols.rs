@@ -0,0 +1,115 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Protocols {
+    #[clap(about = "List available protocols")]
+    List,
+    #[clap(about = "Show the spec of a protocol")]
+    Show(Show),
+    #[clap(about = "Validate protocol spec files")]
+    Validate(Validate),
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Show {
+    #[clap(help = "Show spec for <PROTOCOL_ID>, e.g. `ord`, `ord-v1` or `ord-v1.0.0`.")]
+    protocol_id: String,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Validate {
+    #[clap(
+        required = true,
+        help = "Validate spec <FILES>. Directories are searched for `.json` files."
+    )]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ListOutput {
+    pub protocol: String,
+    pub version: String,
+    pub tracking: bool,
+    pub source: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidateOutput {
+    pub path: PathBuf,
+    pub protocol_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Protocols {
+    pub(crate) fn run(self) -> Result {
+        match self {
+            Self::List => Self::list(),
+            Self::Show(show) => show.run(),
+            Self::Validate(validate) => validate.run(),
+        }
+    }
+
+    fn list() -> Result {
+        print_json(
+            ProtocolRegistry::current()
+                .latest()
+                .into_iter()
+                .map(|spec| ListOutput {
+                    protocol: spec.protocol.clone(),
+                    version: spec.version.clone(),
+                    tracking: spec.tracking,
+                    source: spec.source.clone(),
+                })
+                .collect::<Vec<ListOutput>>(),
+        )
+    }
+}
+
+impl Show {
+    fn run(self) -> Result {
+        match ProtocolRegistry::current().get(&self.protocol_id) {
+            Some(spec) => print_json(&spec.json),
+            None => bail!("unknown protocol `{}`", self.protocol_id),
+        }
+    }
+}
+
+impl Validate {
+    fn run(self) -> Result {
+        let mut paths = Vec::new();
+
+        for file in self.files {
+            if file.is_dir() {
+                paths.extend(ProtocolRegistry::spec_paths(&file)?);
+            } else {
+                paths.push(file);
+            }
+        }
+
+        let output = paths
+            .into_iter()
+            .map(|path| match ProtocolSpec::load(&path) {
+                Ok(spec) => ValidateOutput {
+                    path,
+                    protocol_id: Some(spec.id()),
+                    error: None,
+                },
+                Err(err) => ValidateOutput {
+                    path,
+                    protocol_id: None,
+                    error: Some(format!("{:#}", err.root_cause())),
+                },
+            })
+            .collect::<Vec<ValidateOutput>>();
+
+        print_json(&output)?;
+
+        let invalid = output.iter().filter(|spec| spec.error.is_some()).count();
+
+        if invalid > 0 {
+            bail!("{invalid} of {} protocol specs are invalid", output.len());
+        }
+
+        Ok(())
+    }
+}
@@ -6,26 +6,40 @@ use {
             ServerError,
             ServerResult,
         },
+        offchain::{
+            Mirror,
+            OffchainContent,
+            OffchainResolver,
+        },
     },
     super::*,
     crate::{
         page_config::PageConfig,
         templates::{
+            AddressHtml,
+            AddressOutput,
+            BfsHtml,
+            BidHtml,
+            BidJson,
             BlockHtml,
+            BnwHtml,
+            BnwWall,
             ClockSvg,
             HomeHtml,
             InputHtml,
             InscriptionHtml,
             InscriptionsHtml,
+            MempoolHtml,
             OutputHtml,
             PageContent,
-            PageHtml,
             PreviewAudioHtml,
             PreviewImageHtml,
             PreviewPdfHtml,
             PreviewTextHtml,
             PreviewUnknownHtml,
             PreviewVideoHtml,
+            PubInscriptionHtml,
+            PubInscriptionsHtml,
             RangeHtml,
             RareTxt,
             SatHtml,
@@ -35,9 +49,14 @@ use {
     axum::{
         body,
         extract::{
+            ws::{
+                Message,
+                WebSocketUpgrade,
+            },
             Extension,
             Path,
             Query,
+            RawQuery,
         },
         headers::UserAgent,
         http::{
@@ -49,11 +68,18 @@ use {
             Uri,
         },
         response::{
+            sse::{
+                self,
+                KeepAlive,
+                Sse,
+            },
             IntoResponse,
             Redirect,
             Response,
         },
+        middleware,
         routing::get,
+        Json,
         Router,
         TypedHeader,
     },
@@ -68,22 +94,40 @@ use {
         caches::DirCache,
         AcmeConfig,
     },
+    futures::{
+        stream,
+        Stream,
+    },
     std::{
         cmp::Ordering,
+        convert::Infallible,
+        ops::Range,
         str,
     },
+    tokio::sync::broadcast::error::RecvError,
     tokio_stream::StreamExt,
     tower_http::{
-        compression::CompressionLayer,
+        compression::{
+            predicate::{
+                DefaultPredicate,
+                NotForContentType,
+                Predicate,
+            },
+            CompressionLayer,
+        },
         cors::{
             Any,
             CorsLayer,
         },
-        set_header::SetResponseHeaderLayer,
+        set_header::{
+            SetRequestHeaderLayer,
+            SetResponseHeaderLayer,
+        },
     },
 };
 
 mod error;
+mod offchain;
 
 enum BlockQuery {
     Height(u64),
@@ -113,6 +157,67 @@ struct Search {
     query: String,
 }
 
+#[derive(Serialize)]
+struct StatusJson {
+    reorged: bool,
+}
+
+#[derive(Serialize)]
+struct ErrorJson {
+    error: String,
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    from: Option<u64>,
+}
+
+/// The bytes of a body requested with a `Range` header.
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    Satisfiable(Range<usize>),
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// The single range of bytes that `range` requests from a body of `len`
+    /// bytes, or `None` if the header is malformed or requests several ranges,
+    /// in which case it's ignored and the whole body is sent.
+    fn parse(
+        range: &str,
+        len: usize,
+    ) -> Option<Self> {
+        let spec = range.strip_prefix("bytes=")?.trim();
+
+        if spec.contains(',') {
+            return None;
+        }
+
+        let (start, end) = spec.split_once('-')?;
+
+        let range = match (start.trim(), end.trim()) {
+            ("", suffix) => len.saturating_sub(suffix.parse().ok()?)..len,
+            (start, "") => start.parse().ok()?..len,
+            (start, end) => {
+                let start = start.parse().ok()?;
+                let end = end.parse::<usize>().ok()?;
+
+                if end < start {
+                    return None;
+                }
+
+                start..end.saturating_add(1).min(len)
+            },
+        };
+
+        Some(if range.start < range.end {
+            Self::Satisfiable(range)
+        } else {
+            Self::Unsatisfiable
+        })
+    }
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -171,6 +276,20 @@ pub(crate) struct Server {
     https: bool,
     #[clap(long, help = "Redirect HTTP traffic to HTTPS.")]
     redirect_http_to_https: bool,
+    #[clap(
+        long,
+        help = "Fetch off-chain inscription content from <OFFCHAIN_MIRROR>, a directory or \
+    http:// URL holding each file under its hex encoded SHA-256 hash. Content is verified \
+    against the inscribed hash and cached in the data dir. Files larger than 50 MB aren't \
+    fetched, and HTTP mirrors have 30 seconds to send them."
+    )]
+    offchain_mirror: Vec<Mirror>,
+    #[clap(
+        long,
+        help = "Track pending inscriptions and transfers in bitcoind's mempool, and serve them \
+    at /mempool."
+    )]
+    mempool: bool,
 }
 
 impl Server {
@@ -182,50 +301,94 @@ impl Server {
     ) -> Result {
         Runtime::new()?.block_on(async {
             let clone = index.clone();
+            let mempool = self.mempool;
             thread::spawn(move || loop {
                 if let Err(error) = clone.update() {
                     log::warn!("{error}");
                 }
+                if mempool {
+                    if let Err(error) = clone.update_mempool() {
+                        log::warn!("{error}");
+                    }
+                }
                 thread::sleep(Duration::from_millis(5000));
             });
 
             let config = options.load_config()?;
             let acme_domains = self.acme_domains()?;
 
+            let offchain_resolver = Arc::new(OffchainResolver::new(
+                options.data_dir()?.join("offchain"),
+                self.offchain_mirror.clone(),
+            ));
+
             let page_config = Arc::new(PageConfig {
                 chain: options.chain(),
                 domain: acme_domains.first().cloned(),
             });
 
-            let router = Router::new()
+            // Pages that return JSON when it's requested, which are also version 1 of
+            // the JSON API under `/api/v1`. Routes may be added to the API, and fields
+            // may be added to the objects it returns, but existing routes and fields
+            // must not be changed or removed, so breaking changes need a new version.
+            //
+            // Files under `/bfs` are served as they were inscribed, and content,
+            // previews, events, feeds and static assets aren't part of the API.
+            let pages = Router::new()
                 .route("/", get(Self::home))
+                .route("/address/:address", get(Self::address))
+                .route("/bfs/:bid", get(Self::bfs_root))
+                .route("/bfs/:bid/*path", get(Self::bfs))
+                .route("/bid/:name", get(Self::bid))
                 .route("/block-count", get(Self::block_count))
                 .route("/block/:query", get(Self::block))
+                .route("/bnw/:bid", get(Self::bnw))
+                .route("/bnw/:bid/:wall", get(Self::bnw_wall))
+                .route("/input/:block/:transaction/:input", get(Self::input))
+                .route("/inscription/:inscription_id", get(Self::inscription))
+                .route("/inscriptions", get(Self::inscriptions))
+                .route("/inscriptions/:from", get(Self::inscriptions_from))
+                .route("/mempool", get(Self::mempool))
+                .route("/output/:output", get(Self::output))
+                .route("/pub", get(Self::pub_inscriptions))
+                .route("/pub/:from", get(Self::pub_inscriptions_from))
+                .route("/range/:start/:end", get(Self::range))
+                .route("/resolve/:name", get(Self::resolve))
+                .route("/sat/:sat", get(Self::sat))
+                .route("/status", get(Self::status))
+                .route("/tx/:txid", get(Self::transaction));
+
+            let api = pages
+                .clone()
+                .layer(middleware::map_response(Self::json_error))
+                .layer(SetRequestHeaderLayer::overriding(
+                    header::ACCEPT,
+                    HeaderValue::from_static("application/json"),
+                ));
+
+            let router = Router::new()
                 .route("/bounties", get(Self::bounties))
                 .route("/clock", get(Self::clock))
                 .route("/content/:inscription_id", get(Self::content))
+                .route("/content/sha256/:hash", get(Self::content_by_hash))
+                .route("/events", get(Self::events))
+                .route("/events/ws", get(Self::events_ws))
                 .route("/faq", get(Self::faq))
                 .route("/favicon.ico", get(Self::favicon))
                 .route("/feed.xml", get(Self::feed))
-                .route("/input/:block/:transaction/:input", get(Self::input))
-                .route("/inscription/:inscription_id", get(Self::inscription))
-                .route("/inscriptions", get(Self::inscriptions))
-                .route("/inscriptions/:from", get(Self::inscriptions_from))
                 .route("/install.sh", get(Self::install_script))
                 .route("/ordinal/:sat", get(Self::ordinal))
-                .route("/output/:output", get(Self::output))
                 .route("/preview/:inscription_id", get(Self::preview))
-                .route("/range/:start/:end", get(Self::range))
                 .route("/rare.txt", get(Self::rare_txt))
-                .route("/sat/:sat", get(Self::sat))
                 .route("/search", get(Self::search_by_query))
                 .route("/search/:query", get(Self::search_by_path))
                 .route("/static/*path", get(Self::static_asset))
-                .route("/status", get(Self::status))
-                .route("/tx/:txid", get(Self::transaction))
+                .merge(pages)
+                .nest("/api/v1", api)
                 .layer(Extension(index))
                 .layer(Extension(page_config))
                 .layer(Extension(Arc::new(config)))
+                .layer(Extension(offchain_resolver))
                 .layer(SetResponseHeaderLayer::if_not_present(
                     header::CONTENT_SECURITY_POLICY,
                     HeaderValue::from_static("default-src 'self'"),
@@ -239,7 +402,19 @@ impl Server {
                         .allow_methods([http::Method::GET])
                         .allow_origin(Any),
                 )
-                .layer(CompressionLayer::new());
+                // Compressed event streams are buffered by the encoder, so events
+                // wouldn't reach clients as they happen, and the offsets of partial
+                // content refer to the uncompressed body
+                .layer(
+                    CompressionLayer::new().compress_when(
+                        DefaultPredicate::new()
+                            .and(NotForContentType::const_new("text/event-stream"))
+                            .and(|status: StatusCode, _, headers: &HeaderMap, _: &_| {
+                                status != StatusCode::PARTIAL_CONTENT
+                                    && !headers.contains_key(header::CONTENT_RANGE)
+                            }),
+                    ),
+                );
 
             match (self.http_port(), self.https_port()) {
                 (Some(http_port), None) => {
@@ -429,27 +604,74 @@ impl Server {
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
         Path(DeserializeFromStr(sat)): Path<DeserializeFromStr<Sat>>,
-    ) -> ServerResult<PageHtml<SatHtml>> {
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
         let satpoint = index.rare_sat_satpoint(sat)?;
 
-        Ok(SatHtml {
-            sat,
-            satpoint,
-            blocktime: index.blocktime(sat.height())?,
-            inscription: index.get_inscription_id_by_sat(sat)?,
-        }
-        .page(page_config, index.has_sat_index()?))
+        Self::negotiate(
+            &headers,
+            SatHtml {
+                sat,
+                satpoint,
+                blocktime: index.blocktime(sat.height())?,
+                inscription: index.get_inscription_id_by_sat(sat)?,
+            },
+            SatHtml::to_json,
+            page_config,
+            &index,
+        )
     }
 
     async fn ordinal(Path(sat): Path<String>) -> Redirect {
         Redirect::to(&format!("/sat/{sat}"))
     }
 
+    async fn address(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(DeserializeFromStr(address)): Path<DeserializeFromStr<Address>>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        if !address.is_valid_for_network(page_config.chain.network()) {
+            return Err(ServerError::BadRequest(format!(
+                "address {address} is not valid for {}",
+                page_config.chain
+            )));
+        }
+
+        let outputs = index
+            .get_address_outputs(&address.script_pubkey())?
+            .ok_or_else(|| {
+                ServerError::NotFound(
+                    "tracking addresses requires index created with `--index-addresses` flag"
+                        .into(),
+                )
+            })?
+            .into_iter()
+            .map(|outpoint| {
+                Ok(AddressOutput {
+                    outpoint,
+                    inscriptions: index.get_inscriptions_on_output(outpoint)?,
+                    rare_sats: index.get_rare_sats_on_output(outpoint)?,
+                })
+            })
+            .collect::<Result<Vec<AddressOutput>>>()?;
+
+        Self::negotiate(
+            &headers,
+            AddressHtml { address, outputs },
+            AddressHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
     async fn output(
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
         Path(outpoint): Path<OutPoint>,
-    ) -> ServerResult<PageHtml<OutputHtml>> {
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
         let list = if index.has_sat_index()? {
             index.list(outpoint)?
         } else {
@@ -481,14 +703,19 @@ impl Server {
 
         let inscriptions = index.get_inscriptions_on_output(outpoint)?;
 
-        Ok(OutputHtml {
-            outpoint,
-            inscriptions,
-            list,
-            chain: page_config.chain,
-            output,
-        }
-        .page(page_config, index.has_sat_index()?))
+        Self::negotiate(
+            &headers,
+            OutputHtml {
+                outpoint,
+                inscriptions,
+                list,
+                chain: page_config.chain,
+                output,
+            },
+            OutputHtml::to_json,
+            page_config,
+            &index,
+        )
     }
 
     async fn range(
@@ -498,15 +725,20 @@ impl Server {
             DeserializeFromStr<Sat>,
             DeserializeFromStr<Sat>,
         )>,
-    ) -> ServerResult<PageHtml<RangeHtml>> {
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
         match start.cmp(&end) {
             Ordering::Equal => Err(ServerError::BadRequest("empty range".to_string())),
             Ordering::Greater => Err(ServerError::BadRequest(
                 "range start greater than range end".to_string(),
             )),
-            Ordering::Less => {
-                Ok(RangeHtml { start, end }.page(page_config, index.has_sat_index()?))
-            },
+            Ordering::Less => Self::negotiate(
+                &headers,
+                RangeHtml { start, end },
+                RangeHtml::to_json,
+                page_config,
+                &index,
+            ),
         }
     }
 
@@ -521,10 +753,14 @@ impl Server {
     async fn home(
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
-    ) -> ServerResult<PageHtml<HomeHtml>> {
-        Ok(
-            HomeHtml::new(index.blocks(100)?, index.get_homepage_inscriptions()?)
-                .page(page_config, index.has_sat_index()?),
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::negotiate(
+            &headers,
+            HomeHtml::new(index.blocks(100)?, index.get_homepage_inscriptions()?),
+            HomeHtml::to_json,
+            page_config,
+            &index,
         )
     }
 
@@ -536,7 +772,8 @@ impl Server {
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
         Path(DeserializeFromStr(query)): Path<DeserializeFromStr<BlockQuery>>,
-    ) -> ServerResult<PageHtml<BlockHtml>> {
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
         let (block, height) = match query {
             BlockQuery::Height(height) => {
                 let block = index
@@ -558,9 +795,12 @@ impl Server {
             },
         };
 
-        Ok(
-            BlockHtml::new(block, Height(height), Self::index_height(&index)?)
-                .page(page_config, index.has_sat_index()?),
+        Self::negotiate(
+            &headers,
+            BlockHtml::new(block, Height(height), Self::index_height(&index)?),
+            BlockHtml::to_json,
+            page_config,
+            &index,
         )
     }
 
@@ -568,34 +808,72 @@ impl Server {
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
         Path(txid): Path<Txid>,
-    ) -> ServerResult<PageHtml<TransactionHtml>> {
-        let inscription = index.get_inscription_by_id(txid.into())?;
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let transaction = index
+            .get_transaction(txid)?
+            .ok_or_not_found(|| format!("transaction {txid}"))?;
+
+        let mut inscriptions = Vec::new();
+        for i in 0..Inscription::from_transaction(&transaction).len() {
+            let inscription_id = InscriptionId {
+                txid,
+                index: i.try_into().unwrap(),
+            };
+
+            if index.has_inscription(inscription_id)? {
+                inscriptions.push(inscription_id);
+            }
+        }
 
         let blockhash = index.get_transaction_blockhash(txid)?;
 
-        Ok(TransactionHtml::new(
-            index
-                .get_transaction(txid)?
-                .ok_or_not_found(|| format!("transaction {txid}"))?,
-            blockhash,
-            inscription.map(|_| txid.into()),
-            page_config.chain,
+        Self::negotiate(
+            &headers,
+            TransactionHtml::new(transaction, blockhash, inscriptions, page_config.chain),
+            TransactionHtml::to_json,
+            page_config,
+            &index,
         )
-        .page(page_config, index.has_sat_index()?))
     }
 
-    async fn status(Extension(index): Extension<Arc<Index>>) -> (StatusCode, &'static str) {
-        if index.is_reorged() {
+    async fn status(
+        Extension(index): Extension<Arc<Index>>,
+        headers: HeaderMap,
+    ) -> Response {
+        let reorged = index.is_reorged();
+
+        if Self::accepts_json(&headers) {
+            Json(StatusJson { reorged }).into_response()
+        } else if reorged {
             (
                 StatusCode::OK,
                 "reorg detected, please rebuild the database.",
             )
+                .into_response()
         } else {
             (
                 StatusCode::OK,
                 StatusCode::OK.canonical_reason().unwrap_or_default(),
             )
+                .into_response()
+        }
+    }
+
+    /// Give API errors a JSON body, so clients can parse every response.
+    async fn json_error(response: Response) -> Response {
+        let status = response.status();
+
+        if !(status.is_client_error() || status.is_server_error()) {
+            return response;
         }
+
+        let error = match hyper::body::to_bytes(response.into_body()).await {
+            Ok(body) if !body.is_empty() => String::from_utf8_lossy(&body).into_owned(),
+            _ => status.canonical_reason().unwrap_or_default().to_string(),
+        };
+
+        (status, Json(ErrorJson { error })).into_response()
     }
 
     async fn search_by_query(
@@ -725,15 +1003,25 @@ impl Server {
             .unwrap())
     }
 
-    async fn block_count(Extension(index): Extension<Arc<Index>>) -> ServerResult<String> {
-        Ok(index.block_count()?.to_string())
+    async fn block_count(
+        Extension(index): Extension<Arc<Index>>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let block_count = index.block_count()?;
+
+        Ok(if Self::accepts_json(&headers) {
+            Json(block_count).into_response()
+        } else {
+            block_count.to_string().into_response()
+        })
     }
 
     async fn input(
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
         Path(path): Path<(u64, usize, usize)>,
-    ) -> Result<PageHtml<InputHtml>, ServerError> {
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
         let not_found = || format!("input /{}/{}/{}", path.0, path.1, path.2);
 
         let block = index
@@ -752,7 +1040,13 @@ impl Server {
             .nth(path.2)
             .ok_or_not_found(not_found)?;
 
-        Ok(InputHtml { path, input }.page(page_config, index.has_sat_index()?))
+        Self::negotiate(
+            &headers,
+            InputHtml { path, input },
+            InputHtml::to_json,
+            page_config,
+            &index,
+        )
     }
 
     async fn faq() -> Redirect {
@@ -766,19 +1060,153 @@ impl Server {
     async fn content(
         Extension(index): Extension<Arc<Index>>,
         Extension(config): Extension<Arc<Config>>,
+        Extension(offchain_resolver): Extension<Arc<OffchainResolver>>,
         Path(inscription_id): Path<InscriptionId>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        if config.is_hidden(inscription_id) {
+            return Ok(PreviewUnknownHtml.into_response());
+        }
+
+        // Inscription content never changes, so clients that have it don't need the
+        // body to be built again
+        let etag = Self::etag(inscription_id);
+        if let Some(response) = Self::not_modified(&headers, &etag) {
+            return Ok(response);
+        }
+
+        let inscription = Self::resolve_content(
+            &index,
+            &offchain_resolver,
+            inscription_id,
+            index
+                .get_inscription_by_id(inscription_id)?
+                .ok_or_not_found(|| format!("inscription {inscription_id}"))?,
+        )
+        .await?;
+
+        Ok(Self::conditional_response(
+            &headers,
+            &etag,
+            Self::content_response(inscription)
+                .ok_or_not_found(|| format!("inscription {inscription_id} content"))?,
+        ))
+    }
+
+    /// Replace the body of multipart, reference and off-chain inscriptions,
+    /// whose content is elsewhere, with their content.
+    async fn resolve_content(
+        index: &Index,
+        offchain_resolver: &OffchainResolver,
+        inscription_id: InscriptionId,
+        inscription: Inscription,
+    ) -> ServerResult<Inscription> {
+        if let Some((content_type, content_hash)) = inscription.offchain_content() {
+            return match offchain_resolver.resolve(&content_hash).await? {
+                OffchainContent::Verified(content) => Ok(inscription.with_content(content_type, content)),
+                OffchainContent::Unverified => Err(ServerError::NotFound(format!(
+                    "inscription {inscription_id} off-chain content is unverified: no mirror has \
+                    content matching hash {content_hash}"
+                ))),
+                OffchainContent::Unavailable => Err(ServerError::NotFound(format!(
+                    "inscription {inscription_id} off-chain content is unavailable"
+                ))),
+            };
+        }
+
+        if let Some(content_hash) = inscription.content_reference() {
+            let (content_type, content) = Self::hashed_content(index, &content_hash)?
+                .ok_or_not_found(|| format!("inscription {inscription_id} content"))?;
+
+            return Ok(inscription.with_content(content_type, content));
+        }
+
+        if let Some(compressed) = inscription.compressed_content() {
+            let dictionary = match compressed.dictionary {
+                Some(dictionary_id) => Some(
+                    index
+                        .get_inscription_by_id(dictionary_id)?
+                        .and_then(|dictionary| dictionary.content())
+                        .map(|(_content_type, dictionary)| dictionary)
+                        .ok_or_not_found(|| {
+                            format!("inscription {inscription_id} compression dictionary {dictionary_id}")
+                        })?,
+                ),
+                None => None,
+            };
+
+            let content = compressed.decompress(dictionary.as_deref()).map_err(|err| {
+                ServerError::NotFound(format!("inscription {inscription_id} content: {err}"))
+            })?;
+
+            return Ok(inscription.with_content(compressed.content_type, content));
+        }
+
+        if let Some((content_type, content)) = inscription.wrapped_content() {
+            return Ok(inscription.with_content(Some(content_type), content));
+        }
+
+        let Some(manifest) = inscription.manifest() else {
+            return Ok(inscription);
+        };
+
+        let content = index.get_multipart_content(&manifest)?.map_err(|err| {
+            ServerError::NotFound(format!("inscription {inscription_id} content: {err}"))
+        })?;
+
+        Ok(inscription.with_content(Some(manifest.content_type), content))
+    }
+
+    /// The content type and content of the first inscription whose content has
+    /// `content_hash`.
+    fn hashed_content(
+        index: &Index,
+        content_hash: &str,
+    ) -> ServerResult<Option<(Option<String>, Vec<u8>)>> {
+        let Some(inscription_id) = index.get_inscription_id_by_content_hash(content_hash)? else {
+            return Ok(None);
+        };
+
+        Ok(index
+            .get_inscription_by_id(inscription_id)?
+            .and_then(|inscription| inscription.content()))
+    }
+
+    async fn content_by_hash(
+        Extension(index): Extension<Arc<Index>>,
+        Extension(config): Extension<Arc<Config>>,
+        Path(content_hash): Path<String>,
+        headers: HeaderMap,
     ) -> ServerResult<Response> {
+        let content_hash = content_hash.to_lowercase();
+
+        let inscription_id = index
+            .get_inscription_id_by_content_hash(&content_hash)?
+            .ok_or_not_found(|| format!("content {content_hash}"))?;
+
         if config.is_hidden(inscription_id) {
             return Ok(PreviewUnknownHtml.into_response());
         }
 
+        let etag = Self::etag(&content_hash);
+        if let Some(response) = Self::not_modified(&headers, &etag) {
+            return Ok(response);
+        }
+
         let inscription = index
             .get_inscription_by_id(inscription_id)?
             .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
-        Ok(Self::content_response(inscription)
-            .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
-            .into_response())
+        let (content_type, content) = inscription
+            .content()
+            .ok_or_not_found(|| format!("content {content_hash}"))?;
+
+        Ok(Self::conditional_response(
+            &headers,
+            &etag,
+            Self::content_response(inscription.with_content(content_type, content))
+                .ok_or_not_found(|| format!("content {content_hash}"))?,
+        ))
     }
 
     fn content_response(inscription: Inscription) -> Option<(HeaderMap, Vec<u8>)> {
@@ -822,24 +1250,100 @@ impl Server {
         Some((headers, body?))
     }
 
+    /// The `ETag` of immutable content identified by `tag`, such as an
+    /// inscription id or content hash. It's weak, since the same tag is sent
+    /// whether or not the response is compressed.
+    fn etag(tag: impl Display) -> String {
+        format!("W/\"{tag}\"")
+    }
+
+    /// A `304 Not Modified` response, if `If-None-Match` matches `etag`.
+    fn not_modified(
+        request_headers: &HeaderMap,
+        etag: &str,
+    ) -> Option<Response> {
+        let tag = etag.trim_start_matches("W/");
+
+        request_headers
+            .get_all(header::IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|candidate| candidate.trim().trim_start_matches("W/"))
+            .any(|candidate| candidate == "*" || candidate == tag)
+            .then(|| (StatusCode::NOT_MODIFIED, [(header::ETAG, etag.to_string())]).into_response())
+    }
+
+    /// Send content with `etag`, and honor `If-None-Match` and `Range`
+    /// requests, so clients can revalidate cached content and seek in audio
+    /// and video without fetching the whole body.
+    fn conditional_response(
+        request_headers: &HeaderMap,
+        etag: &str,
+        (mut headers, body): (HeaderMap, Vec<u8>),
+    ) -> Response {
+        if let Some(response) = Self::not_modified(request_headers, etag) {
+            return response;
+        }
+
+        headers.insert(header::ETAG, etag.parse().unwrap());
+        headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+        let range = request_headers
+            .get(header::RANGE)
+            .and_then(|range| range.to_str().ok())
+            .and_then(|range| ByteRange::parse(range, body.len()));
+
+        match range {
+            Some(ByteRange::Satisfiable(range)) => {
+                headers.insert(
+                    header::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", range.start, range.end - 1, body.len())
+                        .parse()
+                        .unwrap(),
+                );
+                (StatusCode::PARTIAL_CONTENT, headers, body[range].to_vec()).into_response()
+            },
+            Some(ByteRange::Unsatisfiable) => {
+                headers.insert(
+                    header::CONTENT_RANGE,
+                    format!("bytes */{}", body.len()).parse().unwrap(),
+                );
+                (StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response()
+            },
+            None => (headers, body).into_response(),
+        }
+    }
+
     async fn preview(
         Extension(index): Extension<Arc<Index>>,
         Extension(config): Extension<Arc<Config>>,
+        Extension(offchain_resolver): Extension<Arc<OffchainResolver>>,
         Path(inscription_id): Path<InscriptionId>,
+        headers: HeaderMap,
     ) -> ServerResult<Response> {
         if config.is_hidden(inscription_id) {
             return Ok(PreviewUnknownHtml.into_response());
         }
 
-        let inscription = index
-            .get_inscription_by_id(inscription_id)?
-            .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+        let inscription = Self::resolve_content(
+            &index,
+            &offchain_resolver,
+            inscription_id,
+            index
+                .get_inscription_by_id(inscription_id)?
+                .ok_or_not_found(|| format!("inscription {inscription_id}"))?,
+        )
+        .await?;
 
         match inscription.media() {
             Media::Audio => Ok(PreviewAudioHtml { inscription_id }.into_response()),
-            Media::Iframe => Ok(Self::content_response(inscription)
-                .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
-                .into_response()),
+            Media::Iframe => Ok(Self::conditional_response(
+                &headers,
+                &Self::etag(inscription_id),
+                Self::content_response(inscription)
+                    .ok_or_not_found(|| format!("inscription {inscription_id} content"))?,
+            )),
             Media::Image => Ok((
                 [(
                     header::CONTENT_SECURITY_POLICY,
@@ -871,18 +1375,18 @@ impl Server {
         }
     }
 
-    async fn inscription(
-        Extension(page_config): Extension<Arc<PageConfig>>,
-        Extension(index): Extension<Arc<Index>>,
-        Path(inscription_id): Path<InscriptionId>,
-    ) -> ServerResult<PageHtml<InscriptionHtml>> {
-        let entry = index
-            .get_inscription_entry(inscription_id)?
-            .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+    fn bid_html(
+        index: &Index,
+        chain: Chain,
+        name: &str,
+    ) -> ServerResult<BidHtml> {
+        let username = name
+            .parse::<Username>()
+            .map_err(|err| ServerError::BadRequest(err.to_string()))?;
 
-        let inscription = index
-            .get_inscription_by_id(inscription_id)?
-            .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+        let (inscription_id, entry) = index
+            .get_bid_registration(&username)?
+            .ok_or_not_found(|| format!("bid {username}"))?;
 
         let satpoint = index
             .get_inscription_satpoint_by_id(inscription_id)?
@@ -898,95 +1402,683 @@ impl Server {
                 format!("inscription {inscription_id} current transaction output")
             })?;
 
-        let previous = if let Some(previous) = entry.number.checked_sub(1) {
-            Some(
-                index
-                    .get_inscription_id_by_inscription_number(previous)?
-                    .ok_or_not_found(|| format!("inscription {previous}"))?,
-            )
-        } else {
-            None
-        };
-
-        let next = index.get_inscription_id_by_inscription_number(entry.number + 1)?;
-
-        Ok(InscriptionHtml {
-            chain: page_config.chain,
-            genesis_fee: entry.fee,
-            genesis_height: entry.height,
-            inscription,
+        Ok(BidHtml {
+            chain,
+            username,
             inscription_id,
-            next,
-            number: entry.number,
-            output,
-            previous,
-            sat: entry.sat,
+            registered: entry.registered,
+            expires: entry.expires,
+            active: entry.is_active(index.block_count()?),
             satpoint,
-            timestamp: timestamp(entry.timestamp),
-        }
-        .page(page_config, index.has_sat_index()?))
+            output,
+        })
     }
 
-    async fn inscriptions(
+    async fn bid(
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
-    ) -> ServerResult<PageHtml<InscriptionsHtml>> {
-        Self::inscriptions_inner(page_config, index, None).await
+        Path(name): Path<String>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::negotiate(
+            &headers,
+            Self::bid_html(&index, page_config.chain, &name)?,
+            BidHtml::to_json,
+            page_config,
+            &index,
+        )
     }
 
-    async fn inscriptions_from(
+    async fn bfs_root(
         Extension(page_config): Extension<Arc<PageConfig>>,
         Extension(index): Extension<Arc<Index>>,
-        Path(from): Path<u64>,
-    ) -> ServerResult<PageHtml<InscriptionsHtml>> {
-        Self::inscriptions_inner(page_config, index, Some(from)).await
+        Path(bid): Path<String>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::bfs_response(&index, page_config, &bid, "/", &headers)
     }
 
-    async fn inscriptions_inner(
-        page_config: Arc<PageConfig>,
-        index: Arc<Index>,
-        from: Option<u64>,
-    ) -> ServerResult<PageHtml<InscriptionsHtml>> {
-        let (inscriptions, prev, next) =
-            index.get_latest_inscriptions_with_prev_and_next(100, from)?;
-        Ok(InscriptionsHtml {
-            inscriptions,
-            next,
-            prev,
+    async fn bfs(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path((bid, path)): Path<(String, String)>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let path = path.trim_end_matches('/');
+
+        if path.starts_with('/') {
+            Self::bfs_response(&index, page_config, &bid, path, &headers)
+        } else {
+            Self::bfs_response(&index, page_config, &bid, &format!("/{path}"), &headers)
         }
-        .page(page_config, index.has_sat_index()?))
     }
 
-    async fn redirect_http_to_https(
-        Extension(mut destination): Extension<String>,
-        uri: Uri,
-    ) -> Redirect {
-        if let Some(path_and_query) = uri.path_and_query() {
-            destination.push_str(path_and_query.as_str());
-        }
+    /// Serve the file at `path` in the file tree of `bid`, or list it if it's a
+    /// directory.
+    fn bfs_response(
+        index: &Index,
+        page_config: Arc<PageConfig>,
+        bid: &str,
+        path: &str,
+        headers: &HeaderMap,
+    ) -> ServerResult<Response> {
+        let bid = bid
+            .parse::<Username>()
+            .map_err(|err| ServerError::BadRequest(err.to_string()))?;
 
-        Redirect::to(&destination)
-    }
-}
+        let path = if path.is_empty() { "/" } else { path }
+            .parse::<BfsPath>()
+            .map_err(|err| ServerError::BadRequest(err.to_string()))?;
 
-#[cfg(test)]
-mod tests {
-    use {
-        super::*,
-        reqwest::Url,
-        std::net::TcpListener,
-    };
+        index
+            .get_bid_registration(&bid)?
+            .ok_or_not_found(|| format!("bid {bid}"))?;
 
-    struct TestServer {
-        bitcoin_rpc_server: test_bitcoincore_rpc::Handle,
-        index: Arc<Index>,
-        arb_server_handle: Handle,
-        url: Url,
-        #[allow(unused)]
-        tempdir: TempDir,
-    }
+        if let Some(inscription_id) = index.get_bfs_file(&bid, &path)? {
+            let (content_type, content) = index
+                .get_inscription_by_id(inscription_id)?
+                .ok_or_not_found(|| format!("inscription {inscription_id}"))?
+                .wrapped_content()
+                .ok_or_not_found(|| format!("inscription {inscription_id} content"))?;
 
-    impl TestServer {
+            let mut headers = HeaderMap::new();
+
+            headers.insert(
+                header::CONTENT_TYPE,
+                content_type
+                    .parse()
+                    .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            );
+            headers.insert(
+                header::CONTENT_SECURITY_POLICY,
+                HeaderValue::from_static("default-src 'unsafe-eval' 'unsafe-inline' data:"),
+            );
+
+            return Ok((headers, content).into_response());
+        }
+
+        let files = index.get_bfs_files(&bid, &path)?;
+
+        if files.is_empty() && !path.is_root() {
+            return Err(ServerError::NotFound(format!("bfs path {bid}{path}")));
+        }
+
+        Self::negotiate(
+            headers,
+            BfsHtml {
+                entries: bfs::list_directory(&path, &files),
+                bid,
+                path,
+            },
+            BfsHtml::to_json,
+            page_config,
+            index,
+        )
+    }
+
+    async fn bnw(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(bid): Path<String>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::negotiate(
+            &headers,
+            Self::bnw_html(&index, &bid, None)?,
+            BnwHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
+    async fn bnw_wall(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path((bid, wall)): Path<(String, String)>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::negotiate(
+            &headers,
+            Self::bnw_html(&index, &bid, Some(&wall))?,
+            BnwHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
+    /// Render the walls of `bid`, or just `wall`, showing the inscriptions that
+    /// the owner of `bid` still holds. A listed inscription counts as held if it
+    /// hasn't moved since the wall was inscribed, since the wallet checks
+    /// ownership when building walls, or if it's held by the same script as the
+    /// registration of `bid`.
+    fn bnw_html(
+        index: &Index,
+        bid: &str,
+        wall: Option<&str>,
+    ) -> ServerResult<BnwHtml> {
+        let bid = bid
+            .parse::<Username>()
+            .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+        let (registration, entry) = index
+            .get_bid_registration(&bid)?
+            .ok_or_not_found(|| format!("bid {bid}"))?;
+
+        if !entry.is_active(index.block_count()?) {
+            return Err(ServerError::NotFound(format!("bid {bid} has expired")));
+        }
+
+        let owner = Self::inscription_script_pubkey(index, registration)?
+            .ok_or_not_found(|| format!("inscription {registration}"))?;
+
+        let wall_ids = match wall {
+            Some(wall) => {
+                let wall = wall
+                    .parse::<WallName>()
+                    .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+                vec![index
+                    .get_bnw_wall(&bid, &wall)?
+                    .ok_or_not_found(|| format!("bnw wall {bid}/{wall}"))?]
+            },
+            None => index.get_bnw_walls(&bid)?,
+        };
+
+        let mut walls = Vec::new();
+
+        for wall_id in wall_ids {
+            let inscription = index
+                .get_inscription_by_id(wall_id)?
+                .ok_or_not_found(|| format!("inscription {wall_id}"))?;
+
+            let Some(Ok(wall)) = bnw::Wall::from_inscription(&inscription) else {
+        continue;
+      };
+
+            let satpoints = index.get_bnw_wall_satpoints(wall_id)?;
+
+            let mut inscriptions = Vec::new();
+            for (inscription_id, satpoint) in wall.inscriptions.into_iter().zip(satpoints) {
+                let unmoved =
+                    index.get_inscription_satpoint_by_id(inscription_id)? == Some(satpoint);
+
+                if unmoved
+                    || Self::inscription_script_pubkey(index, inscription_id)?.as_ref()
+                        == Some(&owner)
+                {
+                    inscriptions.push(inscription_id);
+                }
+            }
+
+            walls.push(BnwWall {
+                name: wall.name,
+                description: inscription
+                    .wrapped_content()
+                    .map(|(_, content)| String::from_utf8_lossy(&content).into_owned())
+                    .unwrap_or_default(),
+                inscriptions,
+            });
+        }
+
+        Ok(BnwHtml { bid, walls })
+    }
+
+    /// The script pubkey of the output currently holding `inscription_id`.
+    fn inscription_script_pubkey(
+        index: &Index,
+        inscription_id: InscriptionId,
+    ) -> Result<Option<Script>> {
+        let Some(satpoint) = index.get_inscription_satpoint_by_id(inscription_id)? else {
+      return Ok(None);
+    };
+
+        Ok(index
+            .get_transaction(satpoint.outpoint.txid)?
+            .and_then(|tx| {
+                tx.output
+                    .into_iter()
+                    .nth(satpoint.outpoint.vout.try_into().unwrap())
+            })
+            .map(|output| output.script_pubkey))
+    }
+
+    async fn resolve(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(name): Path<String>,
+    ) -> ServerResult<Json<BidJson>> {
+        Ok(Json(
+            Self::bid_html(&index, page_config.chain, &name)?.to_json(),
+        ))
+    }
+
+    async fn inscription(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(inscription_id): Path<InscriptionId>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let Some(entry) = index.get_inscription_entry(inscription_id)? else {
+            return Self::pub_inscription(page_config, index, inscription_id, headers);
+        };
+
+        let inscription = index
+            .get_inscription_by_id(inscription_id)?
+            .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+        let satpoint = index
+            .get_inscription_satpoint_by_id(inscription_id)?
+            .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+        let output = index
+            .get_transaction(satpoint.outpoint.txid)?
+            .ok_or_not_found(|| format!("inscription {inscription_id} current transaction"))?
+            .output
+            .into_iter()
+            .nth(satpoint.outpoint.vout.try_into().unwrap())
+            .ok_or_not_found(|| {
+                format!("inscription {inscription_id} current transaction output")
+            })?;
+
+        let previous = if let Some(previous) = entry.number.checked_sub(1) {
+            Some(
+                index
+                    .get_inscription_id_by_inscription_number(previous)?
+                    .ok_or_not_found(|| format!("inscription {previous}"))?,
+            )
+        } else {
+            None
+        };
+
+        let next = index.get_inscription_id_by_inscription_number(entry.number + 1)?;
+
+        let protocol_id = index
+            .get_inscription_protocol_id(inscription_id)?
+            .ok_or_not_found(|| format!("inscription {inscription_id} protocol"))?;
+
+        let validation_error = index.get_inscription_validation_error(inscription_id)?;
+
+        let inscription = InscriptionHtml {
+            chain: page_config.chain,
+            children: index.get_children(inscription_id)?,
+            genesis_fee: entry.fee,
+            genesis_height: entry.height,
+            inscription,
+            inscription_id,
+            next,
+            number: entry.number,
+            output,
+            parent: index.get_parent(inscription_id)?,
+            pending: index.get_pending_by_id(inscription_id),
+            previous,
+            protocol_id,
+            sat: entry.sat,
+            satpoint,
+            timestamp: timestamp(entry.timestamp),
+            transfers: index.get_transfers(inscription_id)?,
+            validation_error,
+        };
+
+        Self::negotiate(
+            &headers,
+            inscription,
+            InscriptionHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
+    fn pub_inscription(
+        page_config: Arc<PageConfig>,
+        index: Arc<Index>,
+        inscription_id: InscriptionId,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let entry = index
+            .get_pub_inscription_entry(inscription_id)?
+            .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+        let inscription = index
+            .get_inscription_by_id(inscription_id)?
+            .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+        let previous = if let Some(previous) = entry.number.checked_sub(1) {
+            Some(
+                index
+                    .get_pub_inscription_id_by_number(previous)?
+                    .ok_or_not_found(|| format!("pub inscription {previous}"))?,
+            )
+        } else {
+            None
+        };
+
+        let next = index.get_pub_inscription_id_by_number(entry.number + 1)?;
+
+        let protocol_id = index
+            .get_inscription_protocol_id(inscription_id)?
+            .ok_or_not_found(|| format!("inscription {inscription_id} protocol"))?;
+
+        Self::negotiate(
+            &headers,
+            PubInscriptionHtml {
+                genesis_fee: entry.fee,
+                genesis_height: entry.height,
+                inscription,
+                inscription_id,
+                next,
+                number: entry.number,
+                previous,
+                protocol_id,
+                timestamp: timestamp(entry.timestamp),
+                validation_error: index.get_inscription_validation_error(inscription_id)?,
+            },
+            PubInscriptionHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
+    /// Events as they're indexed, preceded by the stored events of the blocks
+    /// at and after `from`, if given.
+    ///
+    /// Events are always read from the index, starting at the first block that
+    /// hasn't been sent, and live events only signal that there are new blocks
+    /// to read, so each event is sent exactly once, even after the subscriber
+    /// falls behind.
+    /// The events of blocks from `from`, or from the next block, ending with an
+    /// error if the index can't be read.
+    fn event_stream(
+        index: Arc<Index>,
+        from: Option<u64>,
+    ) -> ServerResult<impl Stream<Item = Result<Event>>> {
+        // Subscribing before reading the block count ensures that no blocks are
+        // missed
+        let receiver = index.subscribe_events();
+
+        let next = match from {
+            Some(from) => from,
+            None => index.block_count()?,
+        };
+
+        Ok(stream::unfold(
+            Some((index, receiver, next, VecDeque::new())),
+            |state| async move {
+                let (index, mut receiver, mut next, mut backlog) = state?;
+
+                loop {
+                    if let Some(event) = backlog.pop_front() {
+                        return Some((Ok(event), Some((index, receiver, next, backlog))));
+                    }
+
+                    let block_count = match index.block_count() {
+                        Ok(block_count) => block_count,
+                        Err(err) => return Some((Err(err), None)),
+                    };
+
+                    if next < block_count {
+                        let end = cmp::min(next + 100, block_count);
+                        match index.get_events(next..end) {
+                            Ok(events) => backlog.extend(events),
+                            Err(err) => return Some((Err(err), None)),
+                        }
+                        next = end;
+                        continue;
+                    }
+
+                    let height = match receiver.recv().await {
+                        Ok(Event::Reorged { height }) => height,
+                        // Missed events may include reorgs, so subscribers that fall behind
+                        // are told to drop the blocks a reorg could have rolled back
+                        Err(RecvError::Lagged(_)) => index.oldest_savepoint().unwrap_or(next),
+                        // The events of the new blocks are read from the index
+                        Ok(_) => continue,
+                        Err(RecvError::Closed) => return None,
+                    };
+
+                    // Blocks at and after `height` may have been replaced, so read them again
+                    next = cmp::min(next, height);
+
+                    return Some((
+                        Ok(Event::Reorged { height }),
+                        Some((index, receiver, next, backlog)),
+                    ));
+                }
+            },
+        ))
+    }
+
+    async fn events(
+        Extension(index): Extension<Arc<Index>>,
+        Query(query): Query<EventsQuery>,
+    ) -> ServerResult<Sse<impl Stream<Item = Result<sse::Event, Infallible>>>> {
+        let events = Self::event_stream(index, query.from)?.map(|event| {
+            Ok(match event {
+                Ok(event) => sse::Event::default()
+                    .event(event.name())
+                    .id(event.height().to_string())
+                    .data(serde_json::to_string(&event).unwrap_or_default()),
+                Err(err) => sse::Event::default().event("error").data(
+                    serde_json::to_string(&ErrorJson {
+                        error: err.to_string(),
+                    })
+                    .unwrap_or_default(),
+                ),
+            })
+        });
+
+        Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+    }
+
+    /// The same events as `/events`, as JSON text messages over a WebSocket.
+    async fn events_ws(
+        Extension(index): Extension<Arc<Index>>,
+        Query(query): Query<EventsQuery>,
+        upgrade: WebSocketUpgrade,
+    ) -> ServerResult<Response> {
+        let mut events = Box::pin(Self::event_stream(index, query.from)?);
+
+        Ok(upgrade.on_upgrade(|mut socket| async move {
+            while let Some(event) = events.next().await {
+                let text = match event {
+                    Ok(event) => match serde_json::to_string(&event) {
+                        Ok(text) => text,
+                        Err(_) => continue,
+                    },
+                    Err(err) => serde_json::json!({ "type": "error", "error": err.to_string() })
+                        .to_string(),
+                };
+
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+        }))
+    }
+
+    async fn mempool(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let pending = index.get_pending().ok_or_else(|| {
+            ServerError::NotFound("tracking the mempool requires server started with `--mempool` flag".into())
+        })?;
+
+        Self::negotiate(
+            &headers,
+            MempoolHtml { pending },
+            |mempool| mempool.pending.clone(),
+            page_config,
+            &index,
+        )
+    }
+
+    /// Respond with the JSON representation of a page's content if the request
+    /// asks for JSON, and with the page otherwise.
+    fn negotiate<T: PageContent, J: Serialize>(
+        headers: &HeaderMap,
+        content: T,
+        to_json: impl FnOnce(&T) -> J,
+        page_config: Arc<PageConfig>,
+        index: &Index,
+    ) -> ServerResult<Response> {
+        Ok(if Self::accepts_json(headers) {
+            Json(to_json(&content)).into_response()
+        } else {
+            content
+                .page(page_config, index.has_sat_index()?)
+                .into_response()
+        })
+    }
+
+    /// Whether a request's `Accept` header asks for JSON.
+    fn accepts_json(headers: &HeaderMap) -> bool {
+        headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .map_or(false, |accept| accept.contains("application/json"))
+    }
+
+    async fn inscriptions(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Query(query): Query<InscriptionQuery>,
+        RawQuery(raw_query): RawQuery,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let Some(raw_query) = raw_query.filter(|raw_query| !raw_query.is_empty()) else {
+            return Self::inscriptions_inner(page_config, index, None, headers).await;
+        };
+
+        if let Some(error) = query.error() {
+            return Err(ServerError::BadRequest(error));
+        }
+
+        let (inscriptions, cursor) = index.query_inscriptions(&query)?;
+
+        Self::negotiate(
+            &headers,
+            InscriptionsHtml {
+                inscriptions,
+                prev: None,
+                next: None,
+                query: Some(
+                    raw_query
+                        .split('&')
+                        .filter(|pair| !pair.is_empty() && !pair.starts_with("cursor="))
+                        .collect::<Vec<&str>>()
+                        .join("&"),
+                ),
+                cursor,
+            },
+            InscriptionsHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
+    async fn inscriptions_from(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(from): Path<u64>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::inscriptions_inner(page_config, index, Some(from), headers).await
+    }
+
+    async fn inscriptions_inner(
+        page_config: Arc<PageConfig>,
+        index: Arc<Index>,
+        from: Option<u64>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let (inscriptions, prev, next) =
+            index.get_latest_inscriptions_with_prev_and_next(100, from)?;
+
+        Self::negotiate(
+            &headers,
+            InscriptionsHtml {
+                inscriptions,
+                next,
+                prev,
+                query: None,
+                cursor: None,
+            },
+            InscriptionsHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
+    async fn pub_inscriptions(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::pub_inscriptions_inner(page_config, index, None, headers)
+    }
+
+    async fn pub_inscriptions_from(
+        Extension(page_config): Extension<Arc<PageConfig>>,
+        Extension(index): Extension<Arc<Index>>,
+        Path(from): Path<u64>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        Self::pub_inscriptions_inner(page_config, index, Some(from), headers)
+    }
+
+    fn pub_inscriptions_inner(
+        page_config: Arc<PageConfig>,
+        index: Arc<Index>,
+        from: Option<u64>,
+        headers: HeaderMap,
+    ) -> ServerResult<Response> {
+        let (inscriptions, prev, next) =
+            index.get_latest_pub_inscriptions_with_prev_and_next(100, from)?;
+
+        Self::negotiate(
+            &headers,
+            PubInscriptionsHtml {
+                inscriptions,
+                next,
+                prev,
+            },
+            PubInscriptionsHtml::to_json,
+            page_config,
+            &index,
+        )
+    }
+
+    async fn redirect_http_to_https(
+        Extension(mut destination): Extension<String>,
+        uri: Uri,
+    ) -> Redirect {
+        if let Some(path_and_query) = uri.path_and_query() {
+            destination.push_str(path_and_query.as_str());
+        }
+
+        Redirect::to(&destination)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        reqwest::Url,
+        std::net::TcpListener,
+    };
+
+    struct TestServer {
+        bitcoin_rpc_server: test_bitcoincore_rpc::Handle,
+        index: Arc<Index>,
+        arb_server_handle: Handle,
+        url: Url,
+        #[allow(unused)]
+        tempdir: TempDir,
+    }
+
+    impl TestServer {
         fn new() -> Self {
             Self::new_with_args(&[], &[])
         }
@@ -1098,6 +2190,29 @@ mod tests {
             reqwest::blocking::get(self.join_url(path.as_ref())).unwrap()
         }
 
+        fn get_json(
+            &self,
+            path: impl AsRef<str>,
+        ) -> serde_json::Value {
+            if let Err(error) = self.index.update() {
+                log::error!("{error}");
+            }
+
+            let response = reqwest::blocking::Client::new()
+                .get(self.join_url(path.as_ref()))
+                .header(header::ACCEPT, "application/json")
+                .send()
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers().get(header::CONTENT_TYPE).unwrap(),
+                "application/json"
+            );
+
+            serde_json::from_str(&response.text().unwrap()).unwrap()
+        }
+
         fn join_url(
             &self,
             url: &str,
@@ -1814,6 +2929,43 @@ mod tests {
         test_server.assert_response_regex("/block/0", StatusCode::OK, ".*<h1>Block 0</h1>.*");
     }
 
+    #[test]
+    fn transaction_lists_every_inscription_it_makes() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let mut builder = bitcoin::blockdata::script::Builder::new();
+        for body in ["foo", "bar"] {
+            builder = inscription("text/plain", body).append_reveal_script_to_builder(builder);
+        }
+
+        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            outputs: 2,
+            witness: Witness::from_vec(vec![builder.into_script().into_bytes(), Vec::new()]),
+            ..Default::default()
+        });
+
+        server.mine_blocks(1);
+
+        server.assert_response_regex(
+            format!("/tx/{txid}"),
+            StatusCode::OK,
+            format!(
+                ".*<h2>Inscription Geneses</h2>
+<div class=thumbnails>
+<a href=/inscription/{txid}i0>.*</a>
+<a href=/inscription/{txid}i1>.*</a>
+</div>.*"
+            ),
+        );
+
+        assert_eq!(
+            server.get_json(format!("/api/v1/tx/{txid}"))["inscriptions"],
+            serde_json::json!([format!("{txid}i0"), format!("{txid}i1")])
+        );
+    }
+
     #[test]
     fn transaction() {
         let test_server = TestServer::new();
@@ -1847,7 +2999,7 @@ mod tests {
     }
 
     #[test]
-    fn detect_reorg() {
+    fn recover_from_reorg() {
         let test_server = TestServer::new();
 
         test_server.mine_blocks(1);
@@ -1857,6 +3009,25 @@ mod tests {
         test_server.bitcoin_rpc_server.invalidate_tip();
         test_server.bitcoin_rpc_server.mine_blocks(2);
 
+        test_server.assert_response("/status", StatusCode::OK, "OK");
+        test_server.assert_response("/block-count", StatusCode::OK, "3");
+    }
+
+    #[test]
+    fn detect_unrecoverable_reorg() {
+        let test_server = TestServer::new();
+
+        for _ in 0..35 {
+            test_server.mine_blocks(1);
+        }
+
+        test_server.assert_response("/status", StatusCode::OK, "OK");
+
+        for _ in 0..30 {
+            test_server.bitcoin_rpc_server.invalidate_tip();
+        }
+        test_server.bitcoin_rpc_server.mine_blocks(31);
+
         test_server.assert_response_regex("/status", StatusCode::OK, "reorg detected.*");
     }
 
@@ -1871,6 +3042,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn address() {
+        TestServer::new_with_args(&["--index-addresses"], &[]).assert_response_regex(
+            "/address/bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw",
+            StatusCode::OK,
+            ".*<title>Address bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw</title>.*
+<h1>Address <span class=monospace>bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw</span></h1>
+<p>No outputs with inscriptions or rare sats.</p>
+.*",
+        );
+    }
+
+    #[test]
+    fn address_json() {
+        assert_eq!(
+            TestServer::new_with_args(&["--index-addresses"], &[])
+                .get_json("/api/v1/address/bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw"),
+            serde_json::json!({
+                "address": "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw",
+                "outputs": [],
+            })
+        );
+    }
+
+    #[test]
+    fn address_must_be_valid_for_chain() {
+        TestServer::new_with_args(&["--index-addresses"], &[]).assert_response(
+            "/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            StatusCode::BAD_REQUEST,
+            "address bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 is not valid for regtest",
+        );
+    }
+
+    #[test]
+    fn address_without_address_index() {
+        TestServer::new().assert_response(
+            "/address/bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw",
+            StatusCode::NOT_FOUND,
+            "tracking addresses requires index created with `--index-addresses` flag",
+        );
+    }
+
     #[test]
     fn rare_without_sat_index() {
         TestServer::new().assert_response(
@@ -2391,62 +3604,311 @@ mod tests {
 
         let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
             inputs: &[(1, 0, 0)],
-            witness: Inscription::new(Some("foo/bar".as_bytes().to_vec()), None).to_witness(),
+            witness: Inscription::new(Some("foo/bar".as_bytes().to_vec()), None).to_witness(),
+            ..Default::default()
+        });
+
+        let inscription_id = InscriptionId::from(txid);
+
+        server.mine_blocks(1);
+
+        server.assert_response(
+            format!("/preview/{inscription_id}"),
+            StatusCode::OK,
+            &fs::read_to_string("templates/preview-unknown.html").unwrap(),
+        );
+    }
+
+    #[test]
+    fn inscription_with_known_type_and_no_body_has_unknown_preview() {
+        let server = TestServer::new_with_sat_index();
+        server.mine_blocks(1);
+
+        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            witness: Inscription::new(Some("image/png".as_bytes().to_vec()), None).to_witness(),
+            ..Default::default()
+        });
+
+        let inscription_id = InscriptionId::from(txid);
+
+        server.mine_blocks(1);
+
+        server.assert_response(
+            format!("/preview/{inscription_id}"),
+            StatusCode::OK,
+            &fs::read_to_string("templates/preview-unknown.html").unwrap(),
+        );
+    }
+
+    #[test]
+    fn content_responses_have_cache_control_headers() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            witness: inscription("text/foo", "hello").to_witness(),
+            ..Default::default()
+        });
+
+        server.mine_blocks(1);
+
+        let response = server.get(format!("/content/{}", InscriptionId::from(txid)));
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            "max-age=31536000, immutable"
+        );
+    }
+
+    #[test]
+    fn byte_ranges() {
+        assert_eq!(
+            ByteRange::parse("bytes=0-1", 5),
+            Some(ByteRange::Satisfiable(0..2))
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=3-", 5),
+            Some(ByteRange::Satisfiable(3..5))
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=-2", 5),
+            Some(ByteRange::Satisfiable(3..5))
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=-10", 5),
+            Some(ByteRange::Satisfiable(0..5))
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=2-100", 5),
+            Some(ByteRange::Satisfiable(2..5))
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=5-", 5),
+            Some(ByteRange::Unsatisfiable)
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=-0", 5),
+            Some(ByteRange::Unsatisfiable)
+        );
+        assert_eq!(ByteRange::parse("bytes=2-1", 5), None);
+        assert_eq!(ByteRange::parse("bytes=0-1,3-4", 5), None);
+        assert_eq!(ByteRange::parse("items=0-1", 5), None);
+        assert_eq!(ByteRange::parse("bytes=a-b", 5), None);
+    }
+
+    #[test]
+    fn content_supports_ranges_and_conditional_requests() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            witness: inscription("audio/flac", "hello").to_witness(),
+            ..Default::default()
+        });
+
+        server.mine_blocks(1);
+
+        let path = format!("/content/{}", InscriptionId::from(txid));
+        let etag = format!("W/\"{}\"", InscriptionId::from(txid));
+
+        let get = |name: HeaderName, value: &str| {
+            server.index.update().unwrap();
+            reqwest::blocking::Client::new()
+                .get(server.join_url(&path))
+                .header(name, value)
+                .send()
+                .unwrap()
+        };
+
+        let response = server.get(&path);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), &etag);
+        assert_eq!(
+            response.headers().get(header::ACCEPT_RANGES).unwrap(),
+            "bytes"
+        );
+        assert_eq!(response.text().unwrap(), "hello");
+
+        let response = get(header::RANGE, "bytes=1-3");
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 1-3/5"
+        );
+        assert_eq!(response.text().unwrap(), "ell");
+
+        let response = get(header::RANGE, "bytes=5-");
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */5"
+        );
+
+        let response = get(header::IF_NONE_MATCH, &etag);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), &etag);
+        assert_eq!(response.text().unwrap(), "");
+
+        let response = get(header::IF_NONE_MATCH, "\"foo\"");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().unwrap(), "hello");
+    }
+
+    #[test]
+    fn partial_content_is_not_compressed() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let content = "hello".repeat(100);
+
+        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            witness: inscription("text/plain;charset=utf-8", &content).to_witness(),
             ..Default::default()
         });
 
-        let inscription_id = InscriptionId::from(txid);
-
         server.mine_blocks(1);
 
-        server.assert_response(
-            format!("/preview/{inscription_id}"),
-            StatusCode::OK,
-            &fs::read_to_string("templates/preview-unknown.html").unwrap(),
+        let get = |range: Option<&str>| {
+            let mut request = reqwest::blocking::Client::new()
+                .get(server.join_url(&format!("/content/{}", InscriptionId::from(txid))))
+                .header(header::ACCEPT_ENCODING, "gzip");
+            if let Some(range) = range {
+                request = request.header(header::RANGE, range);
+            }
+            request.send().unwrap()
+        };
+
+        let response = get(None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "gzip"
+        );
+
+        let response = get(Some("bytes=100-199"));
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers().get(header::CONTENT_ENCODING), None);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 100-199/500"
         );
+        assert_eq!(response.text().unwrap(), &content[100..200]);
     }
 
     #[test]
-    fn inscription_with_known_type_and_no_body_has_unknown_preview() {
-        let server = TestServer::new_with_sat_index();
-        server.mine_blocks(1);
+    fn content_is_served_by_hash() {
+        let server = TestServer::new();
+        server.mine_blocks(3);
 
-        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        server.assert_response(
+            format!("/content/sha256/{}", multipart::content_hash(b"hello")),
+            StatusCode::NOT_FOUND,
+            &format!("content {} not found", multipart::content_hash(b"hello")),
+        );
+
+        let wrapped = Inscription::wrapped(
+            "ord-v1",
+            serde_json::json!({}),
+            "text/plain;charset=utf-8",
+            b"hello",
+        )
+        .unwrap();
+
+        server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
             inputs: &[(1, 0, 0)],
-            witness: Inscription::new(Some("image/png".as_bytes().to_vec()), None).to_witness(),
+            witness: wrapped.to_witness(),
             ..Default::default()
         });
 
-        let inscription_id = InscriptionId::from(txid);
+        server.mine_blocks(1);
+
+        let reference = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(2, 0, 0)],
+            witness: wrapped
+                .with_reference(multipart::content_hash(b"hello"))
+                .unwrap()
+                .to_witness(),
+            ..Default::default()
+        });
 
         server.mine_blocks(1);
 
-        server.assert_response(
-            format!("/preview/{inscription_id}"),
-            StatusCode::OK,
-            &fs::read_to_string("templates/preview-unknown.html").unwrap(),
-        );
+        for (path, etag) in [
+            (
+                format!("/content/sha256/{}", multipart::content_hash(b"hello")),
+                format!("W/\"{}\"", multipart::content_hash(b"hello")),
+            ),
+            (
+                format!("/content/{}", InscriptionId::from(reference)),
+                format!("W/\"{}\"", InscriptionId::from(reference)),
+            ),
+        ] {
+            let response = server.get(path);
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers().get(header::CONTENT_TYPE).unwrap(),
+                "text/plain;charset=utf-8"
+            );
+            assert_eq!(response.headers().get(header::ETAG).unwrap(), &etag);
+            assert_eq!(response.text().unwrap(), "hello");
+        }
     }
 
     #[test]
-    fn content_responses_have_cache_control_headers() {
+    fn pub_inscriptions() {
         let server = TestServer::new();
         server.mine_blocks(1);
 
-        let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
-            inputs: &[(1, 0, 0)],
-            witness: inscription("text/foo", "hello").to_witness(),
-            ..Default::default()
-        });
+        let script = bitcoin::blockdata::script::Builder::new()
+            .push_opcode(bitcoin::blockdata::opcodes::OP_FALSE)
+            .push_opcode(bitcoin::blockdata::opcodes::all::OP_IF)
+            .push_slice(b"pub")
+            .push_slice(&[1])
+            .push_slice(b"text/plain;charset=utf-8")
+            .push_slice(&[])
+            .push_slice(b"hello")
+            .push_opcode(bitcoin::blockdata::opcodes::all::OP_ENDIF)
+            .into_script();
+
+        let inscription_id = InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+            TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: Witness::from_vec(vec![script.into_bytes(), Vec::new()]),
+                ..Default::default()
+            },
+        ));
 
         server.mine_blocks(1);
 
-        let response = server.get(format!("/content/{}", InscriptionId::from(txid)));
+        server.assert_response_regex(
+            "/pub",
+            StatusCode::OK,
+            format!(".*<h1>Pub Inscriptions</h1>.*<a href=/inscription/{inscription_id}>.*"),
+        );
+
+        server.assert_response_regex(
+            format!("/inscription/{inscription_id}"),
+            StatusCode::OK,
+            ".*<h1>Pub Inscription 0</h1>.*<dt>protocol</dt>\\s*<dd>pub-v0</dd>.*",
+        );
+
+        server.assert_response_regex("/inscriptions", StatusCode::OK, ".*prev\nnext.*");
+
+        server.assert_response(format!("/content/{inscription_id}"), StatusCode::OK, "hello");
+
+        let json = server.get_json(format!("/api/v1/inscription/{inscription_id}"));
+        assert_eq!(json["number"], 0);
+        assert_eq!(json["tracking"], false);
+        assert_eq!(json["genesis_height"], 2);
 
-        assert_eq!(response.status(), StatusCode::OK);
         assert_eq!(
-            response.headers().get(header::CACHE_CONTROL).unwrap(),
-            "max-age=31536000, immutable"
+            server.get_json("/api/v1/pub")["inscriptions"],
+            serde_json::json!([inscription_id])
         );
     }
 
@@ -2576,4 +4038,341 @@ mod tests {
             &fs::read_to_string("templates/preview-unknown.html").unwrap(),
         );
     }
+
+    #[test]
+    fn mempool_requires_mempool_flag() {
+        TestServer::new().assert_response(
+            "/mempool",
+            StatusCode::NOT_FOUND,
+            "tracking the mempool requires server started with `--mempool` flag",
+        );
+    }
+
+    #[test]
+    fn pending_inscriptions_and_transfers() {
+        let server = TestServer::new();
+        server.mine_blocks(2);
+
+        let inscription_id = InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+            TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+                ..Default::default()
+            },
+        ));
+
+        server.mine_blocks(1);
+
+        let transfer = server
+            .bitcoin_rpc_server
+            .broadcast_tx(TransactionTemplate {
+                inputs: &[(3, 1, 0)],
+                ..Default::default()
+            });
+
+        let pending = InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+            TransactionTemplate {
+                inputs: &[(2, 0, 0)],
+                witness: inscription("text/plain;charset=utf-8", "pending").to_witness(),
+                ..Default::default()
+            },
+        ));
+
+        server.index.update_mempool().unwrap();
+
+        server.assert_response_regex(
+            "/mempool",
+            StatusCode::OK,
+            format!(
+                ".*<h2>Inscriptions</h2>
+<ul class=monospace>
+  <li>{pending} in <a href=/tx/{}>{}</a> to {}:0:0</li>
+</ul>
+<h2>Transfers</h2>
+<ul class=monospace>
+  <li><a href=/inscription/{inscription_id}>{inscription_id}</a> in <a href=/tx/{transfer}>{transfer}</a> to {transfer}:0:0</li>
+</ul>.*",
+                pending.txid, pending.txid, pending.txid,
+            ),
+        );
+
+        let mut headers = HeaderMap::new();
+
+        headers.insert(header::ACCEPT, "application/json".parse().unwrap());
+
+        let response = reqwest::blocking::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap()
+            .get(server.join_url(&format!("/inscription/{inscription_id}")))
+            .send()
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let json = serde_json::from_str::<serde_json::Value>(&response.text().unwrap()).unwrap();
+
+        assert_eq!(json["inscription_id"], inscription_id.to_string());
+        assert_eq!(
+            json["pending"],
+            serde_json::json!({
+                "kind": "transfer",
+                "satpoint": format!("{transfer}:0:0"),
+                "txid": transfer.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn explorer_routes_serve_json_when_accepted() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let block = server.get_json("/block/1");
+        assert_eq!(block["height"], 1);
+        assert_eq!(block["prev"], 0);
+        assert_eq!(block["next"], serde_json::Value::Null);
+
+        let coinbase = block["transactions"][0].as_str().unwrap().to_string();
+
+        let transaction = server.get_json(format!("/tx/{coinbase}"));
+        assert_eq!(transaction["txid"], coinbase);
+        assert_eq!(transaction["outputs"][0]["value"], 50 * COIN_VALUE);
+
+        let output = server.get_json(format!("/output/{coinbase}:0"));
+        assert_eq!(output["value"], 50 * COIN_VALUE);
+        assert_eq!(output["spent"], serde_json::Value::Null);
+
+        assert_eq!(server.get_json("/block-count"), 2);
+
+        server.assert_response_regex("/block/1", StatusCode::OK, ".*<h1>Block 1</h1>.*");
+    }
+
+    #[test]
+    fn inscription_transfers() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let inscribe = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(1, 0, 0)],
+            witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+            ..Default::default()
+        });
+        let inscription_id = InscriptionId::from(inscribe);
+        server.mine_blocks(1);
+
+        let transfer = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+            inputs: &[(2, 1, 0)],
+            ..Default::default()
+        });
+        server.mine_blocks(1);
+
+        server.assert_response_regex(
+            format!("/inscription/{inscription_id}"),
+            StatusCode::OK,
+            format!(
+                ".*<h2>1 Transfer</h2>
+<ol>
+  <li>
+    <a href=/block/3>3</a>
+    <a class=monospace href=/tx/{transfer}>{transfer}</a>
+    <span class=monospace>{inscribe}:0:0</span> → <span class=monospace>{transfer}:0:0</span>
+  </li>
+</ol>.*"
+            ),
+        );
+
+        assert_eq!(
+            server.get_json(format!("/api/v1/inscription/{inscription_id}"))["transfers"],
+            serde_json::json!([{
+                "height": 3,
+                "txid": transfer.to_string(),
+                "old_satpoint": format!("{inscribe}:0:0"),
+                "new_satpoint": format!("{transfer}:0:0"),
+                "fee_spent": false,
+            }])
+        );
+    }
+
+    #[test]
+    fn api_v1_serves_json() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let inscription_id = InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+            TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+                ..Default::default()
+            },
+        ));
+
+        server.mine_blocks(1);
+
+        let response = server.get("/api/v1/sat/0");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+
+        let sat = serde_json::from_str::<serde_json::Value>(&response.text().unwrap()).unwrap();
+        assert_eq!(sat["name"], "nvtdijuwxlp");
+        assert_eq!(sat["prev"], serde_json::Value::Null);
+        assert_eq!(sat["next"], 1);
+
+        let inscriptions = server.get_json("/api/v1/inscriptions");
+        assert_eq!(
+            inscriptions,
+            serde_json::json!({
+                "cursor": null,
+                "inscriptions": [inscription_id.to_string()],
+                "next": null,
+                "prev": null,
+            })
+        );
+
+        assert_eq!(
+            server.get_json(format!("/api/v1/inscription/{inscription_id}"))["inscription_id"],
+            inscription_id.to_string()
+        );
+
+        assert_eq!(server.get_json("/api/v1/block-count"), 3);
+
+        assert_eq!(
+            server.get_json("/api/v1/status"),
+            serde_json::json!({ "reorged": false })
+        );
+
+        let home = server.get_json("/api/v1");
+        assert_eq!(home["inscriptions"], serde_json::json!([inscription_id]));
+        assert_eq!(home["last"], 2);
+
+        assert_eq!(
+            server.get_json("/api/v1/range/1/10"),
+            serde_json::json!({ "end": 10, "start": 1, "value": 9 })
+        );
+
+        assert_eq!(
+            server.get_json("/api/v1/input/0/0/0")["previous_output"],
+            OutPoint::null().to_string()
+        );
+
+        let response = server.get("/api/v1/range/10/1");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&response.text().unwrap()).unwrap(),
+            serde_json::json!({ "error": "range start greater than range end" })
+        );
+
+        let response = server.get("/api/v1/output/foo");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(serde_json::from_str::<serde_json::Value>(&response.text().unwrap()).unwrap()["error"].is_string());
+    }
+
+    #[test]
+    fn inscriptions_can_be_queried() {
+        let server = TestServer::new();
+        server.mine_blocks(2);
+
+        let mut ids = Vec::new();
+        for i in 1..3 {
+            ids.push(InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+                TransactionTemplate {
+                    inputs: &[(i, 0, 0)],
+                    witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+                    ..Default::default()
+                },
+            )));
+            server.mine_blocks(1);
+        }
+
+        let page = server.get_json("/inscriptions?media=text&limit=1");
+        assert_eq!(page["inscriptions"], serde_json::json!([ids[1].to_string()]));
+
+        let cursor = page["cursor"].as_str().unwrap().to_string();
+
+        server.assert_response_regex(
+            "/inscriptions?media=text&limit=1",
+            StatusCode::OK,
+            format!(
+                ".*<a class=next href=/inscriptions\\?media=text&amp;limit=1&amp;cursor={cursor}>more</a>.*"
+            ),
+        );
+
+        let page = server.get_json(format!("/api/v1/inscriptions?media=text&limit=1&cursor={cursor}"));
+        assert_eq!(page["inscriptions"], serde_json::json!([ids[0].to_string()]));
+        assert_eq!(page["cursor"], serde_json::Value::Null);
+
+        server.assert_response(
+            format!("/inscriptions?order=fee&cursor={cursor}"),
+            StatusCode::BAD_REQUEST,
+            "cursor is for inscriptions ordered by number",
+        );
+
+        server.assert_response_regex(
+            "/inscriptions?cursor=foo",
+            StatusCode::BAD_REQUEST,
+            ".*invalid cursor: foo.*",
+        );
+    }
+
+    #[test]
+    fn events_are_not_compressed() {
+        let server = TestServer::new();
+
+        let response = reqwest::blocking::Client::new()
+            .get(server.join_url("/events"))
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .send()
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::CONTENT_ENCODING), None);
+    }
+
+    #[test]
+    fn events_can_be_resumed_from_a_height() {
+        let server = TestServer::new();
+        server.mine_blocks(1);
+
+        let inscription_id = InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+            TransactionTemplate {
+                inputs: &[(1, 0, 0)],
+                witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+                ..Default::default()
+            },
+        ));
+
+        server.mine_blocks(1);
+
+        let response = reqwest::blocking::get(server.join_url("/events?from=0")).unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        let lines = io::BufRead::lines(io::BufReader::new(response))
+            .take(3)
+            .collect::<Result<Vec<String>, io::Error>>()
+            .unwrap();
+
+        pretty_assert_eq!(
+            lines,
+            [
+                "event:inscribed".to_string(),
+                "id:2".to_string(),
+                format!(
+                    r#"data:{{"type":"inscribed","height":2,"inscription_id":"{inscription_id}","satpoint":"{}:0:0"}}"#,
+                    inscription_id.txid
+                ),
+            ]
+        );
+    }
 }
@@ -0,0 +1,411 @@
+use {
+    super::*,
+    http_body::Limited,
+    hyper::{
+        client::HttpConnector,
+        Client,
+    },
+};
+
+/// The largest payload fetched from a mirror.
+const MAX_CONTENT_SIZE: usize = 50_000_000;
+
+/// How long an HTTP mirror has to send a payload.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A mirror of off-chain content, holding each payload in a file named after
+/// its hex encoded sha256 content hash.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Mirror {
+    Directory(PathBuf),
+    Http(String),
+}
+
+impl FromStr for Mirror {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("https://") {
+            bail!("HTTPS mirrors are not supported, use an HTTP mirror or a mirror directory");
+        }
+
+        if s.starts_with("http://") {
+            let url = s.trim_end_matches('/').to_string();
+            hyper::Uri::try_from(&url).with_context(|| format!("invalid mirror url `{s}`"))?;
+            return Ok(Self::Http(url));
+        }
+
+        Ok(Self::Directory(s.into()))
+    }
+}
+
+/// The state of the content of an off-chain inscription.
+#[derive(Debug, PartialEq)]
+pub(crate) enum OffchainContent {
+    /// Content matching the inscription's content hash.
+    Verified(Vec<u8>),
+    /// Content was found, but none of it matched the inscription's content
+    /// hash.
+    Unverified,
+    /// No content was found in the cache or on any mirror.
+    Unavailable,
+}
+
+/// Fetches off-chain content from mirrors, verifies it against the content
+/// hash inscribed on chain, and caches it.
+pub(crate) struct OffchainResolver {
+    cache_dir: PathBuf,
+    client: Client<HttpConnector>,
+    mirrors: Vec<Mirror>,
+    /// Hashes of cached content that was verified by this resolver, which
+    /// isn't hashed again when it's read from the cache.
+    verified: Mutex<HashSet<String>>,
+}
+
+impl OffchainResolver {
+    pub(crate) fn new(
+        cache_dir: PathBuf,
+        mirrors: Vec<Mirror>,
+    ) -> Self {
+        Self {
+            cache_dir,
+            client: Client::new(),
+            mirrors,
+            verified: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// The content with `content_hash`, from the cache if it was fetched
+    /// before, and from the mirrors, in order, otherwise.
+    pub(crate) async fn resolve(
+        &self,
+        content_hash: &str,
+    ) -> Result<OffchainContent> {
+        let content_hash = content_hash.to_lowercase();
+
+        // Content hashes are used as file names, so anything else is rejected
+        if content_hash.len() != 64 || !content_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(OffchainContent::Unavailable);
+        }
+
+        let cache_path = self.cache_dir.join(&content_hash);
+
+        if let Ok(content) = tokio::fs::read(&cache_path).await {
+            if self.verified.lock().unwrap().contains(&content_hash)
+                || multipart::content_hash(&content) == content_hash
+            {
+                self.verified.lock().unwrap().insert(content_hash);
+                return Ok(OffchainContent::Verified(content));
+            }
+        }
+
+        let mut found = false;
+
+        for mirror in &self.mirrors {
+            let content = match self.fetch(mirror, &content_hash).await {
+                Ok(Some(content)) => content,
+                Ok(None) => continue,
+                Err(err) => {
+                    log::warn!("failed to fetch off-chain content {content_hash}: {err}");
+                    continue;
+                },
+            };
+
+            if multipart::content_hash(&content) != content_hash {
+                log::warn!(
+                    "off-chain content {content_hash} from {mirror:?} does not match its hash"
+                );
+                found = true;
+                continue;
+            }
+
+            tokio::fs::create_dir_all(&self.cache_dir).await?;
+            tokio::fs::write(&cache_path, &content)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to cache off-chain content in {}",
+                        cache_path.display()
+                    )
+                })?;
+
+            self.verified.lock().unwrap().insert(content_hash);
+
+            return Ok(OffchainContent::Verified(content));
+        }
+
+        Ok(if found {
+            OffchainContent::Unverified
+        } else {
+            OffchainContent::Unavailable
+        })
+    }
+
+    async fn fetch(
+        &self,
+        mirror: &Mirror,
+        content_hash: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        match mirror {
+            Mirror::Directory(dir) => {
+                let path = dir.join(content_hash);
+
+                let len = match tokio::fs::metadata(&path).await {
+                    Ok(metadata) => metadata.len(),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                    Err(err) => return Err(err.into()),
+                };
+
+                if len > u64::try_from(MAX_CONTENT_SIZE).unwrap() {
+                    bail!(
+                        "mirror {} has more than {MAX_CONTENT_SIZE} bytes",
+                        path.display()
+                    );
+                }
+
+                Ok(Some(tokio::fs::read(&path).await?))
+            },
+            Mirror::Http(url) => {
+                tokio::time::timeout(FETCH_TIMEOUT, self.fetch_http(url, content_hash))
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "mirror {url} took longer than {} seconds",
+                            FETCH_TIMEOUT.as_secs()
+                        )
+                    })?
+            },
+        }
+    }
+
+    async fn fetch_http(
+        &self,
+        url: &str,
+        content_hash: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .client
+            .get(format!("{url}/{content_hash}").parse()?)
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            bail!("mirror {url} responded with {}", response.status());
+        }
+
+        let too_large = || anyhow!("mirror {url} sent more than {MAX_CONTENT_SIZE} bytes");
+
+        let content_length = response
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok())
+            .and_then(|length| length.parse::<usize>().ok());
+
+        if content_length.map_or(false, |length| length > MAX_CONTENT_SIZE) {
+            return Err(too_large());
+        }
+
+        let content = hyper::body::to_bytes(Limited::new(response.into_body(), MAX_CONTENT_SIZE))
+            .await
+            .map_err(|err| {
+                if err.is::<http_body::LengthLimitError>() {
+                    too_large()
+                } else {
+                    anyhow!(err)
+                }
+            })?;
+
+        Ok(Some(content.to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOO_HASH: &str = "9520437ce8902eb379a7d8aaa98fc4c94eeb07b6684854868fa6f72bf34b0fd3";
+
+    fn resolve(
+        resolver: &OffchainResolver,
+        content_hash: &str,
+    ) -> OffchainContent {
+        Runtime::new()
+            .unwrap()
+            .block_on(resolver.resolve(content_hash))
+            .unwrap()
+    }
+
+    #[test]
+    fn mirrors_are_parsed() {
+        assert_eq!(
+            "http://example.com/content/".parse::<Mirror>().unwrap(),
+            Mirror::Http("http://example.com/content".into())
+        );
+        assert_eq!(
+            "mirror".parse::<Mirror>().unwrap(),
+            Mirror::Directory("mirror".into())
+        );
+        assert_eq!(
+            "https://example.com"
+                .parse::<Mirror>()
+                .unwrap_err()
+                .to_string(),
+            "HTTPS mirrors are not supported, use an HTTP mirror or a mirror directory"
+        );
+    }
+
+    #[test]
+    fn content_is_verified_and_cached() {
+        let tempdir = TempDir::new().unwrap();
+        let mirror = tempdir.path().join("mirror");
+        fs::create_dir(&mirror).unwrap();
+        fs::write(mirror.join(FOO_HASH), "FOO").unwrap();
+
+        let resolver = OffchainResolver::new(
+            tempdir.path().join("cache"),
+            vec![Mirror::Directory(mirror.clone())],
+        );
+
+        assert_eq!(
+            resolve(&resolver, FOO_HASH),
+            OffchainContent::Verified(b"FOO".to_vec())
+        );
+
+        assert!(resolver.verified.lock().unwrap().contains(FOO_HASH));
+
+        fs::remove_file(mirror.join(FOO_HASH)).unwrap();
+
+        assert_eq!(
+            resolve(&resolver, &FOO_HASH.to_uppercase()),
+            OffchainContent::Verified(b"FOO".to_vec())
+        );
+    }
+
+    #[test]
+    fn content_not_matching_its_hash_is_unverified() {
+        let tempdir = TempDir::new().unwrap();
+        fs::write(tempdir.path().join(FOO_HASH), "BAR").unwrap();
+
+        let resolver = OffchainResolver::new(
+            tempdir.path().join("cache"),
+            vec![Mirror::Directory(tempdir.path().into())],
+        );
+
+        assert_eq!(resolve(&resolver, FOO_HASH), OffchainContent::Unverified);
+        assert!(!tempdir.path().join("cache").exists());
+    }
+
+    #[test]
+    fn later_mirrors_are_tried_in_order() {
+        let tempdir = TempDir::new().unwrap();
+        let bad = tempdir.path().join("bad");
+        let good = tempdir.path().join("good");
+        fs::create_dir(&bad).unwrap();
+        fs::create_dir(&good).unwrap();
+        fs::write(bad.join(FOO_HASH), "BAR").unwrap();
+        fs::write(good.join(FOO_HASH), "FOO").unwrap();
+
+        let resolver = OffchainResolver::new(
+            tempdir.path().join("cache"),
+            vec![
+                Mirror::Directory(tempdir.path().join("missing")),
+                Mirror::Directory(bad),
+                Mirror::Directory(good),
+            ],
+        );
+
+        assert_eq!(
+            resolve(&resolver, FOO_HASH),
+            OffchainContent::Verified(b"FOO".to_vec())
+        );
+    }
+
+    #[test]
+    fn content_larger_than_the_limit_is_not_fetched() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = io::Read::read(&mut stream, &mut request).unwrap();
+            io::Write::write_all(
+                &mut stream,
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n",
+                    MAX_CONTENT_SIZE + 1
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        });
+
+        let tempdir = TempDir::new().unwrap();
+
+        let resolver = OffchainResolver::new(
+            tempdir.path().join("cache"),
+            vec![format!("http://127.0.0.1:{port}").parse().unwrap()],
+        );
+
+        let err = Runtime::new()
+            .unwrap()
+            .block_on(resolver.fetch(&resolver.mirrors[0], FOO_HASH))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("mirror http://127.0.0.1:{port} sent more than {MAX_CONTENT_SIZE} bytes")
+        );
+
+        assert_eq!(resolve(&resolver, FOO_HASH), OffchainContent::Unavailable);
+    }
+
+    #[test]
+    fn files_larger_than_the_limit_are_not_read() {
+        let tempdir = TempDir::new().unwrap();
+
+        fs::File::create(tempdir.path().join(FOO_HASH))
+            .unwrap()
+            .set_len(u64::try_from(MAX_CONTENT_SIZE).unwrap() + 1)
+            .unwrap();
+
+        let resolver = OffchainResolver::new(
+            tempdir.path().join("cache"),
+            vec![Mirror::Directory(tempdir.path().into())],
+        );
+
+        let err = Runtime::new()
+            .unwrap()
+            .block_on(resolver.fetch(&resolver.mirrors[0], FOO_HASH))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "mirror {} has more than {MAX_CONTENT_SIZE} bytes",
+                tempdir.path().join(FOO_HASH).display()
+            )
+        );
+
+        assert_eq!(resolve(&resolver, FOO_HASH), OffchainContent::Unavailable);
+    }
+
+    #[test]
+    fn missing_content_is_unavailable() {
+        let tempdir = TempDir::new().unwrap();
+
+        let resolver = OffchainResolver::new(
+            tempdir.path().join("cache"),
+            vec![Mirror::Directory(tempdir.path().into())],
+        );
+
+        assert_eq!(resolve(&resolver, FOO_HASH), OffchainContent::Unavailable);
+        assert_eq!(
+            resolve(&resolver, "../cookie"),
+            OffchainContent::Unavailable
+        );
+    }
+}
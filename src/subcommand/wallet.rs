@@ -32,6 +32,9 @@ use {
 };
 
 pub mod balance;
+pub mod bfs;
+pub mod bid;
+pub mod bnw;
 pub mod cardinals;
 pub mod create;
 pub(crate) mod inscribe;
@@ -45,9 +48,16 @@ pub(crate) mod transaction_builder;
 pub mod transactions;
 
 #[derive(Debug, Parser)]
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum Wallet {
     #[clap(about = "Get wallet balance")]
     Balance,
+    #[clap(subcommand, about = "Manage `bfs` file trees")]
+    Bfs(bfs::Bfs),
+    #[clap(subcommand, about = "Manage `bid` usernames")]
+    Bid(bid::Bid),
+    #[clap(about = "Build a `bnw` wall of inscriptions")]
+    Bnw(bnw::Bnw),
     #[clap(about = "Create new wallet")]
     Create(create::Create),
     #[clap(about = "Create inscription")]
@@ -77,6 +87,9 @@ impl Wallet {
     ) -> Result {
         match self {
             Self::Balance => balance::run(options),
+            Self::Bfs(bfs) => bfs.run(options),
+            Self::Bid(bid) => bid.run(options),
+            Self::Bnw(bnw) => bnw.run(options),
             Self::Create(create) => create.run(options),
             Self::Inscribe(inscribe) => inscribe.run(options),
             Self::Inscriptions => inscriptions::run(options),
@@ -0,0 +1,184 @@
+use {
+    super::*,
+    crate::bfs::{
+        list_directory,
+        Action,
+    },
+    inscribe::Inscribe,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) enum Bfs {
+    #[clap(about = "Write a file to the file tree of a username")]
+    Put(Put),
+    #[clap(about = "Remove a file or directory from the file tree of a username")]
+    Rm(Rm),
+    #[clap(about = "List a directory in the file tree of a username")]
+    Ls(Ls),
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Put {
+    #[clap(help = "Write to the file tree of <BID>.")]
+    bid: Username,
+    #[clap(help = "Write to <PATH>, like `/docs/readme.txt`.")]
+    path: BfsPath,
+    #[clap(help = "Write the contents of <FILE>.")]
+    file: PathBuf,
+    #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+    fee_rate: FeeRate,
+    #[clap(long, help = "Do not back up recovery key.")]
+    no_backup: bool,
+    #[clap(long, help = "Don't sign or broadcast transactions.")]
+    dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Rm {
+    #[clap(help = "Remove from the file tree of <BID>.")]
+    bid: Username,
+    #[clap(help = "Remove the file or directory at <PATH>.")]
+    path: BfsPath,
+    #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+    fee_rate: FeeRate,
+    #[clap(long, help = "Do not back up recovery key.")]
+    no_backup: bool,
+    #[clap(long, help = "Don't sign or broadcast transactions.")]
+    dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Ls {
+    #[clap(help = "List the file tree of <BID>.")]
+    bid: Username,
+    #[clap(help = "List the directory at <PATH>.", default_value = "/")]
+    path: BfsPath,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub inscription: Option<InscriptionId>,
+}
+
+impl Bfs {
+    pub(crate) fn run(
+        self,
+        options: Options,
+    ) -> Result {
+        let index = Index::open(&options)?;
+        index.update()?;
+
+        match self {
+            Self::Put(put) => {
+                let content_type = Media::content_type_for_path(&put.file)?;
+
+                let body = fs::read(&put.file)
+                    .with_context(|| format!("io error reading {}", put.file.display()))?;
+
+                write(
+                    &options,
+                    &index,
+                    &put.bid,
+                    &put.path,
+                    Action::Put,
+                    content_type,
+                    &body,
+                    put.fee_rate,
+                    put.no_backup,
+                    put.dry_run,
+                )
+            },
+            Self::Rm(rm) => {
+                if index.get_bfs_file(&rm.bid, &rm.path)?.is_none()
+                    && index.get_bfs_files(&rm.bid, &rm.path)?.is_empty()
+                {
+                    bail!("bfs path `{}` of bid `{}` does not exist", rm.path, rm.bid);
+                }
+
+                write(
+                    &options,
+                    &index,
+                    &rm.bid,
+                    &rm.path,
+                    Action::Rm,
+                    "text/plain;charset=utf-8",
+                    &[],
+                    rm.fee_rate,
+                    rm.no_backup,
+                    rm.dry_run,
+                )
+            },
+            Self::Ls(ls) => {
+                let files = index.get_bfs_files(&ls.bid, &ls.path)?;
+
+                if files.is_empty() && !ls.path.is_root() {
+                    if index.get_bfs_file(&ls.bid, &ls.path)?.is_some() {
+                        bail!("bfs path `{}` of bid `{}` is a file", ls.path, ls.bid);
+                    }
+                    bail!("bfs path `{}` of bid `{}` does not exist", ls.path, ls.bid);
+                }
+
+                print_json(
+                    list_directory(&ls.path, &files)
+                        .into_iter()
+                        .map(|entry| Entry {
+                            name: entry.name,
+                            inscription: entry.inscription_id,
+                        })
+                        .collect::<Vec<Entry>>(),
+                )?;
+
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Inscribe a `bfs` inscription applying `action` to `path`, spending the
+/// registration inscription of `bid` to prove ownership of it.
+fn write(
+    options: &Options,
+    index: &Index,
+    bid: &Username,
+    path: &BfsPath,
+    action: Action,
+    content_type: &str,
+    body: &[u8],
+    fee_rate: FeeRate,
+    no_backup: bool,
+    dry_run: bool,
+) -> Result {
+    if path.is_root() {
+        bail!("bfs inscription may not {action} the root directory");
+    }
+
+    let (registration, _) = bid::owned_registration(options, index, bid)?;
+
+    let inscription = Inscription::wrapped(
+        "bfs-v1",
+        serde_json::json!({
+            "bid": bid,
+            "path": path.as_str(),
+            "action": action.to_string(),
+        }),
+        content_type,
+        body,
+    )?;
+
+    print_json(Inscribe::inscribe(
+        options,
+        index,
+        inscription,
+        Some(registration),
+        None,
+        None,
+        fee_rate,
+        None,
+        false,
+        dry_run,
+        no_backup,
+    )?)?;
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use {
+    super::*,
+    crate::wallet::Wallet,
+    inscribe::Inscribe,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) enum Bid {
+    #[clap(about = "Register a username")]
+    Register(Register),
+    #[clap(about = "Renew a username registration")]
+    Renew(Renew),
+    #[clap(about = "Transfer a username")]
+    Transfer(Transfer),
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Register {
+    #[clap(help = "Register <NAME>.")]
+    name: Username,
+    #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+    fee_rate: FeeRate,
+    #[clap(long, help = "Send the registration inscription to <DESTINATION>.")]
+    destination: Option<Address>,
+    #[clap(long, help = "Do not back up recovery key.")]
+    no_backup: bool,
+    #[clap(long, help = "Don't sign or broadcast transactions.")]
+    dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Renew {
+    #[clap(help = "Renew <NAME>.")]
+    name: Username,
+    #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+    fee_rate: FeeRate,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Transfer {
+    #[clap(help = "Transfer <NAME>.")]
+    name: Username,
+    #[clap(help = "Send the registration inscription to <ADDRESS>.")]
+    address: Address,
+    #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+    fee_rate: FeeRate,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+    pub transaction: Txid,
+}
+
+impl Bid {
+    pub(crate) fn run(
+        self,
+        options: Options,
+    ) -> Result {
+        match self {
+            Self::Register(register) => register.run(options),
+            Self::Renew(renew) => {
+                let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+                let address = get_change_address(&client)?;
+                send_registration(&options, &renew.name, address, renew.fee_rate)
+            },
+            Self::Transfer(transfer) => {
+                if !transfer
+                    .address
+                    .is_valid_for_network(options.chain().network())
+                {
+                    bail!(
+                        "Address `{}` is not valid for {}",
+                        transfer.address,
+                        options.chain()
+                    );
+                }
+
+                send_registration(
+                    &options,
+                    &transfer.name,
+                    transfer.address,
+                    transfer.fee_rate,
+                )
+            },
+        }
+    }
+}
+
+impl Register {
+    fn run(
+        self,
+        options: Options,
+    ) -> Result {
+        let index = Index::open(&options)?;
+        index.update()?;
+
+        let height = index.block_count()?;
+
+        if let Some((_, entry)) = index.get_bid_registration(&self.name)? {
+            if entry.is_active(height) {
+                bail!("bid `{}` is already registered", self.name);
+            }
+        }
+
+        if self.name.is_reserved(options.chain(), height) {
+            bail!(
+                "bid `{}` is reserved until height {}",
+                self.name,
+                options.chain().bid_sunrise_end_height()
+            );
+        }
+
+        let inscription = Inscription::wrapped(
+            "bid-v1",
+            serde_json::json!({ "name": self.name }),
+            "text/plain;charset=utf-8",
+            self.name.as_str().as_bytes(),
+        )?;
+
+        print_json(Inscribe::inscribe(
+            &options,
+            &index,
+            inscription,
+            None,
+            None,
+            self.destination,
+            self.fee_rate,
+            None,
+            false,
+            self.dry_run,
+            self.no_backup,
+        )?)?;
+
+        Ok(())
+    }
+}
+
+/// The inscription holding the active registration of `name` and its
+/// location, as long as it's held by this wallet.
+pub(crate) fn owned_registration(
+    options: &Options,
+    index: &Index,
+    name: &Username,
+) -> Result<(InscriptionId, SatPoint)> {
+    let (inscription_id, entry) = index
+        .get_bid_registration(name)?
+        .ok_or_else(|| anyhow!("bid `{name}` is not registered"))?;
+
+    if !entry.is_active(index.block_count()?) {
+        bail!("bid `{name}` has expired and must be registered again");
+    }
+
+    let satpoint = index
+        .get_inscription_satpoint_by_id(inscription_id)?
+        .ok_or_else(|| anyhow!("Inscription {inscription_id} not found"))?;
+
+    if !index
+        .get_unspent_outputs(Wallet::load(options)?)?
+        .contains_key(&satpoint.outpoint)
+    {
+        bail!("bid `{name}` is not owned by this wallet");
+    }
+
+    Ok((inscription_id, satpoint))
+}
+
+/// Send the inscription holding the registration of `name` to `address`.
+/// Moving the inscription renews the registration.
+fn send_registration(
+    options: &Options,
+    name: &Username,
+    address: Address,
+    fee_rate: FeeRate,
+) -> Result {
+    let index = Index::open(options)?;
+    index.update()?;
+
+    let (_, satpoint) = owned_registration(options, &index, name)?;
+
+    let unspent_outputs = index.get_unspent_outputs(Wallet::load(options)?)?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let change = [get_change_address(&client)?, get_change_address(&client)?];
+
+    let unsigned_transaction = TransactionBuilder::build_transaction_with_postage(
+        satpoint,
+        index.get_inscriptions(None)?,
+        unspent_outputs,
+        address,
+        change,
+        fee_rate,
+    )?;
+
+    let signed_tx = client
+        .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+        .hex;
+
+    let transaction = client.send_raw_transaction(&signed_tx)?;
+
+    print_json(Output { transaction })?;
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use {
+    super::*,
+    crate::{
+        bnw::Wall,
+        wallet::Wallet,
+    },
+    inscribe::Inscribe,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Bnw {
+    #[clap(help = "Build a wall for <BID>.")]
+    bid: Username,
+    #[clap(help = "Name the wall <WALL>, replacing any earlier wall of that name.")]
+    wall: WallName,
+    #[clap(help = "Showcase <INSCRIPTIONS>, in order.")]
+    inscriptions: Vec<InscriptionId>,
+    #[clap(long, help = "Describe the wall with <DESCRIPTION>.")]
+    description: Option<String>,
+    #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+    fee_rate: FeeRate,
+    #[clap(long, help = "Do not back up recovery key.")]
+    no_backup: bool,
+    #[clap(long, help = "Don't sign or broadcast transactions.")]
+    dry_run: bool,
+}
+
+impl Bnw {
+    pub(crate) fn run(
+        self,
+        options: Options,
+    ) -> Result {
+        if self.inscriptions.len() > Wall::MAX_INSCRIPTIONS {
+            bail!(
+                "bnw wall lists more than {} inscriptions",
+                Wall::MAX_INSCRIPTIONS
+            );
+        }
+
+        let index = Index::open(&options)?;
+        index.update()?;
+
+        let (registration, _) = bid::owned_registration(&options, &index, &self.bid)?;
+
+        let unspent_outputs = index.get_unspent_outputs(Wallet::load(&options)?)?;
+
+        for inscription_id in &self.inscriptions {
+            let satpoint = index
+                .get_inscription_satpoint_by_id(*inscription_id)?
+                .ok_or_else(|| anyhow!("Inscription {inscription_id} not found"))?;
+
+            if !unspent_outputs.contains_key(&satpoint.outpoint) {
+                bail!("inscription {inscription_id} is not owned by this wallet");
+            }
+        }
+
+        let inscription = Inscription::wrapped(
+            "bnw-v1",
+            serde_json::json!({
+                "bid": self.bid,
+                "wall": self.wall.as_str(),
+                "inscriptions": self.inscriptions,
+            }),
+            "text/plain;charset=utf-8",
+            self.description.unwrap_or_default().as_bytes(),
+        )?;
+
+        print_json(Inscribe::inscribe(
+            &options,
+            &index,
+            inscription,
+            Some(registration),
+            None,
+            None,
+            self.fee_rate,
+            None,
+            false,
+            self.dry_run,
+            self.no_backup,
+        )?)?;
+
+        Ok(())
+    }
+}
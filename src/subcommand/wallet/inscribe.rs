@@ -1,6 +1,10 @@
 use {
     super::*,
-    crate::wallet::Wallet,
+    crate::{
+        compression::Compression,
+        multipart,
+        wallet::Wallet,
+    },
     bitcoin::{
         blockdata::{
             opcodes,
@@ -49,11 +53,70 @@ use {
 };
 
 #[derive(Serialize)]
-struct Output {
+pub(crate) struct Output {
     commit: Txid,
     inscription: InscriptionId,
     reveal: Txid,
     fees: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parts: Vec<Output>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    inscriptions: Vec<InscriptionId>,
+}
+
+/// A batch of inscriptions revealed by a single transaction, in the order of
+/// their inscription ids, read from a YAML file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Batchfile {
+    pub(crate) inscriptions: Vec<BatchEntry>,
+}
+
+/// An inscription in a batch. Paths are relative to the batch file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct BatchEntry {
+    pub(crate) file: PathBuf,
+    #[serde(default)]
+    pub(crate) title: Option<String>,
+    #[serde(default)]
+    pub(crate) subtitle: Option<String>,
+    #[serde(default)]
+    pub(crate) destination: Option<DeserializeFromStr<Address>>,
+    #[serde(default)]
+    pub(crate) metadata_file: Option<PathBuf>,
+    #[serde(default)]
+    pub(crate) properties_file: Option<PathBuf>,
+    #[serde(default)]
+    pub(crate) license: Option<String>,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+}
+
+impl Batchfile {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let batchfile: Self = serde_yaml::from_reader(
+            File::open(path).with_context(|| format!("io error reading {}", path.display()))?,
+        )
+        .with_context(|| format!("failed to parse batch file {}", path.display()))?;
+
+        if batchfile.inscriptions.is_empty() {
+            bail!("Batch file {} contains no inscriptions!", path.display());
+        }
+
+        Ok(batchfile)
+    }
+}
+
+/// An inscription in the wallet that the reveal transaction spends and returns
+/// to where it was, proving that the new inscription was made by its owner.
+/// The reveal fee is paid by a cardinal utxo spent after it, so that the sats
+/// of both inscriptions keep their offsets.
+pub(crate) struct Parent {
+    satpoint: SatPoint,
+    output: TxOut,
+    fee_outpoint: OutPoint,
+    fee_output: TxOut,
 }
 
 #[derive(Debug, Parser)]
@@ -68,8 +131,22 @@ pub(crate) struct Inscribe {
     unset."
     )]
     pub(crate) commit_fee_rate: Option<FeeRate>,
-    #[clap(help = "Inscribe sat with contents of <FILE>")]
-    pub(crate) file: PathBuf,
+    #[clap(
+        required_unless_present = "batch",
+        help = "Inscribe sat with contents of <FILE>"
+    )]
+    pub(crate) file: Option<PathBuf>,
+    #[clap(
+        long,
+        conflicts_with_all = &[
+            "file", "title", "subtitle", "off-chain", "metadata-file", "properties-file", "license",
+            "description", "multipart", "dedup",
+        ],
+        help = "Reveal every inscription listed in YAML file <BATCH> in a single transaction, each \
+    on the first sat of its own output. Entries have a `file`, and optionally a `title`, \
+    `subtitle`, `destination`, `metadata_file`, `properties_file`, `license` and `description`."
+    )]
+    pub(crate) batch: Option<PathBuf>,
     #[clap(long, help = "A title for the inscription.")]
     pub(crate) title: Option<String>,
     #[clap(long, help = "A subtitle for the inscription.")]
@@ -90,16 +167,44 @@ pub(crate) struct Inscribe {
     pub(crate) destination: Option<Address>,
     #[clap(
         long,
-        help = "Enable compression, which will reduce inscription size and transaction fees. \
+        conflicts_with = "multipart",
+        help = "Declare <PARENT> as the parent of the inscription. The reveal transaction spends \
+    <PARENT> and returns it to where it was, proving that the inscription was made by its owner."
+    )]
+    pub(crate) parent: Option<InscriptionId>,
+    #[clap(
+        long,
+        arg_enum,
+        require_equals = true,
+        min_values = 0,
+        default_missing_value = "brotli",
+        value_name = "CODEC",
+        help = "Enable compression with <CODEC>, which will reduce inscription size and \
+    transaction fees. Defaults to brotli, and `auto` uses whichever codec compresses best. \
     Compression will only take place if it reduces file size. If compressed, the inscription \
     content won't fully display on outdated explorers."
     )]
-    pub(crate) compression: bool,
+    pub(crate) compression: Option<Compression>,
+    #[clap(
+        long,
+        help = "Compress with zstd using the content of <COMPRESSION_DICTIONARY> as a dictionary. \
+    Dictionaries make compressing small files that resemble each other worthwhile, and may be \
+    trained with `zstd --train`."
+    )]
+    pub(crate) compression_dictionary: Option<InscriptionId>,
+    #[clap(
+        long,
+        help = "Wrap the inscription in a binary envelope, which carries its content as raw bytes \
+    instead of base64 encoded JSON, making it about a third smaller. Inscriptions in binary \
+    envelopes are only understood by clients supporting them."
+    )]
+    pub(crate) binary_envelope: bool,
     #[clap(
         long,
         help = "Create a torrent for <FILE> and inscribe its infohash/magnet. You must use a torrent \
     client to seed the off-chain file in addition to making the inscription. As an off-chain \
-    inscription, the file content will not be stored in the Bitcoin blockchain."
+    inscription, the file content will not be stored in the Bitcoin blockchain. If <FILE> is a \
+    directory, a multi-file torrent is created and the hash of each file is inscribed."
     )]
     pub(crate) off_chain: bool,
     #[clap(
@@ -109,16 +214,33 @@ pub(crate) struct Inscribe {
     pub(crate) torrent_path: Option<PathBuf>,
     #[clap(
         long,
-        help = "Torrent tracker URL to include in .torrent file.",
-        default_value = off_chain::DEFAULT_TRACKER,
+        help = "Torrent tracker URL to include in .torrent file and magnet link. May be repeated, \
+    and may be a udp, http(s) or ws(s) URL.",
+        default_values = &[off_chain::DEFAULT_TRACKER, off_chain::DEFAULT_WSS_TRACKER],
     )]
-    pub(crate) torrent_tracker: String,
+    pub(crate) torrent_tracker: Vec<String>,
     #[clap(
         long,
-        help = "Torrent peers to include in .torrent file (space-separated <host>:<port>).",
+        alias = "torrent-peers",
+        help = "Torrent peer <host>:<port> to include in .torrent file and magnet link. May be \
+    repeated.",
         default_value = off_chain::DEFAULT_PEER,
     )]
-    pub(crate) torrent_peers: String,
+    pub(crate) torrent_peer: Vec<String>,
+    #[clap(
+        long,
+        help = "BEP 19 web seed URL serving <FILE> to include in .torrent file and magnet link. May \
+    be repeated."
+    )]
+    pub(crate) torrent_web_seed: Vec<String>,
+    #[clap(
+        long,
+        arg_enum,
+        default_value = "v1",
+        help = "Create a <TORRENT_VERSION> torrent. v2 and hybrid torrents have a SHA-256 based \
+    infohash."
+    )]
+    pub(crate) torrent_version: TorrentVersion,
     #[clap(
         long,
         help = "Inscribe sat with the contents of <FILE>.json as metadata."
@@ -139,6 +261,28 @@ pub(crate) struct Inscribe {
     pub(crate) protocol_id: String,
     #[clap(long, help = "A description for the inscription content.")]
     pub(crate) description: Option<String>,
+    #[clap(
+        long,
+        conflicts_with_all = &["off-chain", "satpoint"],
+        help = "Split the content of <FILE> into parts, each inscribed by its own reveal \
+    transaction, followed by a manifest inscription listing the parts in order. The indexer \
+    reassembles the content from the parts. Requires a protocol other than `ord-v0`."
+    )]
+    pub(crate) multipart: bool,
+    #[clap(
+        long,
+        requires = "multipart",
+        help = "Split content into parts of at most <PART_SIZE> bytes. Defaults to the largest part \
+    size allowed for the chain."
+    )]
+    pub(crate) part_size: Option<usize>,
+    #[clap(
+        long,
+        conflicts_with_all = &["off-chain", "multipart"],
+        help = "If the content of <FILE> is already inscribed, inscribe a reference to it by hash \
+    instead of the content itself. Requires a protocol other than `ord-v0`."
+    )]
+    pub(crate) dedup: bool,
 }
 
 impl Inscribe {
@@ -146,16 +290,75 @@ impl Inscribe {
         self,
         options: Options,
     ) -> Result {
-        let inscription = Inscription::from_file(
-            options.chain(),
-            &self.file,
+        if let Some(batch) = self.batch.clone() {
+            return self.run_batch(options, &batch);
+        }
+
+        let file = self.file.clone().unwrap();
+
+        let chain = options.chain();
+
+        let part_size = self.part_size.unwrap_or_else(|| {
+            chain
+                .inscription_content_size_limit()
+                .unwrap_or(multipart::DEFAULT_PART_SIZE)
+        });
+
+        if self.multipart {
+            if part_size == 0 {
+                bail!("Part size must be greater than zero!");
+            }
+
+            if let Some(limit) = chain.inscription_content_size_limit() {
+                if part_size > limit {
+                    bail!(
+                        "Part size of {part_size} bytes exceeds {limit} byte limit for {chain} \
+                    inscriptions!"
+                    );
+                }
+            }
+        }
+
+        if self.dedup && self.protocol_id == "ord-v0" {
+            bail!(
+                "Deduplicated inscriptions must use a protocol other than `ord-v0`, like `ord-v1`!"
+            );
+        }
+
+        // Options are checked before opening the index, unless a compression
+        // dictionary needs to be read from it first.
+        let mut index = None;
+
+        let compression_dictionary = match self.compression_dictionary {
+            Some(inscription_id) => {
+                let index = index.insert(Index::open(&options)?);
+                index.update()?;
+
+                Some((
+                    inscription_id,
+                    Self::get_compression_dictionary(index, inscription_id)?,
+                ))
+            },
+            None => None,
+        };
+
+        let mut inscription = Inscription::from_file(
+            chain,
+            &file,
             self.title,
             self.subtitle,
             self.compression,
+            compression_dictionary,
+            self.binary_envelope,
             self.off_chain,
-            self.torrent_path,
-            &self.torrent_tracker,
-            &self.torrent_peers,
+            self.multipart,
+            &TorrentOptions {
+                path: self.torrent_path,
+                trackers: self.torrent_tracker,
+                peers: self.torrent_peer,
+                web_seeds: self.torrent_web_seed,
+                version: self.torrent_version,
+            },
             self.metadata_file,
             self.properties_file,
             self.license,
@@ -163,34 +366,268 @@ impl Inscribe {
             self.description,
         )?;
 
+        // Off-chain content was already hashed while its torrent was created
+        let content_hash = match inscription.offchain_content() {
+            Some((_content_type, content_hash)) => Some(content_hash),
+            None if self.off_chain => None,
+            None => Some(multipart::content_hash(
+                &fs::read(&file).with_context(|| format!("io error reading {}", file.display()))?,
+            )),
+        };
+
+        let index = match index {
+            Some(index) => index,
+            None => {
+                let index = Index::open(&options)?;
+                index.update()?;
+                index
+            },
+        };
+
+        if let Some(content_hash) = content_hash {
+            if let Some(inscription_id) = index.get_inscription_id_by_content_hash(&content_hash)? {
+                if self.dedup {
+                    inscription = inscription.with_reference(content_hash)?;
+                } else {
+                    eprintln!(
+                        "warning: content of {} is already inscribed by {inscription_id}, use \
+                        `--dedup` to inscribe a reference to it instead",
+                        file.display()
+                    );
+                }
+            }
+        }
+
+        if let Some(parent) = self.parent {
+            inscription = inscription.with_parent(parent);
+        }
+
+        if self.multipart {
+            print_json(Self::inscribe_multipart(
+                &options,
+                &index,
+                inscription,
+                part_size,
+                self.destination,
+                self.fee_rate,
+                self.commit_fee_rate,
+                self.no_limit,
+                self.dry_run,
+                self.no_backup,
+            )?)?;
+
+            return Ok(());
+        }
+
+        print_json(Self::inscribe(
+            &options,
+            &index,
+            inscription,
+            self.parent,
+            self.satpoint,
+            self.destination,
+            self.fee_rate,
+            self.commit_fee_rate,
+            self.no_limit,
+            self.dry_run,
+            self.no_backup,
+        )?)?;
+
+        Ok(())
+    }
+
+    /// Reveal the inscriptions listed in the batch file at `path` in a single
+    /// transaction, using the options that aren't specific to an inscription
+    /// for all of them.
+    fn run_batch(
+        self,
+        options: Options,
+        path: &Path,
+    ) -> Result {
+        let batchfile = Batchfile::load(path)?;
+
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
         let index = Index::open(&options)?;
         index.update()?;
 
+        let compression_dictionary = match self.compression_dictionary {
+            Some(inscription_id) => Some((
+                inscription_id,
+                Self::get_compression_dictionary(&index, inscription_id)?,
+            )),
+            None => None,
+        };
+
+        let mut batch = Vec::new();
+
+        for entry in batchfile.inscriptions {
+            let file = directory.join(&entry.file);
+
+            let mut inscription = Inscription::from_file(
+                options.chain(),
+                &file,
+                entry.title,
+                entry.subtitle,
+                self.compression,
+                compression_dictionary.clone(),
+                self.binary_envelope,
+                false,
+                false,
+                &TorrentOptions::default(),
+                entry.metadata_file.map(|path| directory.join(path)),
+                entry.properties_file.map(|path| directory.join(path)),
+                entry.license,
+                self.protocol_id.clone(),
+                entry.description,
+            )?;
+
+            let content_hash = multipart::content_hash(
+                &fs::read(&file).with_context(|| format!("io error reading {}", file.display()))?,
+            );
+
+            if let Some(inscription_id) = index.get_inscription_id_by_content_hash(&content_hash)? {
+                eprintln!(
+                    "warning: content of {} is already inscribed by {inscription_id}",
+                    file.display()
+                );
+            }
+
+            if let Some(parent) = self.parent {
+                inscription = inscription.with_parent(parent);
+            }
+
+            batch.push((
+                inscription,
+                entry
+                    .destination
+                    .map(|destination| destination.0)
+                    .or_else(|| self.destination.clone()),
+            ));
+        }
+
+        print_json(Self::inscribe_batch(
+            &options,
+            &index,
+            batch,
+            self.parent,
+            self.satpoint,
+            self.fee_rate,
+            self.commit_fee_rate,
+            self.no_limit,
+            self.dry_run,
+            self.no_backup,
+        )?)?;
+
+        Ok(())
+    }
+
+    fn get_compression_dictionary(
+        index: &Index,
+        inscription_id: InscriptionId,
+    ) -> Result<Vec<u8>> {
+        let (_content_type, dictionary) = index
+            .get_inscription_by_id(inscription_id)?
+            .ok_or_else(|| anyhow!("compression dictionary {inscription_id} not found"))?
+            .content()
+            .ok_or_else(|| {
+                anyhow!("compression dictionary {inscription_id} has no on-chain content")
+            })?;
+
+        Ok(dictionary)
+    }
+
+    /// Build the commit and reveal transactions for `inscription`, and unless
+    /// `dry_run` is set, sign and broadcast them. If `parent` is given, the
+    /// reveal transaction also spends it.
+    pub(crate) fn inscribe(
+        options: &Options,
+        index: &Index,
+        inscription: Inscription,
+        parent: Option<InscriptionId>,
+        satpoint: Option<SatPoint>,
+        destination: Option<Address>,
+        fee_rate: FeeRate,
+        commit_fee_rate: Option<FeeRate>,
+        no_limit: bool,
+        dry_run: bool,
+        no_backup: bool,
+    ) -> Result<Output> {
+        let output = Self::inscribe_batch(
+            options,
+            index,
+            vec![(inscription, destination)],
+            parent,
+            satpoint,
+            fee_rate,
+            commit_fee_rate,
+            no_limit,
+            dry_run,
+            no_backup,
+        )?;
+
+        Ok(Output {
+            inscriptions: Vec::new(),
+            ..output
+        })
+    }
+
+    /// Build a commit transaction and a reveal transaction revealing every
+    /// inscription in `batch`, each sent to its own output, and unless
+    /// `dry_run` is set, sign and broadcast them. Inscriptions without a
+    /// destination are sent to a change address.
+    fn inscribe_batch(
+        options: &Options,
+        index: &Index,
+        batch: Vec<(Inscription, Option<Address>)>,
+        parent: Option<InscriptionId>,
+        satpoint: Option<SatPoint>,
+        fee_rate: FeeRate,
+        commit_fee_rate: Option<FeeRate>,
+        no_limit: bool,
+        dry_run: bool,
+        no_backup: bool,
+    ) -> Result<Output> {
         let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
 
-        let mut utxos = index.get_unspent_outputs(Wallet::load(&options)?)?;
+        let mut utxos = index.get_unspent_outputs(Wallet::load(options)?)?;
 
         let inscriptions = index.get_inscriptions(None)?;
 
+        let parent = parent
+            .map(|parent| Self::get_parent(index, &utxos, &inscriptions, parent))
+            .transpose()?;
+
+        let has_parent = parent.is_some();
+
         let commit_tx_change = [get_change_address(&client)?, get_change_address(&client)?];
 
-        let reveal_tx_destination = self
-            .destination
-            .map(Ok)
-            .unwrap_or_else(|| get_change_address(&client))?;
+        let batch = batch
+            .into_iter()
+            .map(|(inscription, destination)| {
+                Ok((
+                    inscription,
+                    destination
+                        .map(Ok)
+                        .unwrap_or_else(|| get_change_address(&client))?,
+                ))
+            })
+            .collect::<Result<Vec<(Inscription, Address)>>>()?;
+
+        let count = batch.len();
 
         let (unsigned_commit_tx, reveal_tx, recovery_key_pair) =
-            Inscribe::create_inscription_transactions(
-                self.satpoint,
-                inscription,
+            Inscribe::create_batch_inscription_transactions(
+                satpoint,
+                batch,
+                parent,
                 inscriptions,
                 options.chain().network(),
                 utxos.clone(),
                 commit_tx_change,
-                reveal_tx_destination,
-                self.commit_fee_rate.unwrap_or(self.fee_rate),
-                self.fee_rate,
-                self.no_limit,
+                commit_fee_rate.unwrap_or(fee_rate),
+                fee_rate,
+                no_limit,
             )?;
 
         utxos.insert(
@@ -203,43 +640,234 @@ impl Inscribe {
         let fees = Self::calculate_fee(&unsigned_commit_tx, &utxos)
             + Self::calculate_fee(&reveal_tx, &utxos);
 
-        if self.dry_run {
-            print_json(Output {
-                commit: unsigned_commit_tx.txid(),
-                reveal: reveal_tx.txid(),
-                inscription: reveal_tx.txid().into(),
-                fees,
-            })?;
+        let (commit, reveal) = if dry_run {
+            (unsigned_commit_tx.txid(), reveal_tx.txid())
         } else {
-            if !self.no_backup {
-                Inscribe::backup_recovery_key(
-                    &client,
-                    recovery_key_pair,
-                    options.chain().network(),
-                )?;
-            }
+            Self::broadcast(
+                &client,
+                &unsigned_commit_tx,
+                reveal_tx,
+                recovery_key_pair,
+                options.chain().network(),
+                has_parent,
+                no_backup,
+            )?
+        };
+
+        Ok(Output {
+            commit,
+            reveal,
+            inscription: reveal.into(),
+            fees,
+            parts: Vec::new(),
+            inscriptions: (0..count)
+                .map(|index| InscriptionId {
+                    txid: reveal,
+                    index: index.try_into().unwrap(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Inscribe the content of `inscription` as parts of at most `part_size`
+    /// bytes, each revealed by its own transaction, followed by a manifest
+    /// inscription listing the parts. Each commit transaction spends the change
+    /// of the one before it, so all transactions are built before any are
+    /// broadcast.
+    pub(crate) fn inscribe_multipart(
+        options: &Options,
+        index: &Index,
+        inscription: Inscription,
+        part_size: usize,
+        destination: Option<Address>,
+        fee_rate: FeeRate,
+        commit_fee_rate: Option<FeeRate>,
+        no_limit: bool,
+        dry_run: bool,
+        no_backup: bool,
+    ) -> Result<Output> {
+        let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
 
-            let signed_raw_commit_tx = client
-                .sign_raw_transaction_with_wallet(&unsigned_commit_tx, None, None)?
-                .hex;
+        let network = options.chain().network();
 
-            let commit = client
-                .send_raw_transaction(&signed_raw_commit_tx)
-                .context("Failed to send commit transaction")?;
+        let mut utxos = index.get_unspent_outputs(Wallet::load(options)?)?;
 
-            let reveal = client
-                .send_raw_transaction(&reveal_tx)
-                .context("Failed to send reveal transaction")?;
+        let mut amounts = utxos.clone();
+
+        let inscriptions = index.get_inscriptions(None)?;
+
+        let reveal_tx_destination = destination
+            .map(Ok)
+            .unwrap_or_else(|| get_change_address(&client))?;
+
+        let mut transactions = Vec::new();
+        let mut parts = Vec::new();
+
+        for part in inscription.parts(part_size)? {
+            let content_hash = multipart::content_hash(part.body().unwrap_or_default());
+
+            let transaction = Self::create_chained_inscription_transactions(
+                &client,
+                part,
+                &inscriptions,
+                network,
+                &mut utxos,
+                &mut amounts,
+                &reveal_tx_destination,
+                commit_fee_rate.unwrap_or(fee_rate),
+                fee_rate,
+                no_limit,
+            )?;
 
-            print_json(Output {
+            parts.push(multipart::Part {
+                inscription_id: transaction.1.txid().into(),
+                content_hash,
+            });
+
+            transactions.push(transaction);
+        }
+
+        transactions.push(Self::create_chained_inscription_transactions(
+            &client,
+            inscription.with_parts(parts)?,
+            &inscriptions,
+            network,
+            &mut utxos,
+            &mut amounts,
+            &reveal_tx_destination,
+            commit_fee_rate.unwrap_or(fee_rate),
+            fee_rate,
+            no_limit,
+        )?);
+
+        let mut outputs = Vec::new();
+
+        for (commit_tx, reveal_tx, recovery_key_pair) in transactions {
+            let fees = Self::calculate_fee(&commit_tx, &amounts)
+                + Self::calculate_fee(&reveal_tx, &amounts);
+
+            let (commit, reveal) = if dry_run {
+                (commit_tx.txid(), reveal_tx.txid())
+            } else {
+                Self::broadcast(
+                    &client,
+                    &commit_tx,
+                    reveal_tx,
+                    recovery_key_pair,
+                    network,
+                    false,
+                    no_backup,
+                )?
+            };
+
+            outputs.push(Output {
                 commit,
                 reveal,
                 inscription: reveal.into(),
                 fees,
-            })?;
+                parts: Vec::new(),
+                inscriptions: Vec::new(),
+            });
+        }
+
+        let manifest = outputs.pop().unwrap();
+
+        Ok(Output {
+            parts: outputs,
+            ..manifest
+        })
+    }
+
+    /// Build the commit and reveal transactions for `inscription`, replacing
+    /// the `utxos` spent by the commit transaction with its change, so that the
+    /// transactions built after it can spend it. The values of all commit
+    /// transaction outputs are added to `amounts`.
+    fn create_chained_inscription_transactions(
+        client: &Client,
+        inscription: Inscription,
+        inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+        network: Network,
+        utxos: &mut BTreeMap<OutPoint, Amount>,
+        amounts: &mut BTreeMap<OutPoint, Amount>,
+        destination: &Address,
+        commit_fee_rate: FeeRate,
+        reveal_fee_rate: FeeRate,
+        no_limit: bool,
+    ) -> Result<(Transaction, Transaction, TweakedKeyPair)> {
+        let (commit_tx, reveal_tx, recovery_key_pair) = Self::create_inscription_transactions(
+            None,
+            inscription,
+            None,
+            inscriptions.clone(),
+            network,
+            utxos.clone(),
+            [get_change_address(client)?, get_change_address(client)?],
+            destination.clone(),
+            commit_fee_rate,
+            reveal_fee_rate,
+            no_limit,
+        )?;
+
+        for input in &commit_tx.input {
+            utxos.remove(&input.previous_output);
+        }
+
+        let commit_outpoint = reveal_tx.input[0].previous_output;
+
+        for (vout, output) in commit_tx.output.iter().enumerate() {
+            let outpoint = OutPoint {
+                txid: commit_tx.txid(),
+                vout: vout.try_into().unwrap(),
+            };
+
+            let amount = Amount::from_sat(output.value);
+
+            if outpoint != commit_outpoint {
+                utxos.insert(outpoint, amount);
+            }
+
+            amounts.insert(outpoint, amount);
+        }
+
+        Ok((commit_tx, reveal_tx, recovery_key_pair))
+    }
+
+    /// Sign and broadcast a commit transaction and its reveal transaction,
+    /// signing the reveal transaction's wallet inputs if `sign_reveal` is set.
+    fn broadcast(
+        client: &Client,
+        commit_tx: &Transaction,
+        reveal_tx: Transaction,
+        recovery_key_pair: TweakedKeyPair,
+        network: Network,
+        sign_reveal: bool,
+        no_backup: bool,
+    ) -> Result<(Txid, Txid)> {
+        if !no_backup {
+            Inscribe::backup_recovery_key(client, recovery_key_pair, network)?;
+        }
+
+        let signed_raw_commit_tx = client
+            .sign_raw_transaction_with_wallet(commit_tx, None, None)?
+            .hex;
+
+        let commit = client
+            .send_raw_transaction(&signed_raw_commit_tx)
+            .context("Failed to send commit transaction")?;
+
+        let reveal_tx = if sign_reveal {
+            client
+                .sign_raw_transaction_with_wallet(&reveal_tx, None, None)?
+                .transaction()?
+        } else {
+            reveal_tx
         };
 
-        Ok(())
+        let reveal = client
+            .send_raw_transaction(&reveal_tx)
+            .context("Failed to send reveal transaction")?;
+
+        Ok((commit, reveal))
     }
 
     fn calculate_fee(
@@ -254,9 +882,51 @@ impl Inscribe {
             .unwrap()
     }
 
+    fn get_parent(
+        index: &Index,
+        utxos: &BTreeMap<OutPoint, Amount>,
+        inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+        parent: InscriptionId,
+    ) -> Result<Parent> {
+        let satpoint = index
+            .get_inscription_satpoint_by_id(parent)?
+            .ok_or_else(|| anyhow!("parent {parent} does not exist"))?;
+
+        if !utxos.contains_key(&satpoint.outpoint) {
+            bail!("parent {parent} is not owned by this wallet");
+        }
+
+        let inscribed_utxos = inscriptions
+            .keys()
+            .map(|satpoint| satpoint.outpoint)
+            .collect::<BTreeSet<OutPoint>>();
+
+        let fee_outpoint = utxos
+            .iter()
+            .filter(|(outpoint, _amount)| !inscribed_utxos.contains(outpoint))
+            .min_by_key(|(_outpoint, amount)| **amount)
+            .map(|(outpoint, _amount)| *outpoint)
+            .ok_or_else(|| anyhow!("wallet contains no cardinal utxos"))?;
+
+        let get_output = |outpoint: OutPoint| -> Result<TxOut> {
+            index
+                .get_transaction(outpoint.txid)?
+                .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.try_into().unwrap()))
+                .ok_or_else(|| anyhow!("output {outpoint} not found"))
+        };
+
+        Ok(Parent {
+            satpoint,
+            output: get_output(satpoint.outpoint)?,
+            fee_outpoint,
+            fee_output: get_output(fee_outpoint)?,
+        })
+    }
+
     fn create_inscription_transactions(
         satpoint: Option<SatPoint>,
         inscription: Inscription,
+        parent: Option<Parent>,
         inscriptions: BTreeMap<SatPoint, InscriptionId>,
         network: Network,
         utxos: BTreeMap<OutPoint, Amount>,
@@ -266,6 +936,42 @@ impl Inscribe {
         reveal_fee_rate: FeeRate,
         no_limit: bool,
     ) -> Result<(Transaction, Transaction, TweakedKeyPair)> {
+        Self::create_batch_inscription_transactions(
+            satpoint,
+            vec![(inscription, destination)],
+            parent,
+            inscriptions,
+            network,
+            utxos,
+            change,
+            commit_fee_rate,
+            reveal_fee_rate,
+            no_limit,
+        )
+    }
+
+    /// Build a commit transaction, and a reveal transaction whose tapscript
+    /// has an envelope for each inscription in `batch`. The inscription with
+    /// index i is sent to output i, so each is made on the first sat of its
+    /// own output. The first output is the one the commit transaction's
+    /// inscribed sat ends up in, and carries what's left after the fee, while
+    /// the others carry the target postage.
+    fn create_batch_inscription_transactions(
+        satpoint: Option<SatPoint>,
+        batch: Vec<(Inscription, Address)>,
+        parent: Option<Parent>,
+        inscriptions: BTreeMap<SatPoint, InscriptionId>,
+        network: Network,
+        mut utxos: BTreeMap<OutPoint, Amount>,
+        change: [Address; 2],
+        commit_fee_rate: FeeRate,
+        reveal_fee_rate: FeeRate,
+        no_limit: bool,
+    ) -> Result<(Transaction, Transaction, TweakedKeyPair)> {
+        if let Some(parent) = &parent {
+            utxos.remove(&parent.fee_outpoint);
+        }
+
         let satpoint = if let Some(satpoint) = satpoint {
             satpoint
         } else {
@@ -301,11 +1007,15 @@ impl Inscribe {
         let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
         let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
 
-        let reveal_script = inscription.append_reveal_script(
-            script::Builder::new()
-                .push_slice(&public_key.serialize())
-                .push_opcode(opcodes::all::OP_CHECKSIG),
-        );
+        let mut reveal_script = script::Builder::new()
+            .push_slice(&public_key.serialize())
+            .push_opcode(opcodes::all::OP_CHECKSIG);
+
+        for (inscription, _destination) in &batch {
+            reveal_script = inscription.append_reveal_script_to_builder(reveal_script);
+        }
+
+        let reveal_script = reveal_script.into_script();
 
         let taproot_spend_info = TaprootBuilder::new()
             .add_leaf(0, reveal_script.clone())
@@ -319,17 +1029,49 @@ impl Inscribe {
 
         let commit_tx_address = Address::p2tr_tweaked(taproot_spend_info.output_key(), network);
 
+        let reveal_inputs = |commit: OutPoint| {
+            let mut inputs = vec![commit];
+            if let Some(parent) = &parent {
+                inputs.extend([parent.satpoint.outpoint, parent.fee_outpoint]);
+            }
+            inputs
+        };
+
+        let reveal_outputs = |value: u64| {
+            let mut outputs = batch
+                .iter()
+                .enumerate()
+                .map(|(index, (_inscription, destination))| TxOut {
+                    script_pubkey: destination.script_pubkey(),
+                    value: if index == 0 {
+                        value
+                    } else {
+                        TransactionBuilder::TARGET_POSTAGE.to_sat()
+                    },
+                })
+                .collect::<Vec<TxOut>>();
+            if let Some(parent) = &parent {
+                outputs.extend([parent.output.clone(), parent.fee_output.clone()]);
+            }
+            outputs
+        };
+
         let (_, reveal_fee) = Self::build_reveal_transaction(
             &control_block,
             reveal_fee_rate,
-            OutPoint::null(),
-            TxOut {
-                script_pubkey: destination.script_pubkey(),
-                value: 0,
-            },
+            reveal_inputs(OutPoint::null()),
+            reveal_outputs(0),
             &reveal_script,
         );
 
+        let postage = TransactionBuilder::TARGET_POSTAGE * u64::try_from(batch.len()).unwrap();
+
+        let commit_value = if parent.is_some() {
+            postage
+        } else {
+            reveal_fee + postage
+        };
+
         let unsigned_commit_tx = TransactionBuilder::build_transaction_with_value(
             satpoint,
             inscriptions,
@@ -337,7 +1079,7 @@ impl Inscribe {
             commit_tx_address.clone(),
             change,
             commit_fee_rate,
-            reveal_fee + TransactionBuilder::TARGET_POSTAGE,
+            commit_value,
         )?;
 
         let (vout, output) = unsigned_commit_tx
@@ -350,21 +1092,35 @@ impl Inscribe {
         let (mut reveal_tx, fee) = Self::build_reveal_transaction(
             &control_block,
             reveal_fee_rate,
-            OutPoint {
+            reveal_inputs(OutPoint {
                 txid: unsigned_commit_tx.txid(),
                 vout: vout.try_into().unwrap(),
-            },
-            TxOut {
-                script_pubkey: destination.script_pubkey(),
-                value: output.value,
-            },
+            }),
+            reveal_outputs(output.value - (postage - TransactionBuilder::TARGET_POSTAGE).to_sat()),
             &reveal_script,
         );
 
-        reveal_tx.output[0].value = reveal_tx.output[0]
-            .value
-            .checked_sub(fee.to_sat())
-            .context("commit transaction output value insufficient to pay transaction fee")?;
+        let mut prevouts = vec![output.clone()];
+
+        if let Some(parent) = parent {
+            let change = &mut reveal_tx.output[batch.len() + 1];
+
+            change.value = change
+                .value
+                .checked_sub(fee.to_sat())
+                .context("cardinal utxo value insufficient to pay reveal transaction fee")?;
+
+            if change.value < change.script_pubkey.dust_value().to_sat() {
+                reveal_tx.output.pop();
+            }
+
+            prevouts.extend([parent.output, parent.fee_output]);
+        } else {
+            reveal_tx.output[0].value = reveal_tx.output[0]
+                .value
+                .checked_sub(fee.to_sat())
+                .context("commit transaction output value insufficient to pay transaction fee")?;
+        }
 
         if reveal_tx.output[0].value < reveal_tx.output[0].script_pubkey.dust_value().to_sat() {
             bail!("commit transaction output would be dust");
@@ -375,7 +1131,7 @@ impl Inscribe {
         let signature_hash = sighash_cache
             .taproot_script_spend_signature_hash(
                 0,
-                &Prevouts::All(&[output]),
+                &Prevouts::All(&prevouts),
                 TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
                 SchnorrSighashType::Default,
             )
@@ -450,18 +1206,21 @@ impl Inscribe {
     fn build_reveal_transaction(
         control_block: &ControlBlock,
         fee_rate: FeeRate,
-        input: OutPoint,
-        output: TxOut,
+        inputs: Vec<OutPoint>,
+        outputs: Vec<TxOut>,
         script: &Script,
     ) -> (Transaction, Amount) {
         let reveal_tx = Transaction {
-            input: vec![TxIn {
-                previous_output: input,
-                script_sig: script::Builder::new().into_script(),
-                witness: Witness::new(),
-                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
-            }],
-            output: vec![output],
+            input: inputs
+                .into_iter()
+                .map(|previous_output| TxIn {
+                    previous_output,
+                    script_sig: script::Builder::new().into_script(),
+                    witness: Witness::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                })
+                .collect(),
+            output: outputs,
             lock_time: PackedLockTime::ZERO,
             version: 1,
         };
@@ -477,6 +1236,14 @@ impl Inscribe {
             reveal_tx.input[0].witness.push(script);
             reveal_tx.input[0].witness.push(&control_block.serialize());
 
+            for txin in reveal_tx.input.iter_mut().skip(1) {
+                txin.witness.push(
+                    Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
+                        .unwrap()
+                        .as_ref(),
+                );
+            }
+
             fee_rate.fee(reveal_tx.vsize())
         };
 
@@ -498,6 +1265,7 @@ mod tests {
         let (commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
             Some(satpoint(1, 0)),
             inscription,
+            None,
             BTreeMap::new(),
             Network::Bitcoin,
             utxos.into_iter().collect(),
@@ -529,6 +1297,7 @@ mod tests {
         let (commit_tx, reveal_tx, _) = Inscribe::create_inscription_transactions(
             Some(satpoint(1, 0)),
             inscription,
+            None,
             BTreeMap::new(),
             Network::Bitcoin,
             utxos.into_iter().collect(),
@@ -564,6 +1333,7 @@ mod tests {
         let error = Inscribe::create_inscription_transactions(
             satpoint,
             inscription,
+            None,
             inscriptions,
             Network::Bitcoin,
             utxos.into_iter().collect(),
@@ -606,6 +1376,7 @@ mod tests {
         assert!(Inscribe::create_inscription_transactions(
             satpoint,
             inscription,
+            None,
             inscriptions,
             Network::Bitcoin,
             utxos.into_iter().collect(),
@@ -618,6 +1389,149 @@ mod tests {
         .is_ok())
     }
 
+    #[test]
+    fn inscribe_with_parent() {
+        let utxos = vec![
+            (outpoint(1), Amount::from_sat(20_000)),
+            (outpoint(2), Amount::from_sat(10_000)),
+            (outpoint(3), Amount::from_sat(5_000)),
+        ];
+        let mut inscriptions = BTreeMap::new();
+        inscriptions.insert(satpoint(2, 0), inscription_id(1));
+
+        let parent = Parent {
+            satpoint: satpoint(2, 0),
+            output: TxOut {
+                script_pubkey: recipient().script_pubkey(),
+                value: 10_000,
+            },
+            fee_outpoint: outpoint(3),
+            fee_output: TxOut {
+                script_pubkey: change(2).script_pubkey(),
+                value: 5_000,
+            },
+        };
+
+        let (commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
+            None,
+            inscription("text/plain", "ord"),
+            Some(parent),
+            inscriptions,
+            Network::Bitcoin,
+            utxos.into_iter().collect(),
+            [change(0), change(1)],
+            recipient(),
+            FeeRate::try_from(1.0).unwrap(),
+            FeeRate::try_from(1.0).unwrap(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(commit_tx.input.len(), 1);
+        assert_eq!(commit_tx.input[0].previous_output, outpoint(1));
+
+        assert_eq!(
+            reveal_tx
+                .input
+                .iter()
+                .map(|txin| txin.previous_output)
+                .collect::<Vec<OutPoint>>(),
+            [
+                OutPoint {
+                    txid: commit_tx.txid(),
+                    vout: 0,
+                },
+                outpoint(2),
+                outpoint(3)
+            ]
+        );
+
+        assert_eq!(reveal_tx.output.len(), 3);
+        assert_eq!(reveal_tx.output[0].value, commit_tx.output[0].value);
+        assert_eq!(
+            reveal_tx.output[0].value,
+            TransactionBuilder::TARGET_POSTAGE.to_sat()
+        );
+        assert_eq!(reveal_tx.output[1].value, 10_000);
+        assert_eq!(
+            reveal_tx.output[1].script_pubkey,
+            recipient().script_pubkey()
+        );
+        assert!(reveal_tx.output[2].value < 5_000);
+    }
+
+    #[test]
+    fn inscribe_batch() {
+        let utxos = vec![(outpoint(1), Amount::from_sat(50_000))];
+
+        let batch = vec![
+            (inscription("text/plain", "foo"), recipient()),
+            (inscription("text/plain", "bar"), change(2)),
+            (inscription("text/plain", "baz"), recipient()),
+        ];
+
+        let (commit_tx, reveal_tx, _private_key) = Inscribe::create_batch_inscription_transactions(
+            None,
+            batch,
+            None,
+            BTreeMap::new(),
+            Network::Bitcoin,
+            utxos.into_iter().collect(),
+            [change(0), change(1)],
+            FeeRate::try_from(1.0).unwrap(),
+            FeeRate::try_from(1.0).unwrap(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(reveal_tx.input.len(), 1);
+
+        assert_eq!(
+            Inscription::from_transaction(&reveal_tx),
+            [
+                inscription("text/plain", "foo"),
+                inscription("text/plain", "bar"),
+                inscription("text/plain", "baz"),
+            ]
+        );
+
+        assert_eq!(
+            reveal_tx
+                .output
+                .iter()
+                .map(|txout| txout.script_pubkey.clone())
+                .collect::<Vec<Script>>(),
+            [
+                recipient().script_pubkey(),
+                change(2).script_pubkey(),
+                recipient().script_pubkey(),
+            ]
+        );
+
+        assert!(
+            reveal_tx.output[0].value >= reveal_tx.output[0].script_pubkey.dust_value().to_sat()
+        );
+        assert_eq!(
+            reveal_tx.output[1].value,
+            TransactionBuilder::TARGET_POSTAGE.to_sat()
+        );
+        assert_eq!(
+            reveal_tx.output[2].value,
+            TransactionBuilder::TARGET_POSTAGE.to_sat()
+        );
+
+        let fee = Amount::from_sat(
+            commit_tx.output[0].value
+                - reveal_tx
+                    .output
+                    .iter()
+                    .map(|txout| txout.value)
+                    .sum::<u64>(),
+        );
+
+        assert_eq!(fee, FeeRate::try_from(1.0).unwrap().fee(reveal_tx.vsize()));
+    }
+
     #[test]
     fn inscribe_with_custom_fee_rate() {
         let utxos = vec![
@@ -642,6 +1556,7 @@ mod tests {
         let (commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
             satpoint,
             inscription,
+            None,
             inscriptions,
             bitcoin::Network::Signet,
             utxos.into_iter().collect(),
@@ -704,6 +1619,7 @@ mod tests {
         let (commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
             satpoint,
             inscription,
+            None,
             inscriptions,
             bitcoin::Network::Signet,
             utxos.into_iter().collect(),
@@ -753,6 +1669,7 @@ mod tests {
         let error = Inscribe::create_inscription_transactions(
             satpoint,
             inscription,
+            None,
             BTreeMap::new(),
             Network::Bitcoin,
             utxos.into_iter().collect(),
@@ -787,6 +1704,7 @@ mod tests {
         let (_commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
             satpoint,
             inscription,
+            None,
             BTreeMap::new(),
             Network::Bitcoin,
             utxos.into_iter().collect(),
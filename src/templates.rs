@@ -4,13 +4,27 @@ use {
 };
 
 pub(crate) use {
+    address::{
+        AddressHtml,
+        AddressOutput,
+    },
+    bfs::BfsHtml,
+    bid::{
+        BidHtml,
+        BidJson,
+    },
     block::BlockHtml,
+    bnw::{
+        BnwHtml,
+        BnwWall,
+    },
     clock::ClockSvg,
     home::HomeHtml,
     iframe::Iframe,
     input::InputHtml,
     inscription::InscriptionHtml,
     inscriptions::InscriptionsHtml,
+    mempool::MempoolHtml,
     output::OutputHtml,
     page_config::PageConfig,
     preview::{
@@ -21,21 +35,30 @@ pub(crate) use {
         PreviewUnknownHtml,
         PreviewVideoHtml,
     },
+    pub_inscription::PubInscriptionHtml,
+    pub_inscriptions::PubInscriptionsHtml,
     range::RangeHtml,
     rare::RareTxt,
     sat::SatHtml,
     transaction::TransactionHtml,
 };
 
+mod address;
+mod bfs;
+mod bid;
 mod block;
+mod bnw;
 mod clock;
 mod home;
 mod iframe;
 mod input;
 mod inscription;
 mod inscriptions;
+mod mempool;
 mod output;
 mod preview;
+mod pub_inscription;
+mod pub_inscriptions;
 mod range;
 mod rare;
 mod sat;
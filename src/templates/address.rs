@@ -0,0 +1,87 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct AddressHtml {
+    pub(crate) address: Address,
+    pub(crate) outputs: Vec<AddressOutput>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct AddressOutput {
+    pub(crate) outpoint: OutPoint,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) rare_sats: Vec<Sat>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct AddressJson {
+    pub(crate) address: String,
+    pub(crate) outputs: Vec<AddressOutput>,
+}
+
+impl AddressHtml {
+    pub(crate) fn to_json(&self) -> AddressJson {
+        AddressJson {
+            address: self.address.to_string(),
+            outputs: self.outputs.clone(),
+        }
+    }
+}
+
+impl PageContent for AddressHtml {
+    fn title(&self) -> String {
+        format!("Address {}", self.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address() {
+        assert_regex_match!(
+            AddressHtml {
+                address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+                    .parse()
+                    .unwrap(),
+                outputs: vec![AddressOutput {
+                    outpoint: outpoint(1),
+                    inscriptions: vec![inscription_id(1)],
+                    rare_sats: vec![Sat(0)],
+                }],
+            },
+            "
+        <h1>Address <span class=monospace>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</span></h1>
+        <h2>1 Output</h2>
+        <dl>
+          <dt>output</dt><dd><a class=monospace href=/output/1{64}:1>1{64}:1</a></dd>
+          <dt>inscriptions</dt>
+          <dd class=thumbnails>
+            <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          </dd>
+          <dt>rare sats</dt>
+          <dd><a href=/sat/0 class=mythic>0</a></dd>
+        </dl>
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn empty_address() {
+        assert_regex_match!(
+            AddressHtml {
+                address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+                    .parse()
+                    .unwrap(),
+                outputs: Vec::new(),
+            },
+            "
+        <h1>Address <span class=monospace>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</span></h1>
+        <p>No outputs with inscriptions or rare sats.</p>
+      "
+            .unindent()
+        );
+    }
+}
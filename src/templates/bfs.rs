@@ -0,0 +1,102 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct BfsHtml {
+    pub(crate) bid: Username,
+    pub(crate) path: BfsPath,
+    pub(crate) entries: Vec<DirectoryEntry>,
+}
+
+impl BfsHtml {
+    fn href(
+        &self,
+        path: &BfsPath,
+    ) -> String {
+        let mut href = format!("/bfs/{}", self.bid);
+
+        for segment in path.as_str().split('/').skip(1) {
+            href.push('/');
+            href.push_str(&urlencoding::encode(segment));
+        }
+
+        href
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct BfsJson {
+    pub(crate) bid: String,
+    pub(crate) entries: Vec<DirectoryEntry>,
+    pub(crate) path: String,
+}
+
+impl BfsHtml {
+    pub(crate) fn to_json(&self) -> BfsJson {
+        BfsJson {
+            bid: self.bid.to_string(),
+            entries: self.entries.clone(),
+            path: self.path.to_string(),
+        }
+    }
+}
+
+impl PageContent for BfsHtml {
+    fn title(&self) -> String {
+        format!("bfs {}{}", self.bid, self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root() {
+        assert_regex_match!(
+            BfsHtml {
+                bid: "alice_1".parse().unwrap(),
+                path: BfsPath::root(),
+                entries: vec![
+                    DirectoryEntry {
+                        name: "a b.txt".into(),
+                        inscription_id: Some(inscription_id(1)),
+                    },
+                    DirectoryEntry {
+                        name: "docs".into(),
+                        inscription_id: None,
+                    },
+                ],
+            },
+            "
+        <h1>bfs alice_1/</h1>
+        <ul class=monospace>
+          <li><a href=/bfs/alice_1/a%20b.txt>a b.txt</a></li>
+          <li><a href=/bfs/alice_1/docs>docs/</a></li>
+        </ul>
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn subdirectory() {
+        assert_regex_match!(
+            BfsHtml {
+                bid: "alice_1".parse().unwrap(),
+                path: "/docs/old".parse().unwrap(),
+                entries: vec![DirectoryEntry {
+                    name: "a.txt".into(),
+                    inscription_id: Some(inscription_id(1)),
+                }],
+            },
+            "
+        <h1>bfs alice_1/docs/old</h1>
+        <ul class=monospace>
+          <li><a href=/bfs/alice_1/docs>..</a></li>
+          <li><a href=/bfs/alice_1/docs/old/a.txt>a.txt</a></li>
+        </ul>
+      "
+            .unindent()
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct BidHtml {
+    pub(crate) chain: Chain,
+    pub(crate) username: Username,
+    pub(crate) inscription_id: InscriptionId,
+    pub(crate) registered: u64,
+    pub(crate) expires: u64,
+    pub(crate) active: bool,
+    pub(crate) satpoint: SatPoint,
+    pub(crate) output: TxOut,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct BidJson {
+    pub(crate) name: String,
+    pub(crate) inscription_id: InscriptionId,
+    pub(crate) registered: u64,
+    pub(crate) expires: u64,
+    pub(crate) active: bool,
+    pub(crate) satpoint: SatPoint,
+    pub(crate) address: Option<String>,
+}
+
+impl BidHtml {
+    pub(crate) fn to_json(&self) -> BidJson {
+        BidJson {
+            name: self.username.to_string(),
+            inscription_id: self.inscription_id,
+            registered: self.registered,
+            expires: self.expires,
+            active: self.active,
+            satpoint: self.satpoint,
+            address: self
+                .chain
+                .address_from_script(&self.output.script_pubkey)
+                .ok()
+                .map(|address| address.to_string()),
+        }
+    }
+}
+
+impl PageContent for BidHtml {
+    fn title(&self) -> String {
+        format!("bid {}", self.username)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bid_html(active: bool) -> BidHtml {
+        BidHtml {
+            chain: Chain::Mainnet,
+            username: "alice_1".parse().unwrap(),
+            inscription_id: inscription_id(1),
+            registered: 1,
+            expires: 52_501,
+            active,
+            satpoint: satpoint(1, 0),
+            output: tx_out(1, address()),
+        }
+    }
+
+    #[test]
+    fn active() {
+        assert_regex_match!(
+            bid_html(true),
+            "
+        <h1>bid alice_1</h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+        </div>
+        <dl>
+          <dt>status</dt>
+          <dd>active</dd>
+          <dt>registered</dt>
+          <dd><a href=/block/1>1</a></dd>
+          <dt>expires</dt>
+          <dd>52501</dd>
+          <dt>inscription</dt>
+          <dd><a class=monospace href=/inscription/1{64}i1>1{64}i1</a></dd>
+          <dt>location</dt>
+          <dd class=monospace>1{64}:1:0</dd>
+          <dt>owner</dt>
+          <dd class=monospace>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</dd>
+        </dl>
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn expired() {
+        assert_regex_match!(
+            bid_html(false),
+            "
+        <h1>bid alice_1</h1>
+        .*
+          <dt>status</dt>
+          <dd>expired</dd>
+        .*
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            bid_html(true).to_json(),
+            BidJson {
+                name: "alice_1".into(),
+                inscription_id: inscription_id(1),
+                registered: 1,
+                expires: 52_501,
+                active: true,
+                satpoint: satpoint(1, 0),
+                address: Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into()),
+            }
+        );
+    }
+}
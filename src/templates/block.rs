@@ -27,6 +27,37 @@ impl BlockHtml {
     }
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct BlockJson {
+    pub(crate) hash: BlockHash,
+    pub(crate) height: u64,
+    pub(crate) next: Option<u64>,
+    pub(crate) prev: Option<u64>,
+    pub(crate) previous_blockhash: Option<BlockHash>,
+    pub(crate) size: usize,
+    pub(crate) target: BlockHash,
+    pub(crate) timestamp: i64,
+    pub(crate) transactions: Vec<Txid>,
+    pub(crate) weight: usize,
+}
+
+impl BlockHtml {
+    pub(crate) fn to_json(&self) -> BlockJson {
+        BlockJson {
+            hash: self.hash,
+            height: self.height.n(),
+            next: (self.height < self.best_height).then(|| self.height.n() + 1),
+            prev: self.height.n().checked_sub(1),
+            previous_blockhash: (self.height.n() > 0).then_some(self.block.header.prev_blockhash),
+            size: self.block.size(),
+            target: self.target,
+            timestamp: timestamp(self.block.header.time).timestamp(),
+            transactions: self.block.txdata.iter().map(Transaction::txid).collect(),
+            weight: self.block.weight(),
+        }
+    }
+}
+
 impl PageContent for BlockHtml {
     fn title(&self) -> String {
         format!("Block {}", self.height)
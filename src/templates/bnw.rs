@@ -0,0 +1,89 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct BnwHtml {
+    pub(crate) bid: Username,
+    pub(crate) walls: Vec<BnwWall>,
+}
+
+/// A wall as rendered, listing only the inscriptions its owner still holds.
+pub(crate) struct BnwWall {
+    pub(crate) name: WallName,
+    pub(crate) description: String,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct BnwJson {
+    pub(crate) bid: String,
+    pub(crate) walls: Vec<BnwWallJson>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct BnwWallJson {
+    pub(crate) description: String,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) name: String,
+}
+
+impl BnwHtml {
+    pub(crate) fn to_json(&self) -> BnwJson {
+        BnwJson {
+            bid: self.bid.to_string(),
+            walls: self
+                .walls
+                .iter()
+                .map(|wall| BnwWallJson {
+                    description: wall.description.clone(),
+                    inscriptions: wall.inscriptions.clone(),
+                    name: wall.name.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PageContent for BnwHtml {
+    fn title(&self) -> String {
+        format!("bnw {}", self.bid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walls() {
+        assert_regex_match!(
+            BnwHtml {
+                bid: "alice_1".parse().unwrap(),
+                walls: vec![
+                    BnwWall {
+                        name: "main".parse().unwrap(),
+                        description: "favorites".into(),
+                        inscriptions: vec![inscription_id(1), inscription_id(2)],
+                    },
+                    BnwWall {
+                        name: "empty".parse().unwrap(),
+                        description: String::new(),
+                        inscriptions: Vec::new(),
+                    },
+                ],
+            },
+            "
+        <h1>bnw alice_1</h1>
+        <h2><a href=/bnw/alice_1/main>main</a></h2>
+        <p>favorites</p>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        <h2><a href=/bnw/alice_1/empty>empty</a></h2>
+        <div class=thumbnails>
+        </div>
+      "
+            .unindent()
+        );
+    }
+}
@@ -24,6 +24,23 @@ impl HomeHtml {
     }
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct HomeJson {
+    pub(crate) blocks: Vec<BlockHash>,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) last: u64,
+}
+
+impl HomeHtml {
+    pub(crate) fn to_json(&self) -> HomeJson {
+        HomeJson {
+            blocks: self.blocks.clone(),
+            inscriptions: self.inscriptions.clone(),
+            last: self.last,
+        }
+    }
+}
+
 impl PageContent for HomeHtml {
     fn title(&self) -> String {
         "Ordinals".to_string()
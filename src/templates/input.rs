@@ -6,6 +6,25 @@ pub(crate) struct InputHtml {
     pub(crate) input: TxIn,
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct InputJson {
+    pub(crate) previous_output: OutPoint,
+    pub(crate) script_sig: String,
+    pub(crate) sequence: u32,
+    pub(crate) witness: Vec<String>,
+}
+
+impl InputHtml {
+    pub(crate) fn to_json(&self) -> InputJson {
+        InputJson {
+            previous_output: self.input.previous_output,
+            script_sig: self.input.script_sig.asm(),
+            sequence: self.input.sequence.0,
+            witness: self.input.witness.iter().map(hex::encode).collect(),
+        }
+    }
+}
+
 impl PageContent for InputHtml {
     fn title(&self) -> String {
         format!("Input /{}/{}/{}", self.path.0, self.path.1, self.path.2)
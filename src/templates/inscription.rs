@@ -3,6 +3,7 @@ use super::*;
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionHtml {
     pub(crate) chain: Chain,
+    pub(crate) children: Vec<InscriptionId>,
     pub(crate) genesis_fee: u64,
     pub(crate) genesis_height: u64,
     pub(crate) inscription: Inscription,
@@ -10,10 +11,68 @@ pub(crate) struct InscriptionHtml {
     pub(crate) next: Option<InscriptionId>,
     pub(crate) number: u64,
     pub(crate) output: TxOut,
+    pub(crate) parent: Option<InscriptionId>,
+    pub(crate) pending: Option<Pending>,
     pub(crate) previous: Option<InscriptionId>,
+    pub(crate) protocol_id: String,
     pub(crate) sat: Option<Sat>,
     pub(crate) satpoint: SatPoint,
     pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) transfers: Vec<Transfer>,
+    pub(crate) validation_error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct InscriptionJson {
+    pub(crate) address: Option<String>,
+    pub(crate) children: Vec<InscriptionId>,
+    pub(crate) content_length: Option<usize>,
+    pub(crate) content_type: Option<String>,
+    pub(crate) genesis_fee: u64,
+    pub(crate) genesis_height: u64,
+    pub(crate) inscription_id: InscriptionId,
+    pub(crate) next: Option<InscriptionId>,
+    pub(crate) number: u64,
+    pub(crate) output_value: u64,
+    pub(crate) parent: Option<InscriptionId>,
+    pub(crate) pending: Option<Pending>,
+    pub(crate) previous: Option<InscriptionId>,
+    pub(crate) protocol_id: String,
+    pub(crate) sat: Option<Sat>,
+    pub(crate) satpoint: SatPoint,
+    pub(crate) timestamp: i64,
+    pub(crate) transfers: Vec<Transfer>,
+    pub(crate) validation_error: Option<String>,
+}
+
+impl InscriptionHtml {
+    pub(crate) fn to_json(&self) -> InscriptionJson {
+        InscriptionJson {
+            address: self
+                .chain
+                .address_from_script(&self.output.script_pubkey)
+                .ok()
+                .map(|address| address.to_string()),
+            children: self.children.clone(),
+            content_length: self.inscription.content_length(),
+            content_type: self.inscription.content_type().map(str::to_string),
+            genesis_fee: self.genesis_fee,
+            genesis_height: self.genesis_height,
+            inscription_id: self.inscription_id,
+            next: self.next,
+            number: self.number,
+            output_value: self.output.value,
+            parent: self.parent,
+            pending: self.pending,
+            previous: self.previous,
+            protocol_id: self.protocol_id.clone(),
+            sat: self.sat,
+            satpoint: self.satpoint,
+            timestamp: self.timestamp.timestamp(),
+            transfers: self.transfers.clone(),
+            validation_error: self.validation_error.clone(),
+        }
+    }
 }
 
 impl PageContent for InscriptionHtml {
@@ -35,6 +94,7 @@ mod tests {
         assert_regex_match!(
             InscriptionHtml {
                 chain: Chain::Mainnet,
+                children: Vec::new(),
                 genesis_fee: 1,
                 genesis_height: 0,
                 inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -42,10 +102,15 @@ mod tests {
                 next: None,
                 number: 1,
                 output: tx_out(1, address()),
+                parent: None,
+                pending: None,
                 previous: None,
+                protocol_id: "ord-v0".into(),
                 sat: None,
                 satpoint: satpoint(1, 0),
                 timestamp: timestamp(0),
+                transfers: Vec::new(),
+                validation_error: None,
             },
             "
         <h1>Inscription 1</h1>
@@ -57,6 +122,8 @@ mod tests {
         <dl>
           <dt>id</dt>
           <dd class=monospace>1{64}i1</dd>
+          <dt>protocol</dt>
+          <dd>ord-v0</dd>
           <dt>address</dt>
           <dd class=monospace>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</dd>
           <dt>output value</dt>
@@ -94,6 +161,7 @@ mod tests {
         assert_regex_match!(
             InscriptionHtml {
                 chain: Chain::Mainnet,
+                children: Vec::new(),
                 genesis_fee: 1,
                 genesis_height: 0,
                 inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -101,10 +169,15 @@ mod tests {
                 next: None,
                 number: 1,
                 output: tx_out(1, address()),
+                parent: None,
+                pending: None,
                 previous: None,
+                protocol_id: "ord-v0".into(),
                 sat: Some(Sat(1)),
                 satpoint: satpoint(1, 0),
                 timestamp: timestamp(0),
+                transfers: Vec::new(),
+                validation_error: None,
             },
             "
         <h1>Inscription 1</h1>
@@ -126,6 +199,7 @@ mod tests {
         assert_regex_match!(
             InscriptionHtml {
                 chain: Chain::Mainnet,
+                children: Vec::new(),
                 genesis_fee: 1,
                 genesis_height: 0,
                 inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -133,10 +207,15 @@ mod tests {
                 next: Some(inscription_id(3)),
                 number: 1,
                 output: tx_out(1, address()),
+                parent: None,
+                pending: None,
                 previous: Some(inscription_id(1)),
+                protocol_id: "ord-v0".into(),
                 sat: None,
                 satpoint: satpoint(1, 0),
                 timestamp: timestamp(0),
+                transfers: Vec::new(),
+                validation_error: None,
             },
             "
         <h1>Inscription 1</h1>
@@ -150,4 +229,182 @@ mod tests {
             .unindent()
         );
     }
+
+    #[test]
+    fn with_validation_error() {
+        assert_regex_match!(
+            InscriptionHtml {
+                chain: Chain::Mainnet,
+                children: Vec::new(),
+                genesis_fee: 1,
+                genesis_height: 0,
+                inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+                inscription_id: inscription_id(1),
+                next: None,
+                number: 1,
+                output: tx_out(1, address()),
+                parent: None,
+                pending: None,
+                previous: None,
+                protocol_id: "ord-v1".into(),
+                sat: None,
+                satpoint: satpoint(1, 0),
+                timestamp: timestamp(0),
+                transfers: Vec::new(),
+                validation_error: Some("property `title` must be a string".into()),
+            },
+            "
+        <h1>Inscription 1</h1>
+        .*
+          <dt>protocol</dt>
+          <dd>ord-v1</dd>
+          <dt>invalid</dt>
+          <dd>property `title` must be a string</dd>
+        .*
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn with_parent_and_children() {
+        assert_regex_match!(
+            InscriptionHtml {
+                chain: Chain::Mainnet,
+                children: vec![inscription_id(2), inscription_id(3)],
+                genesis_fee: 1,
+                genesis_height: 0,
+                inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+                inscription_id: inscription_id(1),
+                next: None,
+                number: 1,
+                output: tx_out(1, address()),
+                parent: Some(inscription_id(4)),
+                pending: None,
+                previous: None,
+                protocol_id: "ord-v0".into(),
+                sat: None,
+                satpoint: satpoint(1, 0),
+                timestamp: timestamp(0),
+                transfers: Vec::new(),
+                validation_error: None,
+            },
+            "
+        <h1>Inscription 1</h1>
+        .*
+          <dt>protocol</dt>
+          <dd>ord-v0</dd>
+          <dt>parent</dt>
+          <dd class=thumbnails><a href=/inscription/4{64}i4><iframe .* src=/preview/4{64}i4></iframe></a></dd>
+          <dt>children</dt>
+          <dd class=thumbnails>
+            <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+            <a href=/inscription/3{64}i3><iframe .* src=/preview/3{64}i3></iframe></a>
+          </dd>
+        .*
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn with_pending_transfer() {
+        assert_regex_match!(
+            InscriptionHtml {
+                chain: Chain::Mainnet,
+                children: Vec::new(),
+                genesis_fee: 1,
+                genesis_height: 0,
+                inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+                inscription_id: inscription_id(1),
+                next: None,
+                number: 1,
+                output: tx_out(1, address()),
+                parent: None,
+                pending: Some(Pending {
+                    kind: PendingKind::Transfer,
+                    satpoint: Some(satpoint(2, 0)),
+                    txid: txid(2),
+                }),
+                previous: None,
+                protocol_id: "ord-v0".into(),
+                sat: None,
+                satpoint: satpoint(1, 0),
+                timestamp: timestamp(0),
+                transfers: Vec::new(),
+                validation_error: None,
+            },
+            "
+        <h1>Inscription 1</h1>
+        .*
+          <dt>offset</dt>
+          <dd>0</dd>
+          <dt>pending transfer</dt>
+          <dd><a class=monospace href=/tx/2{64}>2{64}</a> to 2{64}:2:0</dd>
+        </dl>
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn with_transfers() {
+        assert_regex_match!(
+            InscriptionHtml {
+                chain: Chain::Mainnet,
+                children: Vec::new(),
+                genesis_fee: 1,
+                genesis_height: 0,
+                inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+                inscription_id: inscription_id(1),
+                next: None,
+                number: 1,
+                output: tx_out(1, address()),
+                parent: None,
+                pending: None,
+                previous: None,
+                protocol_id: "ord-v0".into(),
+                sat: None,
+                satpoint: satpoint(3, 0),
+                timestamp: timestamp(0),
+                transfers: vec![
+                    Transfer {
+                        height: 1,
+                        txid: txid(2),
+                        old_satpoint: satpoint(1, 0),
+                        new_satpoint: satpoint(2, 0),
+                        fee_spent: false,
+                    },
+                    Transfer {
+                        height: 2,
+                        txid: txid(3),
+                        old_satpoint: satpoint(2, 0),
+                        new_satpoint: satpoint(3, 0),
+                        fee_spent: true,
+                    },
+                ],
+                validation_error: None,
+            },
+            "
+        <h1>Inscription 1</h1>
+        .*
+        </dl>
+        <h2>2 Transfers</h2>
+        <ol>
+          <li>
+            <a href=/block/1>1</a>
+            <a class=monospace href=/tx/2{64}>2{64}</a>
+            <span class=monospace>1{64}:1:0</span> → <span class=monospace>2{64}:2:0</span>
+          </li>
+          <li>
+            <a href=/block/2>2</a>
+            <a class=monospace href=/tx/3{64}>3{64}</a>
+            <span class=monospace>2{64}:2:0</span> → <span class=monospace>3{64}:3:0</span>
+            spent as fee
+          </li>
+        </ol>
+      "
+            .unindent()
+        );
+    }
 }
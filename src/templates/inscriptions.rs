@@ -5,6 +5,41 @@ pub(crate) struct InscriptionsHtml {
     pub(crate) inscriptions: Vec<InscriptionId>,
     pub(crate) prev: Option<u64>,
     pub(crate) next: Option<u64>,
+    /// The filters of a query, without its cursor, if the inscriptions are
+    /// the results of one.
+    pub(crate) query: Option<String>,
+    pub(crate) cursor: Option<Cursor>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct InscriptionsJson {
+    pub(crate) cursor: Option<Cursor>,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) next: Option<u64>,
+    pub(crate) prev: Option<u64>,
+}
+
+impl InscriptionsHtml {
+    pub(crate) fn to_json(&self) -> InscriptionsJson {
+        InscriptionsJson {
+            cursor: self.cursor,
+            inscriptions: self.inscriptions.clone(),
+            next: self.next,
+            prev: self.prev,
+        }
+    }
+
+    /// The link to the next page of a query's results, if there is one.
+    fn more(&self) -> Option<String> {
+        let query = self.query.as_ref()?;
+        let cursor = self.cursor?;
+
+        Some(if query.is_empty() {
+            format!("/inscriptions?cursor={cursor}")
+        } else {
+            format!("/inscriptions?{query}&cursor={cursor}")
+        })
+    }
 }
 
 impl PageContent for InscriptionsHtml {
@@ -24,6 +59,8 @@ mod tests {
                 inscriptions: vec![inscription_id(1), inscription_id(2)],
                 prev: None,
                 next: None,
+                query: None,
+                cursor: None,
             },
             "
         <h1>Inscriptions</h1>
@@ -47,6 +84,8 @@ mod tests {
                 inscriptions: vec![inscription_id(1), inscription_id(2)],
                 prev: Some(1),
                 next: Some(2),
+                query: None,
+                cursor: None,
             },
             "
         <h1>Inscriptions</h1>
@@ -62,4 +101,51 @@ mod tests {
             .unindent()
         );
     }
+
+    #[test]
+    fn query_results() {
+        let cursor = "AQAAAAAAAAAKAAAAAAAAAAM".parse::<Cursor>().unwrap();
+
+        assert_regex_match!(
+            InscriptionsHtml {
+                inscriptions: vec![inscription_id(1)],
+                prev: None,
+                next: None,
+                query: Some("media=text&order=fee".into()),
+                cursor: Some(cursor),
+            },
+            "
+        <h1>Inscriptions</h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+        </div>
+        <div class=center>
+        <a class=next href=/inscriptions\\?media=text&amp;order=fee&amp;cursor=AQAAAAAAAAAKAAAAAAAAAAM>more</a>
+        </div>
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn last_page_of_query_results() {
+        assert_regex_match!(
+            InscriptionsHtml {
+                inscriptions: Vec::new(),
+                prev: None,
+                next: None,
+                query: Some(String::new()),
+                cursor: None,
+            },
+            "
+        <h1>Inscriptions</h1>
+        <div class=thumbnails>
+        </div>
+        <div class=center>
+        more
+        </div>
+      "
+            .unindent()
+        );
+    }
 }
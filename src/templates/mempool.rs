@@ -0,0 +1,96 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct MempoolHtml {
+    pub(crate) pending: BTreeMap<InscriptionId, Pending>,
+}
+
+impl MempoolHtml {
+    fn with_kind(
+        &self,
+        kind: PendingKind,
+    ) -> impl Iterator<Item = (&InscriptionId, &Pending)> {
+        self.pending
+            .iter()
+            .filter(move |(_inscription_id, pending)| pending.kind == kind)
+    }
+}
+
+impl PageContent for MempoolHtml {
+    fn title(&self) -> String {
+        "Mempool".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inscriptions_and_transfers() {
+        assert_regex_match!(
+            MempoolHtml {
+                pending: vec![
+                    (
+                        inscription_id(1),
+                        Pending {
+                            kind: PendingKind::Inscription,
+                            satpoint: Some(satpoint(1, 0)),
+                            txid: txid(1),
+                        }
+                    ),
+                    (
+                        inscription_id(2),
+                        Pending {
+                            kind: PendingKind::Transfer,
+                            satpoint: Some(satpoint(3, 0)),
+                            txid: txid(3),
+                        }
+                    ),
+                    (
+                        inscription_id(4),
+                        Pending {
+                            kind: PendingKind::Transfer,
+                            satpoint: None,
+                            txid: txid(4),
+                        }
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            },
+            "
+        <h1>Mempool</h1>
+        <h2>Inscriptions</h2>
+        <ul class=monospace>
+          <li>1{64}i1 in <a href=/tx/1{64}>1{64}</a> to 1{64}:1:0</li>
+        </ul>
+        <h2>Transfers</h2>
+        <ul class=monospace>
+          <li><a href=/inscription/2{64}i2>2{64}i2</a> in <a href=/tx/3{64}>3{64}</a> to 3{64}:3:0</li>
+          <li><a href=/inscription/4{64}i4>4{64}i4</a> in <a href=/tx/4{64}>4{64}</a> to fees</li>
+        </ul>
+      "
+            .unindent()
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_regex_match!(
+            MempoolHtml {
+                pending: BTreeMap::new(),
+            },
+            "
+        <h1>Mempool</h1>
+        <h2>Inscriptions</h2>
+        <ul class=monospace>
+        </ul>
+        <h2>Transfers</h2>
+        <ul class=monospace>
+        </ul>
+      "
+            .unindent()
+        );
+    }
+}
@@ -9,6 +9,40 @@ pub(crate) struct OutputHtml {
     pub(crate) inscriptions: Vec<InscriptionId>,
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct OutputJson {
+    pub(crate) address: Option<String>,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) outpoint: OutPoint,
+    pub(crate) sat_ranges: Option<Vec<(u64, u64)>>,
+    pub(crate) script_pubkey: String,
+    pub(crate) spent: Option<bool>,
+    pub(crate) transaction: Txid,
+    pub(crate) value: u64,
+}
+
+impl OutputHtml {
+    pub(crate) fn to_json(&self) -> OutputJson {
+        OutputJson {
+            address: self
+                .chain
+                .address_from_script(&self.output.script_pubkey)
+                .ok()
+                .map(|address| address.to_string()),
+            inscriptions: self.inscriptions.clone(),
+            outpoint: self.outpoint,
+            sat_ranges: match &self.list {
+                Some(List::Unspent(ranges)) => Some(ranges.clone()),
+                _ => None,
+            },
+            script_pubkey: self.output.script_pubkey.asm(),
+            spent: self.list.as_ref().map(|list| matches!(list, List::Spent)),
+            transaction: self.outpoint.txid,
+            value: self.output.value,
+        }
+    }
+}
+
 impl PageContent for OutputHtml {
     fn title(&self) -> String {
         format!("Output {}", self.outpoint)
@@ -136,4 +170,31 @@ mod tests {
             .unindent()
         );
     }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            OutputHtml {
+                inscriptions: vec![inscription_id(1)],
+                outpoint: outpoint(1),
+                list: Some(List::Unspent(vec![(0, 1), (1, 3)])),
+                chain: Chain::Mainnet,
+                output: TxOut {
+                    value: 3,
+                    script_pubkey: Script::new_p2pkh(&PubkeyHash::all_zeros()),
+                },
+            }
+            .to_json(),
+            OutputJson {
+                address: Some("1111111111111111111114oLvT2".into()),
+                inscriptions: vec![inscription_id(1)],
+                outpoint: outpoint(1),
+                sat_ranges: Some(vec![(0, 1), (1, 3)]),
+                script_pubkey: "OP_DUP OP_HASH160 OP_PUSHBYTES_20 0000000000000000000000000000000000000000 OP_EQUALVERIFY OP_CHECKSIG".into(),
+                spent: Some(false),
+                transaction: outpoint(1).txid,
+                value: 3,
+            }
+        );
+    }
 }
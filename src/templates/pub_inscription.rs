@@ -0,0 +1,118 @@
+use super::*;
+
+/// A non-tracked `pub` inscription, which isn't on a sat, and so has no
+/// location, and is numbered in the sequence of `pub` inscriptions.
+#[derive(Boilerplate)]
+pub(crate) struct PubInscriptionHtml {
+    pub(crate) genesis_fee: u64,
+    pub(crate) genesis_height: u64,
+    pub(crate) inscription: Inscription,
+    pub(crate) inscription_id: InscriptionId,
+    pub(crate) next: Option<InscriptionId>,
+    pub(crate) number: u64,
+    pub(crate) previous: Option<InscriptionId>,
+    pub(crate) protocol_id: String,
+    pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) validation_error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct PubInscriptionJson {
+    pub(crate) content_length: Option<usize>,
+    pub(crate) content_type: Option<String>,
+    pub(crate) genesis_fee: u64,
+    pub(crate) genesis_height: u64,
+    pub(crate) inscription_id: InscriptionId,
+    pub(crate) next: Option<InscriptionId>,
+    pub(crate) number: u64,
+    pub(crate) previous: Option<InscriptionId>,
+    pub(crate) protocol_id: String,
+    pub(crate) timestamp: i64,
+    pub(crate) tracking: bool,
+    pub(crate) validation_error: Option<String>,
+}
+
+impl PubInscriptionHtml {
+    pub(crate) fn to_json(&self) -> PubInscriptionJson {
+        PubInscriptionJson {
+            content_length: self.inscription.content_length(),
+            content_type: self.inscription.content_type().map(str::to_string),
+            genesis_fee: self.genesis_fee,
+            genesis_height: self.genesis_height,
+            inscription_id: self.inscription_id,
+            next: self.next,
+            number: self.number,
+            previous: self.previous,
+            protocol_id: self.protocol_id.clone(),
+            timestamp: self.timestamp.timestamp(),
+            tracking: false,
+            validation_error: self.validation_error.clone(),
+        }
+    }
+}
+
+impl PageContent for PubInscriptionHtml {
+    fn title(&self) -> String {
+        format!("Pub Inscription {}", self.number)
+    }
+
+    fn preview_image_url(&self) -> Option<Trusted<String>> {
+        Some(Trusted(format!("/content/{}", self.inscription_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pub_inscription() {
+        assert_regex_match!(
+            PubInscriptionHtml {
+                genesis_fee: 1,
+                genesis_height: 0,
+                inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+                inscription_id: inscription_id(1),
+                next: Some(inscription_id(2)),
+                number: 1,
+                previous: None,
+                protocol_id: "pub-v0".into(),
+                timestamp: timestamp(0),
+                validation_error: None,
+            },
+            "
+        <h1>Pub Inscription 1</h1>
+        <div class=inscription>
+        <div>❮</div>
+        <iframe .* src=/preview/1{64}i1></iframe>
+        <a class=next href=/inscription/2{64}i2>❯</a>
+        </div>
+        <dl>
+          <dt>id</dt>
+          <dd class=monospace>1{64}i1</dd>
+          <dt>protocol</dt>
+          <dd>pub-v0</dd>
+          <dt>tracking</dt>
+          <dd>false</dd>
+          <dt>preview</dt>
+          <dd><a href=/preview/1{64}i1>link</a></dd>
+          <dt>content</dt>
+          <dd><a href=/content/1{64}i1>link</a></dd>
+          <dt>content length</dt>
+          <dd>10 bytes</dd>
+          <dt>content type</dt>
+          <dd>text/plain;charset=utf-8</dd>
+          <dt>timestamp</dt>
+          <dd><time>1970-01-01 00:00:00 UTC</time></dd>
+          <dt>genesis height</dt>
+          <dd><a href=/block/0>0</a></dd>
+          <dt>genesis fee</dt>
+          <dd>1</dd>
+          <dt>genesis transaction</dt>
+          <dd><a class=monospace href=/tx/1{64}>1{64}</a></dd>
+        </dl>
+      "
+            .unindent()
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct PubInscriptionsHtml {
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) prev: Option<u64>,
+    pub(crate) next: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct PubInscriptionsJson {
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) next: Option<u64>,
+    pub(crate) prev: Option<u64>,
+}
+
+impl PubInscriptionsHtml {
+    pub(crate) fn to_json(&self) -> PubInscriptionsJson {
+        PubInscriptionsJson {
+            inscriptions: self.inscriptions.clone(),
+            next: self.next,
+            prev: self.prev,
+        }
+    }
+}
+
+impl PageContent for PubInscriptionsHtml {
+    fn title(&self) -> String {
+        "Pub Inscriptions".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_prev_and_next() {
+        assert_regex_match!(
+            PubInscriptionsHtml {
+                inscriptions: vec![inscription_id(1), inscription_id(2)],
+                prev: Some(1),
+                next: Some(2),
+            },
+            "
+        <h1>Pub Inscriptions</h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        <div class=center>
+        <a class=prev href=/pub/1>prev</a>
+        <a class=next href=/pub/2>next</a>
+        </div>
+      "
+            .unindent()
+        );
+    }
+}
@@ -6,6 +6,23 @@ pub(crate) struct RangeHtml {
     pub(crate) end: Sat,
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct RangeJson {
+    pub(crate) end: u64,
+    pub(crate) start: u64,
+    pub(crate) value: u64,
+}
+
+impl RangeHtml {
+    pub(crate) fn to_json(&self) -> RangeJson {
+        RangeJson {
+            end: self.end.n(),
+            start: self.start.n(),
+            value: self.end.n() - self.start.n(),
+        }
+    }
+}
+
 impl PageContent for RangeHtml {
     fn title(&self) -> String {
         format!("Sat range {}–{}", self.start, self.end)
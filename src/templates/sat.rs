@@ -8,6 +8,49 @@ pub(crate) struct SatHtml {
     pub(crate) inscription: Option<InscriptionId>,
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct SatJson {
+    pub(crate) block: u64,
+    pub(crate) cycle: u64,
+    pub(crate) decimal: String,
+    pub(crate) degree: String,
+    pub(crate) epoch: u64,
+    pub(crate) inscription: Option<InscriptionId>,
+    pub(crate) name: String,
+    pub(crate) next: Option<u64>,
+    pub(crate) number: u64,
+    pub(crate) offset: u64,
+    pub(crate) percentile: String,
+    pub(crate) period: u64,
+    pub(crate) prev: Option<u64>,
+    pub(crate) rarity: Rarity,
+    pub(crate) satpoint: Option<SatPoint>,
+    pub(crate) timestamp: i64,
+}
+
+impl SatHtml {
+    pub(crate) fn to_json(&self) -> SatJson {
+        SatJson {
+            block: self.sat.height().n(),
+            cycle: self.sat.cycle(),
+            decimal: self.sat.decimal().to_string(),
+            degree: self.sat.degree().to_string(),
+            epoch: self.sat.epoch().0,
+            inscription: self.inscription,
+            name: self.sat.name(),
+            next: (self.sat < Sat::LAST).then(|| self.sat.n() + 1),
+            number: self.sat.n(),
+            offset: self.sat.third(),
+            percentile: self.sat.percentile(),
+            period: self.sat.period(),
+            prev: self.sat.n().checked_sub(1),
+            rarity: self.sat.rarity(),
+            satpoint: self.satpoint,
+            timestamp: self.blocktime.timestamp().timestamp(),
+        }
+    }
+}
+
 impl PageContent for SatHtml {
     fn title(&self) -> String {
         format!("Sat {}", self.sat)
@@ -135,4 +178,35 @@ mod tests {
             "<h1>Sat 0</h1>.*<dt>location</dt><dd class=monospace>1{64}:1:0</dd>.*",
         );
     }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            SatHtml {
+                sat: Sat(0),
+                satpoint: None,
+                blocktime: Blocktime::confirmed(0),
+                inscription: Some(inscription_id(1)),
+            }
+            .to_json(),
+            SatJson {
+                block: 0,
+                cycle: 0,
+                decimal: "0.0".into(),
+                degree: "0°0′0″0‴".into(),
+                epoch: 0,
+                inscription: Some(inscription_id(1)),
+                name: "nvtdijuwxlp".into(),
+                next: Some(1),
+                number: 0,
+                offset: 0,
+                percentile: "0%".into(),
+                period: 0,
+                prev: None,
+                rarity: Rarity::Mythic,
+                satpoint: None,
+                timestamp: 0,
+            }
+        );
+    }
 }
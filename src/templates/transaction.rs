@@ -4,7 +4,7 @@ use super::*;
 pub(crate) struct TransactionHtml {
     blockhash: Option<BlockHash>,
     chain: Chain,
-    inscription: Option<InscriptionId>,
+    inscriptions: Vec<InscriptionId>,
     transaction: Transaction,
     txid: Txid,
 }
@@ -13,19 +13,68 @@ impl TransactionHtml {
     pub(crate) fn new(
         transaction: Transaction,
         blockhash: Option<BlockHash>,
-        inscription: Option<InscriptionId>,
+        inscriptions: Vec<InscriptionId>,
         chain: Chain,
     ) -> Self {
         Self {
             txid: transaction.txid(),
             blockhash,
             chain,
-            inscription,
+            inscriptions,
             transaction,
         }
     }
 }
 
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct TransactionJson {
+    pub(crate) blockhash: Option<BlockHash>,
+    pub(crate) inputs: Vec<OutPoint>,
+    pub(crate) inscriptions: Vec<InscriptionId>,
+    pub(crate) outputs: Vec<TransactionOutputJson>,
+    pub(crate) txid: Txid,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct TransactionOutputJson {
+    pub(crate) address: Option<String>,
+    pub(crate) outpoint: OutPoint,
+    pub(crate) script_pubkey: String,
+    pub(crate) value: u64,
+}
+
+impl TransactionHtml {
+    pub(crate) fn to_json(&self) -> TransactionJson {
+        TransactionJson {
+            blockhash: self.blockhash,
+            inputs: self
+                .transaction
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .collect(),
+            inscriptions: self.inscriptions.clone(),
+            outputs: self
+                .transaction
+                .output
+                .iter()
+                .enumerate()
+                .map(|(vout, output)| TransactionOutputJson {
+                    address: self
+                        .chain
+                        .address_from_script(&output.script_pubkey)
+                        .ok()
+                        .map(|address| address.to_string()),
+                    outpoint: OutPoint::new(self.txid, vout.try_into().unwrap()),
+                    script_pubkey: output.script_pubkey.asm(),
+                    value: output.value,
+                })
+                .collect(),
+            txid: self.txid,
+        }
+    }
+}
+
 impl PageContent for TransactionHtml {
     fn title(&self) -> String {
         format!("Transaction {}", self.txid)
@@ -69,7 +118,7 @@ mod tests {
         let txid = transaction.txid();
 
         pretty_assert_eq!(
-      TransactionHtml::new(transaction, None, None, Chain::Mainnet).to_string(),
+      TransactionHtml::new(transaction, None, Vec::new(), Chain::Mainnet).to_string(),
       format!(
         "
         <h1>Transaction <span class=monospace>{txid}</span></h1>
@@ -123,7 +172,7 @@ mod tests {
         };
 
         assert_regex_match!(
-            TransactionHtml::new(transaction, Some(blockhash(0)), None, Chain::Mainnet),
+            TransactionHtml::new(transaction, Some(blockhash(0)), Vec::new(), Chain::Mainnet),
             "
         <h1>Transaction <span class=monospace>[[:xdigit:]]{64}</span></h1>
         <dl>
@@ -0,0 +1,161 @@
+use super::*;
+
+/// A `bid` username. Usernames are 1 to 16 characters of `a-z`, `0-9` and `_`,
+/// but only those of at least `MIN_LENGTH` characters can currently be
+/// registered.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Username(String);
+
+impl Username {
+    pub(crate) const MAX_LENGTH: usize = 16;
+    pub(crate) const MIN_LENGTH: usize = 7;
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the username is reserved for the sunrise period at `height`.
+    pub(crate) fn is_reserved(
+        &self,
+        chain: Chain,
+        height: u64,
+    ) -> bool {
+        lazy_static! {
+            static ref SUNRISE: HashSet<&'static str> = include_str!("username/sunrise.txt")
+                .lines()
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect();
+        }
+
+        height < chain.bid_sunrise_end_height() && SUNRISE.contains(self.as_str())
+    }
+
+    /// The username registered by `inscription`, if it is a `bid` inscription.
+    pub(crate) fn from_inscription(inscription: &Inscription) -> Option<Result<Self>> {
+        let registry = ProtocolRegistry::current();
+        let spec = registry.get(inscription.protocol_id())?;
+
+        if spec.protocol != "bid" {
+            return None;
+        }
+
+        let properties = match inscription.protocol_properties() {
+            Some(properties) => properties,
+            None => return Some(Err(anyhow!("bid inscription has no properties"))),
+        };
+
+        Some(
+            serde_json::from_str::<serde_json::Value>(properties)
+                .context("protocol properties are not valid JSON")
+                .and_then(|properties| {
+                    properties["name"]
+                        .as_str()
+                        .ok_or_else(|| anyhow!("bid inscription has no name"))?
+                        .parse()
+                }),
+        )
+    }
+}
+
+impl FromStr for Username {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            bail!("invalid username `{s}`: only `a-z`, `0-9` and `_` are allowed");
+        }
+
+        let length = s.len();
+
+        if length > Self::MAX_LENGTH {
+            bail!(
+                "invalid username `{s}`: longer than {} characters",
+                Self::MAX_LENGTH
+            );
+        }
+
+        if length < Self::MIN_LENGTH {
+            bail!(
+                "invalid username `{s}`: usernames shorter than {} characters are reserved",
+                Self::MIN_LENGTH
+            );
+        }
+
+        Ok(Self(s.into()))
+    }
+}
+
+impl Display for Username {
+    fn fmt(
+        &self,
+        f: &mut Formatter,
+    ) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Username {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_usernames() {
+        assert_eq!("alice_1".parse::<Username>().unwrap().as_str(), "alice_1");
+        assert_eq!(
+            "abcdefghijklmnop".parse::<Username>().unwrap().as_str(),
+            "abcdefghijklmnop"
+        );
+    }
+
+    #[test]
+    fn invalid_usernames() {
+        assert_eq!(
+            "Alice_1".parse::<Username>().unwrap_err().to_string(),
+            "invalid username `Alice_1`: only `a-z`, `0-9` and `_` are allowed"
+        );
+        assert_eq!(
+            "alice-bob".parse::<Username>().unwrap_err().to_string(),
+            "invalid username `alice-bob`: only `a-z`, `0-9` and `_` are allowed"
+        );
+        assert_eq!(
+            "abcdefghijklmnopq"
+                .parse::<Username>()
+                .unwrap_err()
+                .to_string(),
+            "invalid username `abcdefghijklmnopq`: longer than 16 characters"
+        );
+        assert_eq!(
+            "alice".parse::<Username>().unwrap_err().to_string(),
+            "invalid username `alice`: usernames shorter than 7 characters are reserved"
+        );
+        assert!("".parse::<Username>().is_err());
+    }
+
+    #[test]
+    fn sunrise_reservations() {
+        let username = "youtube".parse::<Username>().unwrap();
+        assert!(username.is_reserved(Chain::Mainnet, 0));
+        assert!(username.is_reserved(Chain::Mainnet, Chain::Mainnet.bid_sunrise_end_height() - 1));
+        assert!(!username.is_reserved(Chain::Mainnet, Chain::Mainnet.bid_sunrise_end_height()));
+        assert!(!username.is_reserved(Chain::Regtest, 0));
+        assert!(!"alice_1"
+            .parse::<Username>()
+            .unwrap()
+            .is_reserved(Chain::Mainnet, 0));
+    }
+}
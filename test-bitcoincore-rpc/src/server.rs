@@ -256,7 +256,9 @@ impl Api for Server {
 
         let mut transaction = Transaction::deserialize(&hex::decode(tx).unwrap()).unwrap();
         for input in &mut transaction.input {
-            input.witness = Witness::from_vec(vec![vec![0; 64]]);
+            if input.witness.is_empty() {
+                input.witness = Witness::from_vec(vec![vec![0; 64]]);
+            }
         }
 
         Ok(serde_json::to_value(SignRawTransactionResult {
@@ -342,6 +344,10 @@ impl Api for Server {
         }
     }
 
+    fn get_raw_mempool(&self) -> Result<Vec<Txid>, jsonrpc_core::Error> {
+        Ok(self.state().mempool.iter().map(|tx| tx.txid()).collect())
+    }
+
     fn get_raw_transaction(
         &self,
         txid: Txid,
@@ -371,7 +377,12 @@ impl Api for Server {
                 None => Err(Self::not_found()),
             }
         } else {
-            match self.state().transactions.get(&txid) {
+            let state = self.state();
+            match state
+                .transactions
+                .get(&txid)
+                .or_else(|| state.mempool.iter().find(|tx| tx.txid() == txid))
+            {
                 Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
                 None => Err(Self::not_found()),
             }
@@ -0,0 +1,63 @@
+use {
+    super::*,
+    arb::subcommand::address::Output,
+};
+
+#[test]
+fn address_lists_outputs_with_inscriptions() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let destination = CommandBuilder::new("wallet receive")
+        .rpc_server(&rpc_server)
+        .output::<arb::subcommand::wallet::receive::Output>()
+        .address;
+
+    let reveal = CommandBuilder::new(format!(
+        "wallet inscribe --destination {destination} degenerate.png --fee-rate 1"
+    ))
+    .write("degenerate.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .output::<Inscribe>()
+    .reveal;
+
+    rpc_server.mine_blocks(1);
+
+    assert_eq!(
+        CommandBuilder::new(format!("--index-addresses address {destination}"))
+            .rpc_server(&rpc_server)
+            .output::<Vec<Output>>(),
+        [Output {
+            outpoint: OutPoint::new(reveal, 0),
+            inscriptions: vec![format!("{reveal}i0").parse().unwrap()],
+            rare_sats: Vec::new(),
+        }]
+    );
+}
+
+#[test]
+fn address_without_address_index() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    CommandBuilder::new("address bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+        .rpc_server(&rpc_server)
+        .expected_stderr(
+            "error: tracking addresses requires index created with `--index-addresses` flag\n",
+        )
+        .expected_exit_code(1)
+        .run();
+}
+
+#[test]
+fn address_must_be_valid_for_chain() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    CommandBuilder::new(
+        "--index-addresses address tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+    )
+    .rpc_server(&rpc_server)
+    .expected_stderr(
+        "error: Address `tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx` is not valid for mainnet\n",
+    )
+    .expected_exit_code(1)
+    .run();
+}
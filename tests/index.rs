@@ -35,3 +35,34 @@ fn re_opening_database_does_not_trigger_schema_check() {
         .rpc_server(&rpc_server)
         .run();
 }
+
+#[test]
+fn index_built_with_different_protocol_specs_is_not_opened() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    rpc_server.mine_blocks(1);
+
+    let tempdir = TempDir::new().unwrap();
+
+    let index_path = tempdir.path().join("foo.redb");
+
+    CommandBuilder::new(format!("--index {} index", index_path.display()))
+        .rpc_server(&rpc_server)
+        .run();
+
+    CommandBuilder::new(format!(
+        "--index {} --protocols-dir . index",
+        index_path.display()
+    ))
+    .write(
+        "foo-v1.0.0.json",
+        r#"{"protocol":"foo","version":"1.0.0","tracking":true}"#,
+    )
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: index at `.*` was built with different protocol specs.*")
+    .expected_exit_code(1)
+    .run();
+
+    CommandBuilder::new(format!("--index {} index", index_path.display()))
+        .rpc_server(&rpc_server)
+        .run();
+}
@@ -0,0 +1,73 @@
+use {
+    super::*,
+    arb::subcommand::inscriptions::QueryOutput,
+};
+
+#[test]
+fn query_inscriptions() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    let first = inscribe(&rpc_server, "foo.txt", false, None).inscription;
+    let second = inscribe(&rpc_server, "foo.txt", false, None).inscription;
+
+    let output = CommandBuilder::new("inscriptions query --content-type text/plain;charset=utf-8")
+        .rpc_server(&rpc_server)
+        .output::<QueryOutput>();
+
+    assert_eq!(
+        output,
+        QueryOutput {
+            inscriptions: vec![second.parse().unwrap(), first.parse().unwrap()],
+            cursor: None,
+        }
+    );
+
+    let output = CommandBuilder::new("inscriptions query --media text --ascending --limit 1")
+        .rpc_server(&rpc_server)
+        .output::<QueryOutput>();
+
+    assert_eq!(output.inscriptions, [first.parse().unwrap()]);
+
+    let output = CommandBuilder::new(format!(
+        "inscriptions query --media text --ascending --limit 1 --cursor {}",
+        output.cursor.unwrap()
+    ))
+    .rpc_server(&rpc_server)
+    .output::<QueryOutput>();
+
+    assert_eq!(
+        output,
+        QueryOutput {
+            inscriptions: vec![second.parse().unwrap()],
+            cursor: None,
+        }
+    );
+
+    let output = CommandBuilder::new("inscriptions query --media image")
+        .rpc_server(&rpc_server)
+        .output::<QueryOutput>();
+
+    assert_eq!(output.inscriptions, []);
+}
+
+#[test]
+fn query_cursors_must_match_order() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    inscribe(&rpc_server, "foo.txt", false, None);
+    inscribe(&rpc_server, "foo.txt", false, None);
+
+    let cursor = CommandBuilder::new("inscriptions query --limit 1")
+        .rpc_server(&rpc_server)
+        .output::<QueryOutput>()
+        .cursor
+        .unwrap();
+
+    CommandBuilder::new(format!("inscriptions query --order fee --cursor {cursor}"))
+        .rpc_server(&rpc_server)
+        .expected_stderr("error: cursor is for inscriptions ordered by number\n")
+        .expected_exit_code(1)
+        .run();
+}
@@ -71,6 +71,10 @@ struct Inscribe {
     fees: u64,
 }
 
+/// Inscribing content that's already inscribed warns, which tests that
+/// inscribe the same file more than once expect.
+const DUPLICATE_CONTENT_WARNING: &str = "(warning: content of .* is already inscribed by .*\n)?";
+
 static INSCRIPTION_DIR: Dir<'_> = include_dir!("tests/inscriptions");
 
 fn inscribe(
@@ -129,11 +133,13 @@ fn inscribe(
             .write(filename, content)
             .write(metadata_filename.unwrap(), value.as_str())
             .rpc_server(rpc_server)
+            .stderr_regex(DUPLICATE_CONTENT_WARNING)
             .output()
     } else {
         CommandBuilder::new(command)
             .write(filename, content)
             .rpc_server(rpc_server)
+            .stderr_regex(DUPLICATE_CONTENT_WARNING)
             .output()
     };
 
@@ -153,6 +159,7 @@ fn create_wallet(rpc_server: &test_bitcoincore_rpc::Handle) {
         .output::<Create>();
 }
 
+mod address;
 mod command_builder;
 mod core;
 mod epochs;
@@ -160,8 +167,10 @@ mod expected;
 mod find;
 mod index;
 mod info;
+mod inscriptions;
 mod list;
 mod parse;
+mod protocols;
 mod server;
 mod subsidy;
 mod supply;
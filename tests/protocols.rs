@@ -0,0 +1,167 @@
+use {
+    super::*,
+    arb::subcommand::protocols::{
+        ListOutput,
+        ValidateOutput,
+    },
+    std::path::PathBuf,
+};
+
+const FOO: &str = r#"{"protocol":"foo","version":"1.2.0","tracking":false,"name":"Foo"}"#;
+
+#[test]
+fn list_bundled_protocols() {
+    assert_eq!(
+        CommandBuilder::new("protocols list").output::<Vec<ListOutput>>(),
+        vec![
+            ListOutput {
+                protocol: "bfs".into(),
+                version: "1.0.0".into(),
+                tracking: true,
+                source: "bundled".into(),
+            },
+            ListOutput {
+                protocol: "bid".into(),
+                version: "1.0.0".into(),
+                tracking: true,
+                source: "bundled".into(),
+            },
+            ListOutput {
+                protocol: "bnw".into(),
+                version: "1.0.0".into(),
+                tracking: true,
+                source: "bundled".into(),
+            },
+            ListOutput {
+                protocol: "ord".into(),
+                version: "1.0.0".into(),
+                tracking: true,
+                source: "bundled".into(),
+            },
+            ListOutput {
+                protocol: "pub".into(),
+                version: "1.0.0".into(),
+                tracking: false,
+                source: "bundled".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn list_includes_protocols_dir() {
+    let output = CommandBuilder::new("--protocols-dir . protocols list")
+        .write("foo-v1.2.0.json", FOO)
+        .output::<Vec<ListOutput>>();
+
+    assert_eq!(
+        output[3],
+        ListOutput {
+            protocol: "foo".into(),
+            version: "1.2.0".into(),
+            tracking: false,
+            source: "./foo-v1.2.0.json".into(),
+        }
+    );
+    assert_eq!(output.len(), 6);
+}
+
+#[test]
+fn missing_protocols_dir_is_an_error() {
+    CommandBuilder::new("--protocols-dir missing protocols list")
+        .expected_stderr("error: protocols dir `missing` does not exist\n")
+        .expected_exit_code(1)
+        .run();
+}
+
+#[test]
+fn show_resolves_protocol_id() {
+    assert_eq!(
+        CommandBuilder::new("--protocols-dir . protocols show foo-v1")
+            .write("foo-v1.2.0.json", FOO)
+            .output::<serde_json::Value>(),
+        serde_json::from_str::<serde_json::Value>(FOO).unwrap()
+    );
+}
+
+#[test]
+fn show_unknown_protocol() {
+    CommandBuilder::new("protocols show foo")
+        .expected_stderr("error: unknown protocol `foo`\n")
+        .expected_exit_code(1)
+        .run();
+}
+
+#[test]
+fn validate_reports_invalid_specs() {
+    CommandBuilder::new("protocols validate foo.json bar.json")
+        .write("foo.json", FOO)
+        .write("bar.json", r#"{"protocol":"bar","version":"1"}"#)
+        .stdout_regex(".*")
+        .expected_stderr("error: 1 of 2 protocol specs are invalid\n")
+        .expected_exit_code(1)
+        .run();
+}
+
+#[test]
+fn validate_output() {
+    assert_eq!(
+        CommandBuilder::new("protocols validate foo.json")
+            .write("foo.json", FOO)
+            .output::<Vec<ValidateOutput>>(),
+        vec![ValidateOutput {
+            path: PathBuf::from("foo.json"),
+            protocol_id: Some("foo-v1.2.0".into()),
+            error: None,
+        }]
+    );
+}
+
+#[test]
+fn inscriptions_violating_constraints_are_marked_invalid() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    let protocols_dir = TempDir::new().unwrap();
+    fs::write(
+        protocols_dir.path().join("foo-v1.0.0.json"),
+        r#"{
+            "protocol": "foo",
+            "version": "1.0.0",
+            "tracking": true,
+            "constraints": {"max_content_size": 4, "required_metadata": ["author"]}
+        }"#,
+    )
+    .unwrap();
+    let protocols_dir = protocols_dir.path().display().to_string();
+
+    let inscribe = |file: &str, content: &str| {
+        rpc_server.mine_blocks(1);
+        let output = CommandBuilder::new(format!(
+            "--protocols-dir {protocols_dir} wallet inscribe --protocol-id foo-v1 \
+            --metadata-file metadata.json --fee-rate 1 {file}"
+        ))
+        .write(file, content)
+        .write("metadata.json", r#"{"author": "bar"}"#)
+        .rpc_server(&rpc_server)
+        .output::<Inscribe>();
+        rpc_server.mine_blocks(1);
+        output.inscription
+    };
+
+    let valid = inscribe("small.txt", "FOO");
+    let invalid = inscribe("big.txt", "FOOBARBAZ");
+
+    let server = TestServer::spawn_with_args(&rpc_server, &["--protocols-dir", &protocols_dir]);
+
+    server.assert_response_regex(
+        format!("/inscription/{invalid}"),
+        ".*<dt>invalid</dt>\\s*<dd>content size of 9 bytes exceeds maximum of 4 bytes</dd>.*",
+    );
+
+    assert!(!server
+        .request(format!("/inscription/{valid}"))
+        .text()
+        .unwrap()
+        .contains("<dt>invalid</dt>"));
+}
@@ -64,6 +64,8 @@ fn inscription_page() {
 <dl>
   <dt>id</dt>
   <dd class=monospace>{inscription}</dd>
+  <dt>protocol</dt>
+  <dd>ord-v0</dd>
   <dt>address</dt>
   <dd class=monospace>bc1.*</dd>
   <dt>output value</dt>
@@ -196,6 +198,98 @@ fn inscription_content() {
     assert_eq!(response.bytes().unwrap(), "FOO");
 }
 
+#[test]
+fn wrapped_inscription_content_and_preview() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    rpc_server.mine_blocks(1);
+
+    let Inscribe { inscription, .. } =
+        CommandBuilder::new("wallet inscribe --protocol-id ord-v1 --fee-rate 1 foo.txt")
+            .write("foo.txt", "FOO")
+            .rpc_server(&rpc_server)
+            .output();
+
+    rpc_server.mine_blocks(1);
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    let response = server.request(format!("/content/{inscription}"));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(response.bytes().unwrap(), "FOO");
+
+    server.assert_response_regex(format!("/preview/{inscription}"), ".*<pre>FOO</pre>.*");
+}
+
+#[test]
+fn offchain_inscription_content_is_fetched_from_mirror() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    rpc_server.mine_blocks(1);
+
+    let Inscribe { inscription, .. } = CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --off-chain --fee-rate 1 foo.txt",
+    )
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .output();
+
+    rpc_server.mine_blocks(1);
+
+    let response =
+        TestServer::spawn_with_args(&rpc_server, &[]).request(format!("/content/{inscription}"));
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.text().unwrap(),
+        format!("inscription {inscription} off-chain content is unavailable")
+    );
+
+    let mirror = TempDir::new().unwrap();
+    let hash = "9520437ce8902eb379a7d8aaa98fc4c94eeb07b6684854868fa6f72bf34b0fd3";
+
+    fs::write(mirror.path().join(hash), "BAR").unwrap();
+
+    let response = TestServer::spawn_with_server_args(
+        &rpc_server,
+        &[],
+        &["--offchain-mirror", mirror.path().to_str().unwrap()],
+    )
+    .request(format!("/content/{inscription}"));
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.text().unwrap(),
+        format!(
+            "inscription {inscription} off-chain content is unverified: no mirror has content \
+            matching hash {hash}"
+        )
+    );
+
+    fs::write(mirror.path().join(hash), "FOO").unwrap();
+
+    let response = TestServer::spawn_with_server_args(
+        &rpc_server,
+        &[],
+        &["--offchain-mirror", mirror.path().to_str().unwrap()],
+    )
+    .request(format!("/content/{inscription}"));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(response.bytes().unwrap(), "FOO");
+}
+
 #[test]
 fn inscription_with_compression() {
     static INSCRIPTION_DIR: Dir<'_> = include_dir!("tests/inscriptions");
@@ -223,7 +317,7 @@ fn inscription_with_compression() {
 
     assert_eq!(
         response.headers().get("content-type").unwrap(),
-        "application/json"
+        "text/plain;charset=utf-8"
     );
 
     assert_eq!(
@@ -262,7 +356,7 @@ fn inscription_with_metadata_gets_compressed() {
 
     assert_eq!(
         response.headers().get("content-type").unwrap(),
-        "application/json"
+        "text/plain;charset=utf-8"
     );
 
     assert_eq!(
@@ -310,7 +404,7 @@ fn inscription_with_protocol_options_gets_compressed() {
 
     assert_eq!(
         response.headers().get("content-type").unwrap(),
-        "application/json"
+        "text/plain;charset=utf-8"
     );
 
     assert_eq!(
@@ -359,7 +453,7 @@ fn inscription_with_compression_and_metadata() {
 
     assert_eq!(
         response.headers().get("content-type").unwrap(),
-        "application/json"
+        "text/plain;charset=utf-8"
     );
 
     assert_eq!(
@@ -500,3 +594,73 @@ fn expected_sat_time_is_rounded() {
         r".*<dt>timestamp</dt><dd><time>.* \d+:\d+:\d+ UTC</time> \(expected\)</dd>.*",
     );
 }
+
+#[test]
+fn events_are_sent_exactly_once() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    let mut inscriptions = vec![inscribe(&rpc_server, "foo.txt", false, None).inscription];
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    let response = server.request("/events?from=0");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut events = std::io::BufRead::lines(std::io::BufReader::new(response))
+        .map(Result::unwrap)
+        .filter_map(|line| {
+            serde_json::from_str::<serde_json::Value>(line.strip_prefix("data:")?).ok()
+        });
+
+    assert_eq!(events.next().unwrap()["inscription_id"], inscriptions[0]);
+
+    for _ in 0..2 {
+        inscriptions.push(inscribe(&rpc_server, "foo.txt", false, None).inscription);
+    }
+
+    assert_eq!(
+        events
+            .by_ref()
+            .take(2)
+            .map(|event| event["inscription_id"].as_str().unwrap().to_string())
+            .collect::<Vec<String>>(),
+        inscriptions[1..]
+    );
+
+    // The next event is that of a new inscription, and not a repeat of an
+    // event already sent
+    let last = inscribe(&rpc_server, "foo.txt", false, None).inscription;
+    assert_eq!(events.next().unwrap()["inscription_id"], last);
+}
+
+#[test]
+fn events_are_sent_over_websocket() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    let first = inscribe(&rpc_server, "foo.txt", false, None).inscription;
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    // Wait for the index to catch up with the chain
+    assert_eq!(server.request("/status").status(), StatusCode::OK);
+
+    let mut url = server.url().join("/events/ws?from=0").unwrap();
+    url.set_scheme("ws").unwrap();
+
+    let (mut socket, _) = tungstenite::connect(url).unwrap();
+
+    let mut next_event = || loop {
+        if let tungstenite::Message::Text(text) = socket.read_message().unwrap() {
+            return serde_json::from_str::<serde_json::Value>(&text).unwrap();
+        }
+    };
+
+    let event = next_event();
+    assert_eq!(event["type"], "inscribed");
+    assert_eq!(event["inscription_id"], first);
+
+    let second = inscribe(&rpc_server, "foo.txt", false, None).inscription;
+    assert_eq!(next_event()["inscription_id"], second);
+}
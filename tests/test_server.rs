@@ -21,6 +21,14 @@ impl TestServer {
     pub(crate) fn spawn_with_args(
         rpc_server: &test_bitcoincore_rpc::Handle,
         args: &[&str],
+    ) -> Self {
+        Self::spawn_with_server_args(rpc_server, args, &[])
+    }
+
+    pub(crate) fn spawn_with_server_args(
+        rpc_server: &test_bitcoincore_rpc::Handle,
+        args: &[&str],
+        server_args: &[&str],
     ) -> Self {
         let tempdir = TempDir::new().unwrap();
         fs::write(tempdir.path().join(".cookie"), "foo:bar").unwrap();
@@ -31,11 +39,12 @@ impl TestServer {
             .port();
 
         let child = Command::new(executable_path("arb")).args(format!(
-      "--rpc-url {} --bitcoin-data-dir {} --data-dir {} {} server --http-port {port} --address 127.0.0.1",
+      "--rpc-url {} --bitcoin-data-dir {} --data-dir {} {} server --http-port {port} --address 127.0.0.1 {}",
       rpc_server.url(),
       tempdir.path().display(),
       tempdir.path().display(),
       args.join(" "),
+      server_args.join(" "),
     ).to_args())
       .env("ARB_INTEGRATION_TEST", "1")
       .current_dir(&tempdir)
@@ -1,6 +1,9 @@
 use super::*;
 
 mod balance;
+mod bfs;
+mod bid;
+mod bnw;
 mod cardinals;
 mod create;
 mod inscribe;
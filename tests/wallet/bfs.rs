@@ -0,0 +1,144 @@
+use {
+    super::*,
+    arb::subcommand::wallet::bfs::Entry,
+};
+
+fn register(rpc_server: &test_bitcoincore_rpc::Handle) {
+    CommandBuilder::new("wallet bid register --fee-rate 1 alice_1")
+        .rpc_server(rpc_server)
+        .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+}
+
+#[test]
+fn put_ls_and_rm() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    register(&rpc_server);
+
+    let Inscribe { inscription, .. } =
+        CommandBuilder::new("wallet bfs put --fee-rate 1 alice_1 /docs/hello.txt hello.txt")
+            .write("hello.txt", "HELLOWORLD")
+            .rpc_server(&rpc_server)
+            .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+
+    let entries = CommandBuilder::new("wallet bfs ls alice_1")
+        .rpc_server(&rpc_server)
+        .output::<Vec<Entry>>();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name, "docs");
+    assert_eq!(entries[0].inscription, None);
+
+    let entries = CommandBuilder::new("wallet bfs ls alice_1 /docs")
+        .rpc_server(&rpc_server)
+        .output::<Vec<Entry>>();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].name, "hello.txt");
+    assert_eq!(
+        entries[0].inscription.map(|id| id.to_string()),
+        Some(inscription.clone())
+    );
+
+    let arb_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    arb_server.assert_response_regex(
+        "/bfs/alice_1",
+        ".*<h1>bfs alice_1/</h1>.*<li><a href=/bfs/alice_1/docs>docs/</a></li>.*",
+    );
+
+    arb_server.assert_response_regex(
+        "/bfs/alice_1/docs/",
+        ".*<li><a href=/bfs/alice_1/docs/hello.txt>hello.txt</a></li>.*",
+    );
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(
+            &arb_server
+                .request("/api/v1/bfs/alice_1/docs")
+                .text()
+                .unwrap()
+        )
+        .unwrap(),
+        serde_json::json!({
+            "bid": "alice_1",
+            "entries": [{ "inscription_id": inscription, "name": "hello.txt" }],
+            "path": "/docs",
+        })
+    );
+
+    let response = arb_server.request("/bfs/alice_1/docs/hello.txt");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(response.text().unwrap(), "HELLOWORLD");
+
+    CommandBuilder::new("wallet bfs rm --fee-rate 1 alice_1 /docs")
+        .rpc_server(&rpc_server)
+        .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+
+    let arb_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    assert_eq!(
+        arb_server.request("/bfs/alice_1/docs/hello.txt").status(),
+        StatusCode::NOT_FOUND
+    );
+
+    assert_eq!(
+        CommandBuilder::new("wallet bfs ls alice_1")
+            .rpc_server(&rpc_server)
+            .output::<Vec<Entry>>()
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn put_requires_registration() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    CommandBuilder::new("wallet bfs put --fee-rate 1 alice_1 /hello.txt hello.txt")
+        .write("hello.txt", "HELLOWORLD")
+        .rpc_server(&rpc_server)
+        .expected_exit_code(1)
+        .expected_stderr("error: bid `alice_1` is not registered\n")
+        .run();
+}
+
+#[test]
+fn rm_missing_path() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    register(&rpc_server);
+
+    CommandBuilder::new("wallet bfs rm --fee-rate 1 alice_1 /hello.txt")
+        .rpc_server(&rpc_server)
+        .expected_exit_code(1)
+        .expected_stderr("error: bfs path `/hello.txt` of bid `alice_1` does not exist\n")
+        .run();
+}
+
+#[test]
+fn invalid_paths_are_rejected() {
+    CommandBuilder::new("wallet bfs ls alice_1 docs")
+        .expected_exit_code(2)
+        .stderr_regex(
+            "error: Invalid value \"docs\" for '<PATH>': invalid bfs path `docs`: must start with \
+            `/`\n.*",
+        )
+        .run();
+}
@@ -0,0 +1,155 @@
+use {
+    super::*,
+    arb::subcommand::wallet::bid::Output,
+};
+
+fn register(
+    rpc_server: &test_bitcoincore_rpc::Handle,
+    name: &str,
+) -> Inscribe {
+    let output = CommandBuilder::new(format!("wallet bid register --fee-rate 1 {name}"))
+        .rpc_server(rpc_server)
+        .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+
+    output
+}
+
+#[test]
+fn register_and_resolve() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Inscribe { inscription, .. } = register(&rpc_server, "alice_1");
+
+    let arb_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    arb_server.assert_response_regex(
+        "/bid/alice_1",
+        format!(
+            ".*<h1>bid alice_1</h1>.*<dt>status</dt>\\s*<dd>active</dd>.*\
+            <dt>registered</dt>\\s*<dd><a href=/block/2>2</a></dd>.*\
+            <dt>expires</dt>\\s*<dd>52502</dd>.*{inscription}.*"
+        ),
+    );
+
+    let response = arb_server.request("/resolve/alice_1");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json: serde_json::Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+    assert_eq!(json["name"], "alice_1");
+    assert_eq!(json["inscription_id"], inscription);
+    assert_eq!(json["registered"], 2);
+    assert_eq!(json["expires"], 52502);
+    assert_eq!(json["active"], true);
+
+    assert_eq!(
+        arb_server.request("/resolve/bob_1234").status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        arb_server.request("/resolve/Bob").status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[test]
+fn register_refuses_registered_names() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    register(&rpc_server, "alice_1");
+
+    CommandBuilder::new("wallet bid register --fee-rate 1 alice_1")
+        .rpc_server(&rpc_server)
+        .expected_exit_code(1)
+        .expected_stderr("error: bid `alice_1` is already registered\n")
+        .run();
+}
+
+#[test]
+fn register_refuses_reserved_names() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    CommandBuilder::new("wallet bid register --fee-rate 1 youtube")
+        .rpc_server(&rpc_server)
+        .expected_exit_code(1)
+        .expected_stderr("error: bid `youtube` is reserved until height 840000\n")
+        .run();
+}
+
+#[test]
+fn register_refuses_invalid_names() {
+    CommandBuilder::new("wallet bid register --fee-rate 1 alice")
+        .expected_exit_code(2)
+        .stderr_regex(
+            "error: Invalid value \"alice\" for '<NAME>': invalid username `alice`: usernames \
+            shorter than 7 characters are reserved\n.*",
+        )
+        .run();
+}
+
+#[test]
+fn renew_extends_registration() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    register(&rpc_server, "alice_1");
+
+    rpc_server.mine_blocks(10);
+
+    CommandBuilder::new("wallet bid renew --fee-rate 1 alice_1")
+        .rpc_server(&rpc_server)
+        .output::<Output>();
+
+    rpc_server.mine_blocks(1);
+
+    TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+        "/bid/alice_1",
+        ".*<dt>registered</dt>\\s*<dd><a href=/block/2>2</a></dd>.*\
+        <dt>expires</dt>\\s*<dd>52513</dd>.*",
+    );
+}
+
+#[test]
+fn transfer_sends_registration() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    register(&rpc_server, "alice_1");
+
+    let Output { transaction } = CommandBuilder::new(
+        "wallet bid transfer --fee-rate 1 alice_1 bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv",
+    )
+    .rpc_server(&rpc_server)
+    .output::<Output>();
+
+    rpc_server.mine_blocks(1);
+
+    TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+        "/bid/alice_1",
+        format!(
+            ".*<dt>location</dt>\\s*<dd class=monospace>{transaction}:0:0</dd>.*\
+            <dt>owner</dt>\\s*<dd class=monospace>bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv</dd>.*"
+        ),
+    );
+}
+
+#[test]
+fn renew_unregistered_name() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    CommandBuilder::new("wallet bid renew --fee-rate 1 alice_1")
+        .rpc_server(&rpc_server)
+        .expected_exit_code(1)
+        .expected_stderr("error: bid `alice_1` is not registered\n")
+        .run();
+}
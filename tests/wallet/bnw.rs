@@ -0,0 +1,120 @@
+use super::*;
+
+#[test]
+fn wall_shows_held_inscriptions() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    CommandBuilder::new("wallet bid register --fee-rate 1 alice_1")
+        .rpc_server(&rpc_server)
+        .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+
+    let Inscribe { inscription, .. } = inscribe(&rpc_server, "foo.txt", false, None);
+
+    CommandBuilder::new(format!(
+        "wallet bnw --fee-rate 1 --description favorites alice_1 main {inscription}"
+    ))
+    .rpc_server(&rpc_server)
+    .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+
+    let arb_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    arb_server.assert_response_regex(
+        "/bnw/alice_1",
+        format!(
+            ".*<h1>bnw alice_1</h1>.*<h2><a href=/bnw/alice_1/main>main</a></h2>\\s*<p>favorites</p>\\s*\
+            <div class=thumbnails>\\s*<a href=/inscription/{inscription}>.*"
+        ),
+    );
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(
+            &arb_server
+                .request("/api/v1/bnw/alice_1/main")
+                .text()
+                .unwrap()
+        )
+        .unwrap(),
+        serde_json::json!({
+            "bid": "alice_1",
+            "walls": [{
+                "description": "favorites",
+                "inscriptions": [inscription],
+                "name": "main",
+            }],
+        })
+    );
+
+    CommandBuilder::new(format!(
+        "wallet send --fee-rate 1 bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv {inscription}"
+    ))
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*")
+    .run();
+
+    rpc_server.mine_blocks(1);
+
+    TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+        "/bnw/alice_1/main",
+        ".*<h2><a href=/bnw/alice_1/main>main</a></h2>\\s*<p>favorites</p>\\s*\
+        <div class=thumbnails>\\s*</div>.*",
+    );
+}
+
+#[test]
+fn wall_requires_owned_inscriptions() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    CommandBuilder::new("wallet bid register --fee-rate 1 alice_1")
+        .rpc_server(&rpc_server)
+        .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+
+    CommandBuilder::new(
+        "wallet bnw --fee-rate 1 alice_1 main \
+        0000000000000000000000000000000000000000000000000000000000000000i0",
+    )
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(
+        "error: Inscription 0000000000000000000000000000000000000000000000000000000000000000i0 \
+        not found\n",
+    )
+    .run();
+}
+
+#[test]
+fn missing_walls_are_not_found() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    CommandBuilder::new("wallet bid register --fee-rate 1 alice_1")
+        .rpc_server(&rpc_server)
+        .output::<Inscribe>();
+
+    rpc_server.mine_blocks(1);
+
+    let arb_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    assert_eq!(
+        arb_server.request("/bnw/alice_1/main").status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        arb_server.request("/bnw/bob_1234").status(),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        arb_server.request("/bnw/alice_1/Main").status(),
+        StatusCode::BAD_REQUEST
+    );
+}
@@ -402,3 +402,510 @@ fn inscribe_with_no_limit() {
         .write("degenerate.png", four_megger)
         .rpc_server(&rpc_server);
 }
+
+#[test]
+fn inscribe_with_properties_file() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    rpc_server.mine_blocks(1);
+
+    create_wallet(&rpc_server);
+
+    CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --properties-file props.json --fee-rate 1 foo.txt",
+    )
+    .write("foo.txt", "FOO")
+    .write("props.json", r#"{"title": "Foo", "license": "MIT"}"#)
+    .rpc_server(&rpc_server)
+    .output::<Inscribe>();
+}
+
+#[test]
+fn inscribe_rejects_unknown_properties() {
+    CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --properties-file props.json --fee-rate 1 foo.txt",
+    )
+    .write("foo.txt", "FOO")
+    .write("props.json", r#"{"color": "red"}"#)
+    .expected_exit_code(1)
+    .expected_stderr("error: unknown property `color` for protocol `ord-v1.0.0`\n")
+    .run();
+}
+
+#[test]
+fn inscribe_rejects_mistyped_properties() {
+    CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --properties-file props.json --fee-rate 1 foo.txt",
+    )
+    .write("foo.txt", "FOO")
+    .write("props.json", r#"{"title": 5}"#)
+    .expected_exit_code(1)
+    .expected_stderr(
+        "error: invalid properties for protocol `ord-v1.0.0`\nbecause: property `title` must be a string\n",
+    )
+    .run();
+}
+
+#[test]
+fn inscribe_rejects_reserved_properties() {
+    CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --properties-file props.json --fee-rate 1 foo.txt",
+    )
+    .write("foo.txt", "FOO")
+    .write("props.json", r#"{"tracking": false}"#)
+    .expected_exit_code(1)
+    .expected_stderr("error: property `tracking` is reserved\n")
+    .run();
+}
+
+#[derive(Deserialize)]
+struct Multipart {
+    inscription: String,
+    parts: Vec<Inscribe>,
+}
+
+#[test]
+fn multipart_inscriptions_are_reassembled() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Multipart { inscription, parts } = CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --multipart --part-size 4 --fee-rate 1 hello.txt",
+    )
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .output();
+
+    assert_eq!(parts.len(), 3);
+
+    rpc_server.mine_blocks(1);
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    let response = server.request(format!("/content/{inscription}"));
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(response.text().unwrap(), "HELLOWORLD");
+
+    server.assert_response_regex(format!("/content/{}", parts[1].inscription), "OWOR");
+}
+
+#[test]
+fn compressed_multipart_inscriptions_are_reassembled() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Multipart { inscription, parts } = CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --compression --multipart --part-size 4 --fee-rate 1 \
+        hello.txt",
+    )
+    .write("hello.txt", "A".repeat(2000))
+    .rpc_server(&rpc_server)
+    .output();
+
+    assert!(parts.len() > 1);
+
+    rpc_server.mine_blocks(1);
+
+    TestServer::spawn_with_args(&rpc_server, &[])
+        .assert_response_regex(format!("/content/{inscription}"), "A{2000}");
+}
+
+#[test]
+fn inscriptions_may_be_compressed_with_other_codecs() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let mut inscriptions = Vec::new();
+
+    for codec in ["deflate", "gzip", "zstd", "auto"] {
+        let Inscribe { inscription, .. } = CommandBuilder::new(format!(
+            "wallet inscribe --protocol-id ord-v1 --compression={codec} --fee-rate 1 hello.txt"
+        ))
+        .write("hello.txt", codec.repeat(500))
+        .rpc_server(&rpc_server)
+        .output();
+
+        rpc_server.mine_blocks(1);
+
+        inscriptions.push((codec, inscription));
+    }
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    for (codec, inscription) in inscriptions {
+        server.assert_response_regex(
+            format!("/content/{inscription}"),
+            format!("({codec}){{500}}"),
+        );
+    }
+}
+
+#[test]
+fn unknown_compression_codecs_are_rejected() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+
+    CommandBuilder::new("wallet inscribe --compression=lzma --fee-rate 1 hello.txt")
+        .write("hello.txt", "HELLOWORLD")
+        .rpc_server(&rpc_server)
+        .expected_exit_code(2)
+        .stderr_regex("(?s)error: \"lzma\" isn't a valid value for '--compression.*")
+        .run();
+}
+
+#[test]
+fn inscriptions_may_be_compressed_with_a_dictionary() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let dictionary = "HELLO WORLD ".repeat(100);
+
+    let Inscribe {
+        inscription: dictionary_id,
+        ..
+    } = CommandBuilder::new("wallet inscribe --fee-rate 1 dictionary.txt")
+        .write("dictionary.txt", &dictionary)
+        .rpc_server(&rpc_server)
+        .output();
+
+    rpc_server.mine_blocks(1);
+
+    let Inscribe { inscription, .. } = CommandBuilder::new(format!(
+    "wallet inscribe --protocol-id ord-v1 --compression-dictionary {dictionary_id} --fee-rate 1 \
+    hello.txt"
+  ))
+    .write("hello.txt", &dictionary[..600])
+    .rpc_server(&rpc_server)
+    .output();
+
+    rpc_server.mine_blocks(1);
+
+    let response =
+        TestServer::spawn_with_args(&rpc_server, &[]).request(format!("/content/{inscription}"));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(response.text().unwrap(), &dictionary[..600]);
+}
+
+#[test]
+fn inscriptions_may_be_wrapped_in_binary_envelopes() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Inscribe { inscription, .. } = CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --binary-envelope --compression=zstd --fee-rate 1 \
+        hello.txt",
+    )
+    .write("hello.txt", "HELLO".repeat(100))
+    .rpc_server(&rpc_server)
+    .output();
+
+    rpc_server.mine_blocks(1);
+
+    let response =
+        TestServer::spawn_with_args(&rpc_server, &[]).request(format!("/content/{inscription}"));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain;charset=utf-8"
+    );
+    assert_eq!(response.text().unwrap(), "HELLO".repeat(100));
+}
+
+#[test]
+fn binary_envelopes_require_a_wrapping_protocol() {
+    CommandBuilder::new("wallet inscribe --binary-envelope --fee-rate 1 hello.txt")
+        .write("hello.txt", "HELLOWORLD")
+        .expected_exit_code(1)
+        .expected_stderr(
+            "error: Binary envelopes must use a protocol other than `ord-v0`, like `ord-v1`!\n",
+        )
+        .run();
+}
+
+#[test]
+fn batch_inscriptions_are_revealed_in_one_transaction() {
+    #[derive(Deserialize)]
+    struct Batch {
+        reveal: Txid,
+        inscriptions: Vec<String>,
+    }
+
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Batch {
+        reveal,
+        inscriptions,
+    } = CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml")
+        .write(
+            "batch.yaml",
+            "inscriptions:\n- file: foo.txt\n  title: Foo\n- file: bar.txt\n- file: baz.txt\n",
+        )
+        .write("foo.txt", "FOO")
+        .write("bar.txt", "BAR")
+        .write("baz.txt", "BAZ")
+        .rpc_server(&rpc_server)
+        .output();
+
+    assert_eq!(
+        inscriptions,
+        [
+            format!("{reveal}i0"),
+            format!("{reveal}i1"),
+            format!("{reveal}i2")
+        ]
+    );
+
+    rpc_server.mine_blocks(1);
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    for (vout, (inscription, content)) in inscriptions.iter().zip(["FOO", "BAR", "BAZ"]).enumerate()
+    {
+        server.assert_response_regex(format!("/content/{inscription}"), content);
+        server.assert_response_regex(
+            format!("/inscription/{inscription}"),
+            format!(".*<dt>location</dt>\\s*<dd class=monospace>{reveal}:{vout}:0</dd>.*"),
+        );
+    }
+}
+
+#[test]
+fn batch_files_must_list_inscriptions() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+
+    CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml")
+        .write("batch.yaml", "inscriptions: []\n")
+        .rpc_server(&rpc_server)
+        .expected_exit_code(1)
+        .expected_stderr("error: Batch file batch.yaml contains no inscriptions!\n")
+        .run();
+}
+
+#[test]
+fn inscriptions_may_declare_a_parent() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Inscribe {
+        inscription: parent,
+        ..
+    } = CommandBuilder::new("wallet inscribe --fee-rate 1 parent.txt")
+        .write("parent.txt", "PARENT")
+        .rpc_server(&rpc_server)
+        .output();
+
+    rpc_server.mine_blocks(1);
+
+    let Inscribe {
+        inscription: child,
+        reveal,
+        ..
+    } = CommandBuilder::new(format!(
+        "wallet inscribe --fee-rate 1 --parent {parent} child.txt"
+    ))
+    .write("child.txt", "CHILD")
+    .rpc_server(&rpc_server)
+    .output();
+
+    rpc_server.mine_blocks(1);
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    server.assert_response_regex(
+        format!("/inscription/{child}"),
+        format!(".*<dt>parent</dt>\\s*<dd class=thumbnails><a href=/inscription/{parent}>.*"),
+    );
+
+    server.assert_response_regex(
+        format!("/inscription/{parent}"),
+        format!(
+            ".*<dt>children</dt>\\s*<dd class=thumbnails>\\s*<a href=/inscription/{child}>.*\
+            <dt>location</dt>\\s*<dd class=monospace>{reveal}:1:0</dd>.*"
+        ),
+    );
+}
+
+#[test]
+fn multipart_inscriptions_cannot_declare_a_parent() {
+    CommandBuilder::new(format!(
+        "wallet inscribe --fee-rate 1 --protocol-id ord-v1 --multipart --parent {} foo.txt",
+        "0".repeat(64) + "i0"
+    ))
+    .write("foo.txt", "FOO")
+    .expected_exit_code(2)
+    .stderr_regex("(?s)error: The argument '--multipart' cannot be used with '--parent <PARENT>'.*")
+    .run();
+}
+
+#[test]
+fn multipart_inscriptions_may_exceed_chain_limit() {
+    let rpc_server = test_bitcoincore_rpc::builder()
+        .network(Network::Signet)
+        .build();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Multipart { parts, .. } = CommandBuilder::new(
+        "--chain signet wallet inscribe --protocol-id ord-v1 --multipart --fee-rate 1 \
+        degenerate.png",
+    )
+    .write("degenerate.png", [1; 1025])
+    .rpc_server(&rpc_server)
+    .output();
+
+    assert_eq!(parts.len(), 2);
+
+    CommandBuilder::new(
+        "--chain signet wallet inscribe --protocol-id ord-v1 --multipart --part-size 1025 \
+        --fee-rate 1 degenerate.png",
+    )
+    .write("degenerate.png", [1; 1025])
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(
+        "error: Part size of 1025 bytes exceeds 1024 byte limit for signet inscriptions!\n",
+    )
+    .run();
+}
+
+#[test]
+fn multipart_inscriptions_require_a_wrapping_protocol() {
+    CommandBuilder::new("wallet inscribe --multipart --fee-rate 1 hello.txt")
+        .write("hello.txt", "HELLOWORLD")
+        .expected_exit_code(1)
+        .expected_stderr(
+            "error: Multipart inscriptions must use a protocol other than `ord-v0`, like \
+            `ord-v1`!\n",
+        )
+        .run();
+}
+
+#[test]
+fn inscribing_duplicate_content_warns() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    let Inscribe { inscription, .. } = inscribe(&rpc_server, "foo.txt", false, None);
+
+    CommandBuilder::new("wallet inscribe --fee-rate 1 foo.txt")
+        .write("foo.txt", "FOO")
+        .rpc_server(&rpc_server)
+        .expected_stderr(format!(
+            "warning: content of foo.txt is already inscribed by {inscription}, use `--dedup` to \
+            inscribe a reference to it instead\n"
+        ))
+        .output::<Inscribe>();
+}
+
+#[test]
+fn dedup_inscribes_a_reference_to_existing_content() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    inscribe(&rpc_server, "foo.txt", false, None);
+
+    let Inscribe { inscription, .. } =
+        CommandBuilder::new("wallet inscribe --protocol-id ord-v1 --dedup --fee-rate 1 foo.txt")
+            .write("foo.txt", "FOO")
+            .rpc_server(&rpc_server)
+            .output();
+
+    rpc_server.mine_blocks(1);
+
+    let server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+    server.assert_response_regex(format!("/content/{inscription}"), "FOO");
+    server.assert_response_regex(
+        "/content/sha256/9520437ce8902eb379a7d8aaa98fc4c94eeb07b6684854868fa6f72bf34b0fd3",
+        "FOO",
+    );
+}
+
+#[test]
+fn dedup_requires_a_wrapping_protocol() {
+    CommandBuilder::new("wallet inscribe --dedup --fee-rate 1 foo.txt")
+        .write("foo.txt", "FOO")
+        .expected_exit_code(1)
+        .expected_stderr(
+            "error: Deduplicated inscriptions must use a protocol other than `ord-v0`, like \
+            `ord-v1`!\n",
+        )
+        .run();
+}
+
+#[test]
+fn offchain_inscriptions_reject_invalid_torrent_trackers() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+
+    CommandBuilder::new(
+        "wallet inscribe --protocol-id ord-v1 --off-chain --torrent-version hybrid \
+        --torrent-tracker wss://tracker.example.com --torrent-tracker ftp://example.com \
+        --fee-rate 1 foo.txt",
+    )
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(
+        "error: Torrent tracker `ftp://example.com` must be a udp, http(s) or ws(s) URL!\n",
+    )
+    .run();
+}
+
+#[test]
+fn offchain_directories_are_inscribed_with_a_file_manifest() {
+    let rpc_server = test_bitcoincore_rpc::spawn();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    let Inscribe { inscription, .. } =
+        CommandBuilder::new("wallet inscribe --protocol-id ord-v1 --off-chain --fee-rate 1 assets")
+            .write("assets/big.bin", [0; 2048])
+            .write("assets/text/foo.txt", "FOO")
+            .rpc_server(&rpc_server)
+            .output();
+
+    rpc_server.mine_blocks(1);
+
+    TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+        format!("/content/{inscription}"),
+        format!(
+            r#".*"files":\[\{{"path":"big.bin","length":2048,"content_hash":"{}"}},\{{"path":"text/foo.txt","length":3,"content_hash":"{}"}}\]}}"#,
+            "e5a00aa9991ac8a5ee3109844d84a55583bd20572ad3ffcd42792f3c36b183ad",
+            "9520437ce8902eb379a7d8aaa98fc4c94eeb07b6684854868fa6f72bf34b0fd3",
+        ),
+    );
+}
+
+#[test]
+fn offchain_inscriptions_may_exceed_chain_limit() {
+    let rpc_server = test_bitcoincore_rpc::builder()
+        .network(Network::Signet)
+        .build();
+    create_wallet(&rpc_server);
+    rpc_server.mine_blocks(1);
+
+    CommandBuilder::new(
+        "--chain signet wallet inscribe --protocol-id ord-v1 --off-chain --fee-rate 1 \
+        degenerate.png",
+    )
+    .write("degenerate.png", [1; 1025])
+    .rpc_server(&rpc_server)
+    .output::<Inscribe>();
+}